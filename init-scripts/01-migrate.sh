@@ -0,0 +1,47 @@
+#!/bin/sh
+# PostgreSQL初期化スクリプト（マイグレーション適用）
+# このファイルはPostgreSQLコンテナ起動時に自動実行されます
+#
+# アプリと同じ src-tauri/migrations のファイルを順番に適用し、
+# schema_migrations に記録します（チェックサムはアプリと同じ SHA-256）。
+# そのため、アプリ起動時に同じマイグレーションが二重に適用されることはありません。
+set -e
+
+MIGRATIONS_DIR="${MIGRATIONS_DIR:-/migrations}"
+
+run_psql() {
+    psql -v ON_ERROR_STOP=1 --username "$POSTGRES_USER" --dbname "$POSTGRES_DB" "$@"
+}
+
+# データベースの設定
+run_psql -c "ALTER DATABASE \"$POSTGRES_DB\" SET timezone = 'UTC';"
+
+run_psql <<'SQL'
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version BIGINT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    checksum CHAR(64) NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+SQL
+
+for file in "$MIGRATIONS_DIR"/*.sql; do
+    base=$(basename "$file" .sql)
+    version=$(echo "${base%%_*}" | sed 's/^0*//')
+    name="${base#*_}"
+    checksum=$(sha256sum "$file" | cut -d ' ' -f 1)
+
+    # ファイル名から取った値は SQL に埋め込まず psql の変数で渡す（-c では変数が展開されないため標準入力から読ませる）
+    applied=$(echo "SELECT 1 FROM schema_migrations WHERE version = :'version'" |
+        run_psql -tA -v version="$version" -f -)
+    if [ "$applied" = "1" ]; then
+        continue
+    fi
+
+    echo "Applying migration $base"
+    echo "INSERT INTO schema_migrations (version, name, checksum) VALUES (:'version', :'name', :'checksum')" |
+        run_psql --single-transaction \
+            -v version="$version" -v name="$name" -v checksum="$checksum" \
+            -f "$file" \
+            -f -
+done
@@ -0,0 +1,82 @@
+-- サンプルデータ
+-- このファイルはPostgreSQLコンテナ起動時に 01-migrate.sh の後で自動実行されます
+
+-- パスワードはすべて "password"（開発用。argon2id のハッシュ）
+INSERT INTO users (name, email, password_hash, role) VALUES 
+    ('Alice Johnson', 'alice@example.com', '$argon2id$v=19$m=19456,t=2,p=1$kmXC/5YSVmQjgIJ+wMsWWA$h/z2oeWw0nCvx8CNcqPrI/t1kHJoYZBTWdh7zolutAI', 'admin'),
+    ('Bob Smith', 'bob@example.com', '$argon2id$v=19$m=19456,t=2,p=1$kmXC/5YSVmQjgIJ+wMsWWA$h/z2oeWw0nCvx8CNcqPrI/t1kHJoYZBTWdh7zolutAI', 'manager'),
+    ('Carol Davis', 'carol@example.com', '$argon2id$v=19$m=19456,t=2,p=1$kmXC/5YSVmQjgIJ+wMsWWA$h/z2oeWw0nCvx8CNcqPrI/t1kHJoYZBTWdh7zolutAI', 'clerk')
+ON CONFLICT (email) DO NOTHING;
+
+-- 商品カテゴリ（Computers の下に Accessories と Displays）
+INSERT INTO product_categories (name) VALUES ('Computers')
+ON CONFLICT DO NOTHING;
+
+INSERT INTO product_categories (parent_id, name)
+SELECT c.id, sub.name
+FROM product_categories c
+CROSS JOIN (VALUES ('Accessories'), ('Displays')) AS sub(name)
+WHERE c.name = 'Computers' AND c.parent_id IS NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO products (name, sku, barcode, description, category_id, price, reorder_point)
+SELECT p.name, p.sku, p.barcode, p.description, c.id, p.price, p.reorder_point
+FROM (VALUES
+    ('Laptop Pro', 'LAP-PRO-001', '4900000000016', 'High-performance laptop for professionals', 'Computers', 1299.99, 10),
+    ('Wireless Mouse', 'ACC-MOU-001', '4900000000023', 'Ergonomic wireless mouse', 'Accessories', 29.99, 40),
+    ('Mechanical Keyboard', 'ACC-KEY-001', '4900000000030', 'RGB mechanical keyboard', 'Accessories', 149.99, 15),
+    ('Monitor 4K', 'DSP-4K-027', '4900000000047', '27-inch 4K monitor', 'Displays', 399.99, 5)
+) AS p(name, sku, barcode, description, category, price, reorder_point)
+LEFT JOIN product_categories c ON c.name = p.category
+ON CONFLICT DO NOTHING;
+
+-- 2つ目の保管場所（既定の "Main warehouse" はマイグレーションで作成される）
+INSERT INTO warehouses (name, address) VALUES ('Osaka warehouse', '大阪府大阪市住之江区南港北1-2-3')
+ON CONFLICT DO NOTHING;
+
+-- 最初の在庫は保管場所ごとに入庫として記録する（products.stock と stock_levels はトリガーで反映される）
+INSERT INTO stock_movements (product_id, warehouse_id, movement_type, quantity, reason)
+SELECT p.id, w.id, 'receipt', s.quantity, 'Initial stock'
+FROM products p
+JOIN (VALUES
+    ('LAP-PRO-001', 'Main warehouse', 50),
+    ('ACC-MOU-001', 'Main warehouse', 150),
+    ('ACC-MOU-001', 'Osaka warehouse', 50),
+    ('ACC-KEY-001', 'Main warehouse', 75),
+    ('DSP-4K-027', 'Main warehouse', 20),
+    ('DSP-4K-027', 'Osaka warehouse', 10)
+) AS s(sku, warehouse, quantity) ON s.sku = p.sku
+JOIN warehouses w ON w.name = s.warehouse
+WHERE NOT EXISTS (SELECT 1 FROM stock_movements m WHERE m.product_id = p.id);
+
+-- サンプル取引先を挿入
+INSERT INTO clients (company_name, contact_name, email, phone, payment_terms) VALUES
+    ('株式会社サンプル商事', '佐藤 花子', 'sato@sample-shoji.example.jp', '03-1234-5678', 'end_of_next_month'),
+    ('Example Trading Co.', 'John Miller', 'john@example-trading.example.com', '+1-555-0100', 'prepaid')
+ON CONFLICT DO NOTHING;
+
+-- 取引先の住所（本社を既定の請求先・配送先にする）
+INSERT INTO client_addresses (client_id, label, postal_code, region, city, line1, line2, country)
+SELECT c.id, a.label, a.postal_code, a.region, a.city, a.line1, a.line2, a.country
+FROM clients c
+JOIN (VALUES
+    ('株式会社サンプル商事', '本社', '100-0005', '東京都', '千代田区', '丸の内1-1-1', 'サンプルビル5F', 'JP'),
+    ('株式会社サンプル商事', '大阪倉庫', '559-0034', '大阪府', '大阪市住之江区', '南港北1-2-3', NULL, 'JP'),
+    ('Example Trading Co.', 'Head office', '94105', 'CA', 'San Francisco', '1 Market St', 'Suite 100', 'US')
+) AS a(company_name, label, postal_code, region, city, line1, line2, country)
+    ON a.company_name = c.company_name;
+
+UPDATE clients c
+SET default_billing_address_id = a.id,
+    default_shipping_address_id = a.id
+FROM client_addresses a
+WHERE a.client_id = c.id
+  AND (c.company_name, a.label) IN (('株式会社サンプル商事', '本社'), ('Example Trading Co.', 'Head office'))
+  AND c.default_billing_address_id IS NULL;
+
+-- 完了メッセージ
+DO $$
+BEGIN
+    RAISE NOTICE 'Database initialization completed successfully!';
+    RAISE NOTICE 'Sample data has been inserted.';
+END $$;
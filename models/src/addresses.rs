@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::query::double_option;
+
+// 日本の国コード（郵便番号の形式を検証する）
+pub const COUNTRY_JP: &str = "JP";
+
+// 都道府県（JP の住所の region はこのいずれか）
+pub const JP_PREFECTURES: [&str; 47] = [
+    "北海道", "青森県", "岩手県", "宮城県", "秋田県", "山形県", "福島県",
+    "茨城県", "栃木県", "群馬県", "埼玉県", "千葉県", "東京都", "神奈川県",
+    "新潟県", "富山県", "石川県", "福井県", "山梨県", "長野県", "岐阜県",
+    "静岡県", "愛知県", "三重県", "滋賀県", "京都府", "大阪府", "兵庫県",
+    "奈良県", "和歌山県", "鳥取県", "島根県", "岡山県", "広島県", "山口県",
+    "徳島県", "香川県", "愛媛県", "高知県", "福岡県", "佐賀県", "長崎県",
+    "熊本県", "大分県", "宮崎県", "鹿児島県", "沖縄県",
+];
+
+fn default_country() -> String {
+    COUNTRY_JP.to_string()
+}
+
+// 住所の内容（取引先の住所と、注文に保存する住所のスナップショットで共通）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostalAddress {
+    // 宛名（部署名・担当者名など）
+    pub recipient: Option<String>,
+    // JP は 123-4567 の形式に揃えて保存する
+    pub postal_code: String,
+    // 都道府県・州
+    pub region: String,
+    // 市区町村
+    pub city: String,
+    // 町名・番地
+    pub line1: String,
+    // 建物名・部屋番号
+    pub line2: Option<String>,
+    // ISO 3166-1 alpha-2 の国コード
+    #[serde(default = "default_country")]
+    pub country: String,
+}
+
+impl Default for PostalAddress {
+    fn default() -> Self {
+        PostalAddress {
+            recipient: None,
+            postal_code: String::new(),
+            region: String::new(),
+            city: String::new(),
+            line1: String::new(),
+            line2: None,
+            country: default_country(),
+        }
+    }
+}
+
+// 1行で表示する（JP は「〒123-4567 東京都千代田区丸の内1-1-1」の順）
+impl fmt::Display for PostalAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.country == COUNTRY_JP {
+            write!(f, "〒{} {}{}{}", self.postal_code, self.region, self.city, self.line1)?;
+            if let Some(line2) = &self.line2 {
+                write!(f, " {}", line2)?;
+            }
+        } else {
+            write!(f, "{}", self.line1)?;
+            if let Some(line2) = &self.line2 {
+                write!(f, ", {}", line2)?;
+            }
+            write!(f, ", {}, {} {}, {}", self.city, self.region, self.postal_code, self.country)?;
+        }
+        if let Some(recipient) = &self.recipient {
+            write!(f, " ({})", recipient)?;
+        }
+        Ok(())
+    }
+}
+
+// 取引先の住所（1つの取引先に複数登録でき、既定の請求先・配送先を clients で指定する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    // 本社・倉庫などの呼び名
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub address: PostalAddress,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Address {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Address {
+            id: row.try_get("id")?,
+            client_id: row.try_get("client_id")?,
+            label: row.try_get("label")?,
+            address: PostalAddress {
+                recipient: row.try_get("recipient")?,
+                postal_code: row.try_get("postal_code")?,
+                region: row.try_get("region")?,
+                city: row.try_get("city")?,
+                line1: row.try_get("line1")?,
+                line2: row.try_get("line2")?,
+                country: row.try_get("country")?,
+            },
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateAddress {
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub address: PostalAddress,
+}
+
+// 更新用の構造体（None のフィールドは変更しない。Some(None) で値を削除する）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateAddress {
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub label: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recipient: Option<Option<String>>,
+    pub postal_code: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub line1: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub line2: Option<Option<String>>,
+    pub country: Option<String>,
+}
+
+impl UpdateAddress {
+    // 現在の住所に変更を適用した住所（検証は変更後の住所全体に対して行う）
+    pub fn apply(self, current: &Address) -> (Option<String>, PostalAddress) {
+        let address = &current.address;
+        (
+            self.label.unwrap_or_else(|| current.label.clone()),
+            PostalAddress {
+                recipient: self.recipient.unwrap_or_else(|| address.recipient.clone()),
+                postal_code: self.postal_code.unwrap_or_else(|| address.postal_code.clone()),
+                region: self.region.unwrap_or_else(|| address.region.clone()),
+                city: self.city.unwrap_or_else(|| address.city.clone()),
+                line1: self.line1.unwrap_or_else(|| address.line1.clone()),
+                line2: self.line2.unwrap_or_else(|| address.line2.clone()),
+                country: self.country.unwrap_or_else(|| address.country.clone()),
+            },
+        )
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::query::ListQuery;
+
+// 監査ログの対象（audit_log.entity の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntity {
+    User,
+    Client,
+    Address,
+    Category,
+    Product,
+    Variant,
+    Order,
+    Warehouse,
+}
+
+impl AuditEntity {
+    pub const ALL: [AuditEntity; 8] = [
+        AuditEntity::User,
+        AuditEntity::Client,
+        AuditEntity::Address,
+        AuditEntity::Category,
+        AuditEntity::Product,
+        AuditEntity::Variant,
+        AuditEntity::Order,
+        AuditEntity::Warehouse,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::User => "user",
+            AuditEntity::Client => "client",
+            AuditEntity::Address => "address",
+            AuditEntity::Category => "category",
+            AuditEntity::Product => "product",
+            AuditEntity::Variant => "variant",
+            AuditEntity::Order => "order",
+            AuditEntity::Warehouse => "warehouse",
+        }
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditEntity::ALL
+            .into_iter()
+            .find(|entity| entity.as_str() == s)
+            .ok_or_else(|| format!("unknown audit entity: {:?}", s))
+    }
+}
+
+impl fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// 監査ログの操作（audit_log.action の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    // 論理削除（deleted_at の設定）
+    Delete,
+    // 論理削除の取り消し
+    Restore,
+    // 完全削除（changes は削除したレコードの値）
+    Purge,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 5] = [
+        AuditAction::Create,
+        AuditAction::Update,
+        AuditAction::Delete,
+        AuditAction::Restore,
+        AuditAction::Purge,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or_else(|| format!("unknown audit action: {:?}", s))
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// 1つのフィールドの変更前と変更後（作成時の before、完全削除時の after は null）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditChange {
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    // 操作したユーザー（ユーザーが削除されると None になるが、actor_name は残る）
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub entity: AuditEntity,
+    pub entity_id: Uuid,
+    pub action: AuditAction,
+    // 変更されたフィールドごとの値（キーはフィールド名）
+    pub changes: BTreeMap<String, AuditChange>,
+    pub created_at: DateTime<Utc>,
+}
+
+// entity・action は VARCHAR、changes は JSONB の列から読み込む
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AuditLogEntry {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let decode = |column: &str, e: String| sqlx::Error::ColumnDecode {
+            index: column.to_string(),
+            source: e.into(),
+        };
+        let entity: String = row.try_get("entity")?;
+        let action: String = row.try_get("action")?;
+        let changes: sqlx::types::Json<BTreeMap<String, AuditChange>> = row.try_get("changes")?;
+        Ok(AuditLogEntry {
+            id: row.try_get("id")?,
+            actor_id: row.try_get("actor_id")?,
+            actor_name: row.try_get("actor_name")?,
+            entity: entity.parse().map_err(|e| decode("entity", e))?,
+            entity_id: row.try_get("entity_id")?,
+            action: action.parse().map_err(|e| decode("action", e))?,
+            changes: changes.0,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSort {
+    #[default]
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditFilter {
+    pub entity: Option<AuditEntity>,
+    pub entity_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    // このフィールドが変更された記録だけを返す（例: "total_amount"）
+    pub field: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+}
+
+pub type AuditQuery = ListQuery<AuditSort, AuditFilter>;
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::roles::Permission;
+use crate::users::User;
+
+// ログイン中のセッション
+// token はデータのコマンドを呼び出すたびに渡す（アプリを閉じると無効になる）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub token: String,
+    pub user: User,
+    // ログイン時のロールの権限（ロールを変更すると更新される）
+    pub permissions: Vec<Permission>,
+    // 最後に使われてから一定時間で切れる（使うたびに延長する）
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::query::double_option;
+
+// 商品カテゴリ（parent_id で階層にする。最上位のカテゴリは parent_id が None）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductCategory {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    // 最上位からの名前を " / " でつないだもの（例: "Computers / Accessories"）
+    pub path: String,
+    // 最上位のカテゴリは 0
+    pub depth: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// path と depth はカテゴリの一覧を取得するクエリで計算する
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ProductCategory {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(ProductCategory {
+            id: row.try_get("id")?,
+            parent_id: row.try_get("parent_id")?,
+            name: row.try_get("name")?,
+            path: row.try_get("path")?,
+            depth: row.try_get("depth")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateProductCategory {
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+}
+
+// 更新用の構造体（None のフィールドは変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProductCategory {
+    // Some(None) で最上位に移動する（自分の子孫の下には移動できない）
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent_id: Option<Option<Uuid>>,
+    pub name: Option<String>,
+}
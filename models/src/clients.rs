@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::query::{double_option, ListQuery};
+
+// 支払条件（clients.payment_terms の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentTerms {
+    // 前払い
+    #[default]
+    Prepaid,
+    // 代金引換
+    CashOnDelivery,
+    // 請求から30日以内
+    Net30,
+    // 月末締め翌月末払い
+    EndOfNextMonth,
+}
+
+impl PaymentTerms {
+    pub const ALL: [PaymentTerms; 4] = [
+        PaymentTerms::Prepaid,
+        PaymentTerms::CashOnDelivery,
+        PaymentTerms::Net30,
+        PaymentTerms::EndOfNextMonth,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentTerms::Prepaid => "prepaid",
+            PaymentTerms::CashOnDelivery => "cash_on_delivery",
+            PaymentTerms::Net30 => "net_30",
+            PaymentTerms::EndOfNextMonth => "end_of_next_month",
+        }
+    }
+
+    // 画面に表示する名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaymentTerms::Prepaid => "Prepaid",
+            PaymentTerms::CashOnDelivery => "Cash on delivery",
+            PaymentTerms::Net30 => "Net 30 days",
+            PaymentTerms::EndOfNextMonth => "End of next month",
+        }
+    }
+}
+
+impl FromStr for PaymentTerms {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PaymentTerms::ALL
+            .into_iter()
+            .find(|terms| terms.as_str() == s)
+            .ok_or_else(|| format!("unknown payment terms: {:?}", s))
+    }
+}
+
+impl fmt::Display for PaymentTerms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// 取引先（注文の顧客）。ログインするユーザー（users）とは別に管理する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Client {
+    pub id: Uuid,
+    pub company_name: String,
+    // 担当者
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    // 既定の請求先・配送先（client_addresses。注文の作成時に住所を指定しなければこれを使う）
+    pub default_billing_address_id: Option<Uuid>,
+    pub default_shipping_address_id: Option<Uuid>,
+    // 適格請求書発行事業者の登録番号など
+    pub tax_id: Option<String>,
+    pub payment_terms: PaymentTerms,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // 削除された日時（論理削除。削除されていなければ None）
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+// payment_terms は VARCHAR の列から読み込む
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Client {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let payment_terms: String = row.try_get("payment_terms")?;
+        Ok(Client {
+            id: row.try_get("id")?,
+            company_name: row.try_get("company_name")?,
+            contact_name: row.try_get("contact_name")?,
+            email: row.try_get("email")?,
+            phone: row.try_get("phone")?,
+            default_billing_address_id: row.try_get("default_billing_address_id")?,
+            default_shipping_address_id: row.try_get("default_shipping_address_id")?,
+            tax_id: row.try_get("tax_id")?,
+            payment_terms: payment_terms.parse().map_err(|e: String| {
+                sqlx::Error::ColumnDecode {
+                    index: "payment_terms".to_string(),
+                    source: e.into(),
+                }
+            })?,
+            notes: row.try_get("notes")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+        })
+    }
+}
+
+// 住所は作成後に create_client_address で登録する（最初の住所が既定の請求先・配送先になる）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateClient {
+    pub company_name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub tax_id: Option<String>,
+    #[serde(default)]
+    pub payment_terms: PaymentTerms,
+    pub notes: Option<String>,
+}
+
+// 更新用の構造体（None のフィールドは変更しない。Some(None) で値を削除する）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateClient {
+    pub company_name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contact_name: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub email: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub phone: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_billing_address_id: Option<Option<Uuid>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_shipping_address_id: Option<Option<Uuid>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tax_id: Option<Option<String>>,
+    pub payment_terms: Option<PaymentTerms>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub notes: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientSort {
+    #[default]
+    CreatedAt,
+    CompanyName,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientFilter {
+    // 指定した ID のレコードだけを返す（検索結果からの移動に使う）
+    pub ids: Option<Vec<Uuid>>,
+    // 会社名・担当者・メールアドレス・電話番号の部分一致
+    pub search: Option<String>,
+    pub payment_terms: Option<PaymentTerms>,
+    // 削除されたレコードも返す（既定では除外する）
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+pub type ClientQuery = ListQuery<ClientSort, ClientFilter>;
@@ -0,0 +1,78 @@
+// Tauri コマンドの一覧
+// バックエンドはこの一覧から generate_handler! を、フロントエンドはコマンド名を生成するため、
+// 一覧にあってバックエンドに存在しないコマンドや、一覧にないコマンドの呼び出しはコンパイルエラーになる
+//
+// 使い方: 受け取る側で `macro_rules! handler { ($($name:ident),*) => { ... } }` を定義し、
+// `ws_models::commands!(handler)` と呼ぶ
+#[macro_export]
+macro_rules! commands {
+    ($callback:ident) => {
+        $callback! {
+            greet,
+            get_connection_status,
+            connect_database,
+            get_connection_config,
+            save_connection_profile,
+            delete_connection_profile,
+            select_connection_profile,
+            is_setup_required,
+            setup_first_user,
+            login,
+            logout,
+            get_users,
+            get_user_by_id,
+            create_user,
+            update_user,
+            delete_user,
+            restore_user,
+            get_clients,
+            get_client_by_id,
+            create_client,
+            update_client,
+            delete_client,
+            restore_client,
+            get_client_addresses,
+            create_client_address,
+            update_client_address,
+            delete_client_address,
+            get_products,
+            get_product_by_id,
+            get_product_by_barcode,
+            create_product,
+            update_product,
+            delete_product,
+            restore_product,
+            get_product_variants,
+            set_product_options,
+            update_product_variants,
+            get_stock_movements,
+            adjust_stock,
+            adjust_stock_batch,
+            get_stock_discrepancies,
+            get_reorder_suggestions,
+            get_stock_levels,
+            create_stock_transfer,
+            get_warehouses,
+            create_warehouse,
+            update_warehouse,
+            delete_warehouse,
+            get_product_categories,
+            create_product_category,
+            update_product_category,
+            delete_product_category,
+            get_orders,
+            get_orders_with_items,
+            get_order_by_id,
+            create_order,
+            update_order,
+            delete_order,
+            restore_order,
+            transition_order,
+            get_order_status_history,
+            search,
+            get_migration_status,
+            get_audit_log,
+            purge_deleted
+        }
+    };
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 接続状態が変わったときにバックエンドが送るイベント（ペイロードは ConnectionStatus）
+// 接続プールが使えるようになったとき（state が connected になったとき）にも送られる
+pub const CONNECTION_STATUS_EVENT: &str = "connection-status";
+
+// 接続プロファイルの保存先（Tauri のアプリ設定ディレクトリ内）のファイル名
+pub const CONNECTION_CONFIG_FILE: &str = "connections.json";
+
+// TLS の使い方（PostgreSQL の sslmode と同じ）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    Disable,
+    Allow,
+    #[default]
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    pub const ALL: [SslMode; 6] = [
+        SslMode::Disable,
+        SslMode::Allow,
+        SslMode::Prefer,
+        SslMode::Require,
+        SslMode::VerifyCa,
+        SslMode::VerifyFull,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Allow => "allow",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+}
+
+// PostgreSQL の接続設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionSettings {
+    pub host: String,
+    pub port: u16,
+    pub database: String,
+    pub username: String,
+    #[serde(default)]
+    pub ssl_mode: SslMode,
+    // 設定ファイルには保存せず、フロントエンドに返すときも常に None
+    // 接続し直すときに None を渡すと、今のパスワードをそのまま使う
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        ConnectionSettings {
+            host: "localhost".to_string(),
+            port: 5432,
+            database: "ws_app".to_string(),
+            username: "postgres".to_string(),
+            ssl_mode: SslMode::default(),
+            password: None,
+        }
+    }
+}
+
+// 接続プールの設定（PgPoolOptions に渡す）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    // 接続を取得するまで待つ秒数（最初の接続のタイムアウトにも使う）
+    pub acquire_timeout_secs: u64,
+    // 使われていない接続を閉じるまでの秒数（None なら閉じない）
+    pub idle_timeout_secs: Option<u64>,
+    // 1つの SQL の実行時間の上限（None ならサーバーの設定のまま）
+    pub statement_timeout_ms: Option<u64>,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        PoolSettings {
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout_secs: 5,
+            idle_timeout_secs: Some(600),
+            statement_timeout_ms: None,
+        }
+    }
+}
+
+// 名前付きの接続先（dev・staging・production など）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub connection: ConnectionSettings,
+    #[serde(default)]
+    pub pool: PoolSettings,
+}
+
+// 設定ファイル（CONNECTION_CONFIG_FILE）の内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    // 使用中のプロファイル名
+    pub active: String,
+    pub profiles: Vec<ConnectionProfile>,
+}
+
+impl ConnectionConfig {
+    pub fn profile(&self, name: &str) -> Option<&ConnectionProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    // 接続中（起動直後・再接続の試行中）
+    Connecting,
+    // 接続プールが使える
+    Connected,
+    // 接続できず、retry_at に再試行する
+    Disconnected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    // 使用中のプロファイル名
+    pub profile: String,
+    pub settings: ConnectionSettings,
+    // 最後に失敗したときのエラー
+    pub error: Option<String>,
+    // 連続して失敗した回数
+    pub attempts: u32,
+    // 次に自動で再接続する時刻
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+impl ConnectionStatus {
+    pub fn is_connected(&self) -> bool {
+        self.state == ConnectionState::Connected
+    }
+}
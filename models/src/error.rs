@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+// 入力欄ごとのエラー
+// field はリクエストの JSON 上のパス（例: "email"、"items.0.quantity"）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+// コマンドのエラー（フロントエンドで種類を判別できるようにシリアライズする）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppError {
+    // 入力値の誤り
+    Validation {
+        message: String,
+        #[serde(default)]
+        fields: Vec<FieldError>,
+    },
+    NotFound {
+        entity: String,
+        id: Option<Uuid>,
+    },
+    // 一意制約・外部キー制約の違反や、現在の状態では実行できない操作
+    Conflict {
+        message: String,
+        constraint: Option<String>,
+        #[serde(default)]
+        fields: Vec<FieldError>,
+    },
+    // ログインしていない、またはセッションの有効期限が切れた
+    Unauthorized {
+        message: String,
+    },
+    // ログイン中のユーザーのロールに必要な権限がない
+    Forbidden {
+        message: String,
+    },
+    // データベースに接続できない（時間をおいて再試行すれば回復しうる）
+    Unavailable {
+        message: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn not_found(entity: &str, id: Uuid) -> Self {
+        AppError::NotFound {
+            entity: entity.to_string(),
+            id: Some(id),
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation {
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    // 1つの入力欄に対するエラー
+    pub fn field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        let error = FieldError::new(field, message);
+        AppError::Validation {
+            message: format!("{}: {}", error.field, error.message),
+            fields: vec![error],
+        }
+    }
+
+    // 複数の入力欄のエラーをまとめて返す（空なら Ok）
+    pub fn check_fields(fields: Vec<FieldError>) -> Result<(), AppError> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let message = fields
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(AppError::Validation { message, fields })
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        AppError::Unauthorized {
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        AppError::Internal {
+            message: message.into(),
+        }
+    }
+
+    pub fn fields(&self) -> &[FieldError] {
+        match self {
+            AppError::Validation { fields, .. } | AppError::Conflict { fields, .. } => fields,
+            _ => &[],
+        }
+    }
+
+    // 入力欄の下に表示するメッセージ（"items" なら "items.0.quantity" なども含めてまとめる）
+    pub fn field_message(&self, field: &str) -> Option<String> {
+        let prefix = format!("{}.", field);
+        let messages: Vec<&str> = self
+            .fields()
+            .iter()
+            .filter(|e| e.field == field || e.field.starts_with(&prefix))
+            .map(|e| e.message.as_str())
+            .collect();
+        (!messages.is_empty()).then(|| messages.join(", "))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Validation { message, .. } => f.write_str(message),
+            AppError::NotFound { entity, id: Some(id) } => write!(f, "{} not found: {}", entity, id),
+            AppError::NotFound { entity, id: None } => write!(f, "{} not found", entity),
+            AppError::Conflict { message, .. } => f.write_str(message),
+            AppError::Unauthorized { message } => f.write_str(message),
+            AppError::Forbidden { message } => f.write_str(message),
+            AppError::Unavailable { message } => write!(f, "database unavailable: {}", message),
+            AppError::Internal { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// 既定の制約名（<テーブル>_<列>_key / _check / _fkey）から列名を取り出す
+// 複数列の CHECK 制約（<テーブル>_check）のように列名を含まない場合は None
+#[cfg(feature = "sqlx")]
+fn constraint_column(table: Option<&str>, constraint: &str) -> Option<String> {
+    let rest = constraint.strip_prefix(table?)?.strip_prefix('_')?;
+    let column = ["_key", "_check", "_fkey"]
+        .iter()
+        .find_map(|suffix| rest.strip_suffix(suffix))?;
+    (!column.is_empty()).then(|| column.to_string())
+}
+
+// SQLSTATE と制約名でエラーの種類を判別する
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) => {
+                let constraint = db.constraint().map(str::to_string);
+                let pg = db.try_downcast_ref::<sqlx::postgres::PgDatabaseError>();
+                let table = pg.and_then(|pg| pg.table());
+                let column = pg
+                    .and_then(|pg| pg.column().map(str::to_string))
+                    .or_else(|| constraint_column(table, constraint.as_deref()?));
+                let code = db.code().map(|c| c.into_owned()).unwrap_or_default();
+                match code.as_str() {
+                    "23505" => {
+                        return AppError::Conflict {
+                            message: "a record with the same value already exists".to_string(),
+                            fields: column
+                                .map(|c| FieldError::new(c, "is already in use"))
+                                .into_iter()
+                                .collect(),
+                            constraint,
+                        }
+                    }
+                    "23503" => {
+                        return AppError::Conflict {
+                            message: "the record is referenced by other records or refers to a missing record".to_string(),
+                            fields: Vec::new(),
+                            constraint,
+                        }
+                    }
+                    "23514" | "23502" | "22001" | "22003" => {
+                        let message = match code.as_str() {
+                            "23502" => "is required",
+                            "22001" => "is too long",
+                            "22003" => "is out of range",
+                            _ => "is invalid",
+                        };
+                        return AppError::Validation {
+                            message: db.message().to_string(),
+                            fields: column
+                                .map(|c| FieldError::new(c, message))
+                                .into_iter()
+                                .collect(),
+                        };
+                    }
+                    // 08xxx: 接続エラー、57P0x: サーバー停止中、53300: 接続数超過
+                    c if c.starts_with("08") || c.starts_with("57P0") || c == "53300" => {
+                        return AppError::Unavailable {
+                            message: db.message().to_string(),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => {
+                return AppError::Unavailable {
+                    message: e.to_string(),
+                }
+            }
+            sqlx::Error::RowNotFound => {
+                return AppError::NotFound {
+                    entity: "record".to_string(),
+                    id: None,
+                }
+            }
+            _ => {}
+        }
+        AppError::Internal {
+            message: e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// バックエンド（ws）とフロントエンド（ws-ui）で共有するコマンドの入出力型
+// フィールドを変更すると両方のクレートでコンパイルエラーになる
+pub mod addresses;
+pub mod audit;
+pub mod auth;
+pub mod categories;
+pub mod clients;
+pub mod commands;
+pub mod connection;
+pub mod error;
+pub mod migrations;
+pub mod money;
+pub mod orders;
+pub mod products;
+pub mod purge;
+pub mod query;
+pub mod reorder;
+pub mod roles;
+pub mod search;
+pub mod stock;
+pub mod users;
+pub mod variants;
+pub mod warehouses;
+
+pub use addresses::{
+    Address, CreateAddress, PostalAddress, UpdateAddress, COUNTRY_JP, JP_PREFECTURES,
+};
+pub use audit::{
+    AuditAction, AuditChange, AuditEntity, AuditFilter, AuditLogEntry, AuditQuery, AuditSort,
+};
+pub use auth::Session;
+pub use categories::{CreateProductCategory, ProductCategory, UpdateProductCategory};
+pub use clients::{
+    Client, ClientFilter, ClientQuery, ClientSort, CreateClient, PaymentTerms, UpdateClient,
+};
+pub use connection::{
+    ConnectionConfig, ConnectionProfile, ConnectionSettings, ConnectionState, ConnectionStatus,
+    PoolSettings, SslMode, CONNECTION_CONFIG_FILE, CONNECTION_STATUS_EVENT,
+};
+pub use error::{AppError, FieldError};
+pub use migrations::{MigrationState, MigrationStatus};
+pub use money::{CurrencyCode, Money};
+pub use orders::{
+    CreateOrder, CreateOrderItem, Order, OrderFilter, OrderItem, OrderQuery, OrderSort,
+    OrderStatus, OrderStatusHistory, OrderWithItems, UpdateOrder,
+};
+pub use products::{
+    CreateProduct, Product, ProductFilter, ProductQuery, ProductSort, UnitOfMeasure, UpdateProduct,
+};
+pub use purge::{PurgeReport, ORDER_RETENTION_YEARS, PURGE_AFTER_DAYS};
+pub use query::{ListQuery, Page, SortDirection};
+pub use reorder::{
+    LowStockAlert, ReorderQuery, ReorderSuggestion, LOW_STOCK_EVENT, MAX_REORDER_DAYS,
+    REORDER_COVER_DAYS, REORDER_SALES_DAYS,
+};
+pub use roles::{Permission, Role};
+pub use search::{SearchHit, SearchKind};
+pub use stock::{
+    AdjustStock, AdjustStockBatch, AdjustStockItem, CreateStockTransfer, StockDiscrepancy,
+    StockLevel, StockMovement, StockMovementFilter, StockMovementQuery, StockMovementSort,
+    StockMovementType, StockTransfer, StockTransferItem,
+};
+pub use users::{CreateUser, UpdateUser, User, UserFilter, UserQuery, UserSort};
+pub use variants::{
+    ProductOption, ProductVariant, ProductVariants, SetProductOption, UpdateProductVariant,
+    MAX_PRODUCT_OPTIONS,
+};
+pub use warehouses::{CreateWarehouse, UpdateWarehouse, Warehouse};
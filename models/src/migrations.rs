@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    // 適用済み
+    Applied,
+    // 未適用
+    Pending,
+    // 適用後にファイルが変更された
+    Modified,
+    // データベースにのみ存在する（アプリより新しいスキーマ）
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,164 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+// ISO 4217 の通貨コード（英大文字3文字）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurrencyCode([u8; 3]);
+
+impl CurrencyCode {
+    pub const JPY: CurrencyCode = CurrencyCode(*b"JPY");
+
+    pub fn as_str(&self) -> &str {
+        // 生成時に ASCII の英大文字であることを検証済み
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+
+    // 補助通貨単位の桁数（表示用）
+    pub fn minor_units(&self) -> u32 {
+        match &self.0 {
+            b"JPY" | b"KRW" => 0,
+            _ => 2,
+        }
+    }
+
+    pub fn symbol(&self) -> Option<&'static str> {
+        match &self.0 {
+            b"JPY" => Some("¥"),
+            b"USD" => Some("$"),
+            b"EUR" => Some("€"),
+            b"GBP" => Some("£"),
+            _ => None,
+        }
+    }
+}
+
+impl Default for CurrencyCode {
+    fn default() -> Self {
+        CurrencyCode::JPY
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.trim().as_bytes();
+        if bytes.len() == 3 && bytes.iter().all(|b| b.is_ascii_uppercase()) {
+            Ok(CurrencyCode([bytes[0], bytes[1], bytes[2]]))
+        } else {
+            Err(format!("invalid currency code: {:?}", s))
+        }
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CurrencyCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// 金額（DECIMAL(10,2) の列と通貨コードの列の組）
+// 金額は文字列としてシリアライズされるため、invoke の境界で精度が落ちない
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Money {
+    pub amount: Decimal,
+    #[serde(default)]
+    pub currency: CurrencyCode,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: CurrencyCode) -> Self {
+        Money { amount, currency }
+    }
+
+    pub fn zero(currency: CurrencyCode) -> Self {
+        Money::new(Decimal::ZERO, currency)
+    }
+
+    // 通貨が異なる場合・桁あふれの場合は None
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(self.amount.checked_add(other.amount)?, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(self.amount.checked_sub(other.amount)?, self.currency))
+    }
+
+    pub fn checked_mul(self, quantity: i64) -> Option<Money> {
+        Some(Money::new(
+            self.amount.checked_mul(Decimal::from(quantity))?,
+            self.currency,
+        ))
+    }
+
+    // 金額列と通貨コード列から読み込む
+    #[cfg(feature = "sqlx")]
+    pub fn from_row(
+        row: &sqlx::postgres::PgRow,
+        amount: &str,
+        currency: &str,
+    ) -> Result<Money, sqlx::Error> {
+        use sqlx::Row;
+
+        let code: String = row.try_get(currency)?;
+        let currency = code.parse().map_err(|e: String| sqlx::Error::ColumnDecode {
+            index: currency.to_string(),
+            source: e.into(),
+        })?;
+        Ok(Money::new(row.try_get(amount)?, currency))
+    }
+}
+
+// 例: ¥1,299 / $29.99 / CHF 10.00
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = self.amount.normalize().scale().max(self.currency.minor_units());
+        let formatted = format!("{:.*}", scale as usize, self.amount.abs());
+        let (int_part, frac_part) = match formatted.split_once('.') {
+            Some((i, f)) => (i, Some(f)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut grouped = String::new();
+        for (i, c) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        if self.amount.is_sign_negative() && !self.amount.is_zero() {
+            f.write_str("-")?;
+        }
+        match self.currency.symbol() {
+            Some(symbol) => f.write_str(symbol)?,
+            None => write!(f, "{} ", self.currency)?,
+        }
+        f.write_str(&grouped)?;
+        if let Some(frac) = frac_part {
+            write!(f, ".{}", frac)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::addresses::PostalAddress;
+use crate::money::Money;
+use crate::query::{double_option, ListQuery};
+
+// 注文ステータス（orders.status の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Pending,
+    Processing,
+    Shipped,
+    Delivered,
+    Cancelled,
+}
+
+impl OrderStatus {
+    pub const ALL: [OrderStatus; 5] = [
+        OrderStatus::Pending,
+        OrderStatus::Processing,
+        OrderStatus::Shipped,
+        OrderStatus::Delivered,
+        OrderStatus::Cancelled,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Pending => "pending",
+            OrderStatus::Processing => "processing",
+            OrderStatus::Shipped => "shipped",
+            OrderStatus::Delivered => "delivered",
+            OrderStatus::Cancelled => "cancelled",
+        }
+    }
+
+    // このステータスから遷移できるステータス
+    pub fn next(&self) -> &'static [OrderStatus] {
+        match self {
+            OrderStatus::Pending => &[OrderStatus::Processing, OrderStatus::Cancelled],
+            OrderStatus::Processing => &[OrderStatus::Shipped, OrderStatus::Cancelled],
+            OrderStatus::Shipped => &[OrderStatus::Delivered],
+            OrderStatus::Delivered | OrderStatus::Cancelled => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, to: OrderStatus) -> bool {
+        self.next().contains(&to)
+    }
+
+    // 在庫を引き当てたままのステータス（出荷前）
+    pub fn holds_stock(&self) -> bool {
+        matches!(self, OrderStatus::Pending | OrderStatus::Processing)
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OrderStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| format!("unknown order status: {:?}", s))
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// VARCHAR の列から OrderStatus を読み込む
+#[cfg(feature = "sqlx")]
+fn status_column(row: &sqlx::postgres::PgRow, column: &str) -> Result<OrderStatus, sqlx::Error> {
+    use sqlx::Row;
+
+    let value: String = row.try_get(column)?;
+    value.parse().map_err(|e: String| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: e.into(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: Uuid,
+    // 顧客（取引先）
+    pub client_id: Option<Uuid>,
+    // 注文時点の請求先・配送先（取引先の住所を後から変更しても過去の注文は変わらない）
+    pub billing_address: Option<PostalAddress>,
+    pub shipping_address: Option<PostalAddress>,
+    // 出荷する保管場所（出荷前の注文の在庫はこの保管場所から引き当てる）
+    pub warehouse_id: Uuid,
+    pub total_amount: Money,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // 削除された日時（論理削除。削除されていなければ None）
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Order {
+    // 画面に表示する注文番号（UUID の先頭8文字。search コマンドはこの前方一致で注文を探す）
+    pub fn number(&self) -> String {
+        self.id.to_string()[..8].to_string()
+    }
+}
+
+// JSONB の列から住所のスナップショットを読み込む
+#[cfg(feature = "sqlx")]
+fn address_column(
+    row: &sqlx::postgres::PgRow,
+    column: &str,
+) -> Result<Option<PostalAddress>, sqlx::Error> {
+    use sqlx::Row;
+
+    let value: Option<sqlx::types::Json<PostalAddress>> = row.try_get(column)?;
+    Ok(value.map(|json| json.0))
+}
+
+// 金額は total_amount と currency の2列から組み立てる
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Order {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Order {
+            id: row.try_get("id")?,
+            client_id: row.try_get("client_id")?,
+            billing_address: address_column(row, "billing_address")?,
+            shipping_address: address_column(row, "shipping_address")?,
+            warehouse_id: row.try_get("warehouse_id")?,
+            total_amount: Money::from_row(row, "total_amount", "currency")?,
+            status: status_column(row, "status")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderItem {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub product_id: Uuid,
+    // バリエーションのある商品ではサイズ・カラーなどの組み合わせ
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+    pub unit_price: Money,
+    pub discount: Money,
+    // 単価 × 数量 − 値引き
+    pub line_total: Money,
+    pub created_at: DateTime<Utc>,
+}
+
+// 金額は unit_price・discount と currency の列から組み立てる
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrderItem {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let quantity: i32 = row.try_get("quantity")?;
+        let unit_price = Money::from_row(row, "unit_price", "currency")?;
+        let discount = Money::from_row(row, "discount", "currency")?;
+        let line_total = unit_price
+            .checked_mul(quantity.into())
+            .and_then(|subtotal| subtotal.checked_sub(discount))
+            .ok_or_else(|| sqlx::Error::ColumnDecode {
+                index: "unit_price".to_string(),
+                source: "line total overflow".into(),
+            })?;
+
+        Ok(OrderItem {
+            id: row.try_get("id")?,
+            order_id: row.try_get("order_id")?,
+            product_id: row.try_get("product_id")?,
+            variant_id: row.try_get("variant_id")?,
+            quantity,
+            unit_price,
+            discount,
+            line_total,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderWithItems {
+    #[serde(flatten)]
+    pub order: Order,
+    pub items: Vec<OrderItem>,
+}
+
+// 注文ステータスの変更履歴（タイムライン表示用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderStatusHistory {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    // 注文作成時の記録では None
+    pub from_status: Option<OrderStatus>,
+    pub to_status: OrderStatus,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_name: Option<String>,
+    pub note: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for OrderStatusHistory {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let from_status: Option<String> = row.try_get("from_status")?;
+        Ok(OrderStatusHistory {
+            id: row.try_get("id")?,
+            order_id: row.try_get("order_id")?,
+            from_status: match from_status {
+                Some(_) => Some(status_column(row, "from_status")?),
+                None => None,
+            },
+            to_status: status_column(row, "to_status")?,
+            changed_by: row.try_get("changed_by")?,
+            changed_by_name: row.try_get("changed_by_name")?,
+            note: row.try_get("note")?,
+            changed_at: row.try_get("changed_at")?,
+        })
+    }
+}
+
+// 合計金額はクライアントから受け取らず、明細と現在の商品価格からサーバー側で計算する
+// ステータスは常に pending で作成され、以降は transition_order で変更する
+// 住所を指定しなければ取引先の既定の請求先・配送先を使い、その時点の内容を注文に保存する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrder {
+    pub client_id: Option<Uuid>,
+    // 取引先の住所（client_addresses）の ID
+    #[serde(default)]
+    pub billing_address_id: Option<Uuid>,
+    #[serde(default)]
+    pub shipping_address_id: Option<Uuid>,
+    // 出荷する保管場所（None なら既定の保管場所）
+    #[serde(default)]
+    pub warehouse_id: Option<Uuid>,
+    pub items: Vec<CreateOrderItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrderItem {
+    pub product_id: Uuid,
+    // バリエーションのある商品では必須（単価と在庫はバリエーションのもの）
+    #[serde(default)]
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+    // 明細単位の値引き額（商品と同じ通貨）
+    pub discount: Option<Decimal>,
+}
+
+// 合計金額は明細から、ステータスは transition_order で変更するため、ここでは扱わない
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateOrder {
+    // Some(None) で顧客の紐付けを外す（None は送信しない）
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub client_id: Option<Option<Uuid>>,
+    // 指定した住所の現在の内容で請求先・配送先を保存し直す（配送先は出荷前の注文だけ）
+    pub billing_address_id: Option<Uuid>,
+    pub shipping_address_id: Option<Uuid>,
+    // 出荷する保管場所を変更し、引き当て済みの在庫を移す（出荷前の注文だけ）
+    pub warehouse_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSort {
+    #[default]
+    CreatedAt,
+    TotalAmount,
+    Status,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderFilter {
+    // 指定した ID のレコードだけを返す（検索結果からの移動に使う）
+    pub ids: Option<Vec<Uuid>>,
+    // いずれかのステータスに一致する注文
+    pub status: Option<Vec<OrderStatus>>,
+    pub client_id: Option<Uuid>,
+    // 出荷する保管場所
+    pub warehouse_id: Option<Uuid>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub min_total: Option<Decimal>,
+    pub max_total: Option<Decimal>,
+    // 削除されたレコードも返す（既定では除外する）
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+pub type OrderQuery = ListQuery<OrderSort, OrderFilter>;
+
+#[cfg(test)]
+mod tests {
+    use super::OrderStatus;
+
+    #[test]
+    fn allows_only_forward_transitions() {
+        use OrderStatus::*;
+        let allowed = [
+            (Pending, Processing),
+            (Pending, Cancelled),
+            (Processing, Shipped),
+            (Processing, Cancelled),
+            (Shipped, Delivered),
+        ];
+        for from in OrderStatus::ALL {
+            for to in OrderStatus::ALL {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{} -> {}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn delivered_and_cancelled_are_final() {
+        assert!(OrderStatus::Delivered.next().is_empty());
+        assert!(OrderStatus::Cancelled.next().is_empty());
+    }
+
+    #[test]
+    fn every_status_is_reachable_from_pending() {
+        let mut reached = vec![OrderStatus::Pending];
+        let mut i = 0;
+        while i < reached.len() {
+            for next in reached[i].next() {
+                if !reached.contains(next) {
+                    reached.push(*next);
+                }
+            }
+            i += 1;
+        }
+        for status in OrderStatus::ALL {
+            assert!(reached.contains(&status), "{}", status);
+        }
+    }
+
+    #[test]
+    fn stock_is_released_once_shipped_or_cancelled() {
+        // 引き当て中のステータスからだけキャンセルでき、出荷後に引き当て中へは戻れない
+        for from in OrderStatus::ALL {
+            for to in from.next() {
+                if *to == OrderStatus::Cancelled {
+                    assert!(from.holds_stock(), "{}", from);
+                }
+                if !from.holds_stock() {
+                    assert!(!to.holds_stock(), "{} -> {}", from, to);
+                }
+            }
+        }
+    }
+}
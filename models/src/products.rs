@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::money::Money;
+use crate::query::{double_option, ListQuery};
+
+// 数量の単位（products.unit の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitOfMeasure {
+    #[default]
+    Piece,
+    Box,
+    Pack,
+    Set,
+    Pair,
+    Kilogram,
+    Meter,
+    Liter,
+}
+
+impl UnitOfMeasure {
+    pub const ALL: [UnitOfMeasure; 8] = [
+        UnitOfMeasure::Piece,
+        UnitOfMeasure::Box,
+        UnitOfMeasure::Pack,
+        UnitOfMeasure::Set,
+        UnitOfMeasure::Pair,
+        UnitOfMeasure::Kilogram,
+        UnitOfMeasure::Meter,
+        UnitOfMeasure::Liter,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitOfMeasure::Piece => "piece",
+            UnitOfMeasure::Box => "box",
+            UnitOfMeasure::Pack => "pack",
+            UnitOfMeasure::Set => "set",
+            UnitOfMeasure::Pair => "pair",
+            UnitOfMeasure::Kilogram => "kilogram",
+            UnitOfMeasure::Meter => "meter",
+            UnitOfMeasure::Liter => "liter",
+        }
+    }
+
+    // 数量の後ろに表示する記号
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnitOfMeasure::Piece => "pcs",
+            UnitOfMeasure::Box => "box",
+            UnitOfMeasure::Pack => "pack",
+            UnitOfMeasure::Set => "set",
+            UnitOfMeasure::Pair => "pair",
+            UnitOfMeasure::Kilogram => "kg",
+            UnitOfMeasure::Meter => "m",
+            UnitOfMeasure::Liter => "L",
+        }
+    }
+}
+
+impl FromStr for UnitOfMeasure {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnitOfMeasure::ALL
+            .into_iter()
+            .find(|unit| unit.as_str() == s)
+            .ok_or_else(|| format!("unknown unit of measure: {:?}", s))
+    }
+}
+
+impl fmt::Display for UnitOfMeasure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Product {
+    pub id: Uuid,
+    pub name: String,
+    // 商品コード（大文字に揃えて保存し、削除されていない商品の間で一意）
+    pub sku: String,
+    // JAN/EAN コード（8桁・13桁、または UPC の12桁。削除されていない商品の間で一意）
+    pub barcode: Option<String>,
+    pub description: Option<String>,
+    pub unit: UnitOfMeasure,
+    pub category_id: Option<Uuid>,
+    pub price: Money,
+    // 在庫数（stock_movements の記録の合計。直接は変更しない）
+    pub stock: i32,
+    // 在庫がこの数以下になったら発注する（None なら発注点なし）
+    pub reorder_point: Option<i32>,
+    // 取扱終了の商品は新しい注文に使えない
+    pub discontinued: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // 削除された日時（論理削除。削除されていなければ None）
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+// 金額は price と currency の2列から組み立て、unit は VARCHAR の列から読み込む
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Product {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let unit: String = row.try_get("unit")?;
+        Ok(Product {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            sku: row.try_get("sku")?,
+            barcode: row.try_get("barcode")?,
+            description: row.try_get("description")?,
+            unit: unit.parse().map_err(|e: String| sqlx::Error::ColumnDecode {
+                index: "unit".to_string(),
+                source: e.into(),
+            })?,
+            category_id: row.try_get("category_id")?,
+            price: Money::from_row(row, "price", "currency")?,
+            stock: row.try_get("stock")?,
+            reorder_point: row.try_get("reorder_point")?,
+            discontinued: row.try_get("discontinued")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProduct {
+    pub name: String,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub unit: UnitOfMeasure,
+    pub category_id: Option<Uuid>,
+    pub price: Money,
+    // 最初の在庫数（入庫として在庫の記録に残す。作成後は adjust_stock で変更する）
+    pub stock: i32,
+    pub reorder_point: Option<i32>,
+}
+
+// 更新用の構造体（None のフィールドは変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProduct {
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    // Some(None) で値を削除する（None は送信しない）
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub barcode: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<Option<String>>,
+    pub unit: Option<UnitOfMeasure>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub category_id: Option<Option<Uuid>>,
+    pub price: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reorder_point: Option<Option<i32>>,
+    pub discontinued: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductSort {
+    #[default]
+    CreatedAt,
+    Name,
+    Sku,
+    Price,
+    Stock,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProductFilter {
+    // 指定した ID のレコードだけを返す（検索結果からの移動に使う）
+    pub ids: Option<Vec<Uuid>>,
+    // 商品名・SKU・バーコード・説明の部分一致
+    pub search: Option<String>,
+    // このカテゴリとその下位のカテゴリの商品
+    pub category_id: Option<Uuid>,
+    // バーコードの完全一致（読み取った値をそのまま渡せる）
+    pub barcode: Option<String>,
+    // Some(false) で取扱中、Some(true) で取扱終了の商品だけを返す
+    pub discontinued: Option<bool>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    // 在庫がこの数以下の商品だけを返す
+    pub low_stock_threshold: Option<i32>,
+    // 在庫が発注点以下の商品だけを返す
+    #[serde(default)]
+    pub below_reorder_point: bool,
+    // 削除されたレコードも返す（既定では除外する）
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+pub type ProductQuery = ListQuery<ProductSort, ProductFilter>;
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+// 削除（論理削除）してから完全削除できるようになるまでの日数
+pub const PURGE_AFTER_DAYS: i32 = 30;
+
+// 注文は作成から7年間は完全削除しない（帳簿書類の保存期間）
+pub const ORDER_RETENTION_YEARS: i32 = 7;
+
+// purge_deleted で完全削除したレコードの件数
+// 注文（ステータスの変更履歴を含む）から参照されているユーザー・取引先・商品は、注文の履歴を残すため完全削除しない
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub users: u64,
+    pub clients: u64,
+    pub products: u64,
+    pub orders: u64,
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// 一覧コマンド共通のクエリパラメータ
+// page は 1 始まり、省略時は1ページ目・DEFAULT_PAGE_SIZE 件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListQuery<S, F> {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub sort: Option<S>,
+    pub direction: Option<SortDirection>,
+    #[serde(default)]
+    pub filter: F,
+}
+
+impl<S, F> ListQuery<S, F> {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.page() == 0 {
+            return Err(AppError::field("page", "must be 1 or greater"));
+        }
+        if self.page_size() == 0 || self.page_size() > MAX_PAGE_SIZE {
+            return Err(AppError::field(
+                "page_size",
+                format!("must be between 1 and {}", MAX_PAGE_SIZE),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// 一覧コマンドのレスポンス
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub page_size: u32,
+    // フィルター適用後の総件数
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            page_size: self.page_size,
+            total: self.total,
+            total_pages: self.total_pages,
+        }
+    }
+}
+
+// まだ読み込んでいない一覧（1ページ目・0件）
+impl<T> Default for Page<T> {
+    fn default() -> Self {
+        Page {
+            items: Vec::new(),
+            page: 1,
+            page_size: DEFAULT_PAGE_SIZE,
+            total: 0,
+            total_pages: 0,
+        }
+    }
+}
+
+// フィールドの省略（変更しない）と null（値を消す）を区別する
+pub(crate) fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,97 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 在庫が発注点以下になった商品があるときにバックエンドが送るイベント（ペイロードは Vec<LowStockAlert>）
+// 新しく発注点以下になった商品だけを送る（発注点を上回ってから再び下回ったら、もう一度送る）
+// 送ったことはデータベースに残すため、アプリを起動し直しても同じ商品は送らない
+pub const LOW_STOCK_EVENT: &str = "low-stock";
+
+// 発注の提案で販売の速さを求める期間（日数）の既定値
+pub const REORDER_SALES_DAYS: i32 = 30;
+
+// 発注の提案で何日分の販売をまかなうか（日数）の既定値
+pub const REORDER_COVER_DAYS: i32 = 30;
+
+// 発注の提案の期間に指定できる最大の日数
+pub const MAX_REORDER_DAYS: i32 = 365;
+
+// 在庫が発注点以下になった商品（削除済み・取扱終了の商品は含まない）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LowStockAlert {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub stock: i32,
+    pub reorder_point: i32,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for LowStockAlert {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(LowStockAlert {
+            product_id: row.try_get("product_id")?,
+            name: row.try_get("name")?,
+            sku: row.try_get("sku")?,
+            stock: row.try_get("stock")?,
+            reorder_point: row.try_get("reorder_point")?,
+        })
+    }
+}
+
+// 発注の提案の条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorderQuery {
+    // 直近の何日間の販売（返品を差し引いた数）から販売の速さを求めるか
+    pub sales_days: i32,
+    // 発注後の在庫で何日分の販売をまかなうか
+    pub cover_days: i32,
+}
+
+impl Default for ReorderQuery {
+    fn default() -> Self {
+        Self {
+            sales_days: REORDER_SALES_DAYS,
+            cover_days: REORDER_COVER_DAYS,
+        }
+    }
+}
+
+// 発注の提案（在庫が発注点と cover_days 日分の販売を下回る商品）
+// suggested_quantity = 1日あたりの販売数 × cover_days（切り上げ）+ 発注点 − 在庫数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReorderSuggestion {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub stock: i32,
+    pub reorder_point: Option<i32>,
+    // sales_days 日間の販売数（返品を差し引いた数）
+    pub sold: i64,
+    // 1日あたりの販売数（小数点以下2桁）
+    pub daily_sales: Decimal,
+    // 今の在庫が何日でなくなるか（販売がなければ None）
+    pub days_of_stock: Option<i32>,
+    pub suggested_quantity: i32,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ReorderSuggestion {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(ReorderSuggestion {
+            product_id: row.try_get("product_id")?,
+            name: row.try_get("name")?,
+            sku: row.try_get("sku")?,
+            stock: row.try_get("stock")?,
+            reorder_point: row.try_get("reorder_point")?,
+            sold: row.try_get("sold")?,
+            daily_sales: row.try_get("daily_sales")?,
+            days_of_stock: row.try_get("days_of_stock")?,
+            suggested_quantity: row.try_get("suggested_quantity")?,
+        })
+    }
+}
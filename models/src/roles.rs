@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// ユーザーのロール（users.role と roles.name の値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Manager,
+    Clerk,
+    #[default]
+    ReadOnly,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Manager, Role::Clerk, Role::ReadOnly];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Manager => "manager",
+            Role::Clerk => "clerk",
+            Role::ReadOnly => "read_only",
+        }
+    }
+
+    // 画面に表示する名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Admin => "Admin",
+            Role::Manager => "Manager",
+            Role::Clerk => "Clerk",
+            Role::ReadOnly => "Read-only",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("unknown role: {:?}", s))
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// コマンドと画面の権限（role_permissions.permission の値）
+// ロールごとの権限は PostgreSQL の role_permissions で管理する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ViewUsers,
+    // ユーザーの作成・更新・削除
+    ManageUsers,
+    // ユーザーのロールの変更
+    ManageRoles,
+    ViewClients,
+    // 取引先の作成・更新・削除
+    ManageClients,
+    ViewProducts,
+    // 商品の作成・更新（価格の変更を含む）・削除
+    ManageProducts,
+    ViewOrders,
+    // 注文の作成・更新・削除・ステータスの変更
+    ManageOrders,
+    // 接続プロファイルとマイグレーションの状況
+    ManageSettings,
+    // 監査ログ（誰がいつ何を変更したか）
+    ViewAuditLog,
+    // 削除済みレコードの完全削除（保存期間を過ぎたものだけ）
+    PurgeRecords,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 12] = [
+        Permission::ViewUsers,
+        Permission::ManageUsers,
+        Permission::ManageRoles,
+        Permission::ViewClients,
+        Permission::ManageClients,
+        Permission::ViewProducts,
+        Permission::ManageProducts,
+        Permission::ViewOrders,
+        Permission::ManageOrders,
+        Permission::ManageSettings,
+        Permission::ViewAuditLog,
+        Permission::PurgeRecords,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::ViewUsers => "view_users",
+            Permission::ManageUsers => "manage_users",
+            Permission::ManageRoles => "manage_roles",
+            Permission::ViewClients => "view_clients",
+            Permission::ManageClients => "manage_clients",
+            Permission::ViewProducts => "view_products",
+            Permission::ManageProducts => "manage_products",
+            Permission::ViewOrders => "view_orders",
+            Permission::ManageOrders => "manage_orders",
+            Permission::ManageSettings => "manage_settings",
+            Permission::ViewAuditLog => "view_audit_log",
+            Permission::PurgeRecords => "purge_records",
+        }
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == s)
+            .ok_or_else(|| format!("unknown permission: {:?}", s))
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    User,
+    Client,
+    Product,
+    Order,
+}
+
+impl SearchKind {
+    pub const ALL: [SearchKind; 4] = [
+        SearchKind::User,
+        SearchKind::Client,
+        SearchKind::Product,
+        SearchKind::Order,
+    ];
+
+    // 検索結果の kind 列の値
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchKind::User => "user",
+            SearchKind::Client => "client",
+            SearchKind::Product => "product",
+            SearchKind::Order => "order",
+        }
+    }
+}
+
+// 検索結果の1件（rank の大きい順に並ぶ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub rank: f32,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for SearchHit {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let kind: String = row.try_get("kind")?;
+        let kind = match kind.as_str() {
+            "user" => SearchKind::User,
+            "client" => SearchKind::Client,
+            "product" => SearchKind::Product,
+            "order" => SearchKind::Order,
+            other => {
+                return Err(sqlx::Error::ColumnDecode {
+                    index: "kind".to_string(),
+                    source: format!("unknown search kind: {}", other).into(),
+                })
+            }
+        };
+        Ok(SearchHit {
+            kind,
+            id: row.try_get("id")?,
+            title: row.try_get("title")?,
+            subtitle: row.try_get("subtitle")?,
+            rank: row.try_get("rank")?,
+        })
+    }
+}
@@ -0,0 +1,280 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::query::ListQuery;
+
+// 入出庫の種類（stock_movements.movement_type の CHECK 制約と同じ値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StockMovementType {
+    // 仕入・入荷
+    #[default]
+    Receipt,
+    // 注文による出庫
+    Sale,
+    // 返品・キャンセルによる戻り
+    Return,
+    // 棚卸などによる調整（増減どちらも）
+    Adjustment,
+    // 保管場所の間の移動
+    Transfer,
+}
+
+impl StockMovementType {
+    pub const ALL: [StockMovementType; 5] = [
+        StockMovementType::Receipt,
+        StockMovementType::Sale,
+        StockMovementType::Return,
+        StockMovementType::Adjustment,
+        StockMovementType::Transfer,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockMovementType::Receipt => "receipt",
+            StockMovementType::Sale => "sale",
+            StockMovementType::Return => "return",
+            StockMovementType::Adjustment => "adjustment",
+            StockMovementType::Transfer => "transfer",
+        }
+    }
+
+    // 画面に表示する名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            StockMovementType::Receipt => "Receipt",
+            StockMovementType::Sale => "Sale",
+            StockMovementType::Return => "Return",
+            StockMovementType::Adjustment => "Adjustment",
+            StockMovementType::Transfer => "Transfer",
+        }
+    }
+
+    // 在庫調整（adjust_stock）で記録できる種類（販売は注文、移動は移動伝票から記録される）
+    pub fn is_manual(&self) -> bool {
+        matches!(
+            self,
+            StockMovementType::Receipt | StockMovementType::Return | StockMovementType::Adjustment
+        )
+    }
+}
+
+impl FromStr for StockMovementType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StockMovementType::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| format!("unknown stock movement type: {:?}", s))
+    }
+}
+
+impl fmt::Display for StockMovementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// 在庫の入出庫の記録（追記のみで、更新・削除はできない）
+// 商品とバリエーションの stock は、この記録の quantity の合計
+// 保管場所ごとの在庫数（StockLevel）は、保管場所ごとの合計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockMovement {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    // バリエーションのある商品ではバリエーション（バリエーションが削除されると None）
+    pub variant_id: Option<Uuid>,
+    pub warehouse_id: Uuid,
+    pub movement_type: StockMovementType,
+    // 入庫は正、出庫は負の数
+    pub quantity: i32,
+    pub reason: Option<String>,
+    // 納品書番号などの参照する書類
+    pub reference: Option<String>,
+    // 注文による入出庫の注文（注文が完全削除されると None）
+    pub order_id: Option<Uuid>,
+    // 移動伝票による入出庫の伝票（移動元の出庫と移動先の入庫の2件が同じ伝票になる）
+    pub transfer_id: Option<Uuid>,
+    // 記録したユーザー（ユーザーが削除されると None になるが、actor_name は残る）
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// movement_type は VARCHAR の列から読み込む
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StockMovement {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let movement_type: String = row.try_get("movement_type")?;
+        Ok(StockMovement {
+            id: row.try_get("id")?,
+            product_id: row.try_get("product_id")?,
+            variant_id: row.try_get("variant_id")?,
+            warehouse_id: row.try_get("warehouse_id")?,
+            movement_type: movement_type.parse().map_err(|e: String| {
+                sqlx::Error::ColumnDecode {
+                    index: "movement_type".to_string(),
+                    source: e.into(),
+                }
+            })?,
+            quantity: row.try_get("quantity")?,
+            reason: row.try_get("reason")?,
+            reference: row.try_get("reference")?,
+            order_id: row.try_get("order_id")?,
+            transfer_id: row.try_get("transfer_id")?,
+            actor_id: row.try_get("actor_id")?,
+            actor_name: row.try_get("actor_name")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+// 在庫の入庫・返品・調整を記録する（quantity は増やすなら正、減らすなら負の数）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdjustStock {
+    pub product_id: Uuid,
+    // バリエーションのある商品では必須
+    pub variant_id: Option<Uuid>,
+    // None なら既定の保管場所
+    #[serde(default)]
+    pub warehouse_id: Option<Uuid>,
+    pub movement_type: StockMovementType,
+    pub quantity: i32,
+    // 調整（adjustment）では必須
+    pub reason: Option<String>,
+    pub reference: Option<String>,
+}
+
+// 在庫の調整の明細（quantity は増やすなら正、減らすなら負の数）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdjustStockItem {
+    pub product_id: Uuid,
+    // バリエーションのある商品では必須
+    #[serde(default)]
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+}
+
+// 在庫の調整をまとめて記録する（バリエーションの表での一括編集）
+// 明細ごとに同じ保管場所・理由の調整（adjustment）を記録し、1つでも在庫が足りなければ何も記録しない
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdjustStockBatch {
+    // None なら既定の保管場所
+    #[serde(default)]
+    pub warehouse_id: Option<Uuid>,
+    // 必須
+    pub reason: Option<String>,
+    pub reference: Option<String>,
+    pub items: Vec<AdjustStockItem>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StockMovementSort {
+    #[default]
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StockMovementFilter {
+    pub product_id: Option<Uuid>,
+    pub variant_id: Option<Uuid>,
+    pub warehouse_id: Option<Uuid>,
+    pub movement_type: Option<StockMovementType>,
+    pub order_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+}
+
+pub type StockMovementQuery = ListQuery<StockMovementSort, StockMovementFilter>;
+
+// 在庫数と入出庫の記録の合計が一致しない商品・バリエーション（保管場所ごとの在庫数も含む）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockDiscrepancy {
+    pub product_id: Uuid,
+    pub variant_id: Option<Uuid>,
+    // 商品名（バリエーションは「商品名 (S / Red)」、保管場所ごとの在庫数は「商品名 at 保管場所」）
+    pub name: String,
+    pub stock: i32,
+    pub ledger_stock: i64,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StockDiscrepancy {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(StockDiscrepancy {
+            product_id: row.try_get("product_id")?,
+            variant_id: row.try_get("variant_id")?,
+            name: row.try_get("name")?,
+            stock: row.try_get("stock")?,
+            ledger_stock: row.try_get("ledger_stock")?,
+        })
+    }
+}
+
+// 保管場所ごとの在庫数（バリエーションのある商品はバリエーションごと）
+// 入出庫の記録のない保管場所の行はない（在庫 0 として扱う）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockLevel {
+    pub warehouse_id: Uuid,
+    pub product_id: Uuid,
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for StockLevel {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(StockLevel {
+            warehouse_id: row.try_get("warehouse_id")?,
+            product_id: row.try_get("product_id")?,
+            variant_id: row.try_get("variant_id")?,
+            quantity: row.try_get("quantity")?,
+        })
+    }
+}
+
+// 移動伝票の明細（移動する数量は正の数）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StockTransferItem {
+    pub product_id: Uuid,
+    // バリエーションのある商品では必須
+    #[serde(default)]
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+}
+
+// 保管場所の間の移動伝票（明細ごとに移動元の出庫と移動先の入庫を記録する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockTransfer {
+    pub id: Uuid,
+    pub from_warehouse_id: Uuid,
+    pub to_warehouse_id: Uuid,
+    pub reference: Option<String>,
+    pub note: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub items: Vec<StockTransferItem>,
+}
+
+// 移動伝票を作成する（すべての明細を1つのトランザクションで移動する）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateStockTransfer {
+    pub from_warehouse_id: Uuid,
+    pub to_warehouse_id: Uuid,
+    pub reference: Option<String>,
+    pub note: Option<String>,
+    pub items: Vec<StockTransferItem>,
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::query::ListQuery;
+use crate::roles::Role;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: Role,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // 削除された日時（論理削除。削除されていなければ None）
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+// role は VARCHAR の列から読み込む
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for User {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let role: String = row.try_get("role")?;
+        Ok(User {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            email: row.try_get("email")?,
+            role: role.parse().map_err(|e: String| sqlx::Error::ColumnDecode {
+                index: "role".to_string(),
+                source: e.into(),
+            })?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateUser {
+    pub name: String,
+    pub email: String,
+    // None ならログインできないユーザーとして作成する
+    #[serde(default)]
+    pub password: Option<String>,
+    // None なら read_only（ロールの指定には manage_roles の権限が必要）
+    #[serde(default)]
+    pub role: Option<Role>,
+}
+
+// 更新用の構造体（None のフィールドは変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateUser {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    // 指定したときだけパスワードを変更する
+    #[serde(default)]
+    pub password: Option<String>,
+    // ロールの変更には manage_roles の権限が必要
+    #[serde(default)]
+    pub role: Option<Role>,
+}
+
+// 一覧の並び替え列とフィルター
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    Name,
+    Email,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserFilter {
+    // 指定した ID のレコードだけを返す（検索結果からの移動に使う）
+    pub ids: Option<Vec<Uuid>>,
+    // 名前・メールアドレスの部分一致
+    pub search: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    // 削除されたレコードも返す（既定では除外する）
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+pub type UserQuery = ListQuery<UserSort, UserFilter>;
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::money::Money;
+use crate::query::double_option;
+
+// 1つの商品に定義できるオプションの数（サイズ × カラーの表で編集する）
+pub const MAX_PRODUCT_OPTIONS: usize = 2;
+
+// 商品のオプション（例: Size = S, M, L）。values の順に表の行・列を並べる
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductOption {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    // 0 から始まるオプションの順番（バリエーションの option_values と同じ順）
+    pub position: i32,
+    pub values: Vec<String>,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ProductOption {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(ProductOption {
+            id: row.try_get("id")?,
+            product_id: row.try_get("product_id")?,
+            name: row.try_get("name")?,
+            position: row.try_get("position")?,
+            values: row.try_get("option_values")?,
+        })
+    }
+}
+
+// オプションの値の組み合わせごとのバリエーション（例: M / Red）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductVariant {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub sku: String,
+    // オプションの position の順の値
+    pub option_values: Vec<String>,
+    // None なら商品の価格で販売する（通貨は商品と同じ）
+    pub price: Option<Money>,
+    pub stock: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProductVariant {
+    // 画面に表示する組み合わせの名前（例: "M / Red"）
+    pub fn label(&self) -> String {
+        self.option_values.join(" / ")
+    }
+
+    // 注文の単価（価格の指定がなければ商品の価格）
+    pub fn effective_price(&self, product_price: Money) -> Money {
+        self.price.unwrap_or(product_price)
+    }
+}
+
+// 価格は price と商品の currency の列から組み立てる
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for ProductVariant {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let price: Option<Decimal> = row.try_get("price")?;
+        Ok(ProductVariant {
+            id: row.try_get("id")?,
+            product_id: row.try_get("product_id")?,
+            sku: row.try_get("sku")?,
+            option_values: row.try_get("option_values")?,
+            price: match price {
+                Some(_) => Some(Money::from_row(row, "price", "currency")?),
+                None => None,
+            },
+            stock: row.try_get("stock")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+// 商品のオプションとバリエーション（バリエーションはオプションの値の順）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProductVariants {
+    pub options: Vec<ProductOption>,
+    pub variants: Vec<ProductVariant>,
+}
+
+impl ProductVariants {
+    // オプションの値の組み合わせに対応するバリエーション
+    pub fn find(&self, option_values: &[&str]) -> Option<&ProductVariant> {
+        self.variants.iter().find(|v| {
+            v.option_values.len() == option_values.len()
+                && v.option_values.iter().zip(option_values).all(|(a, b)| a == b)
+        })
+    }
+}
+
+// set_product_options で指定するオプション（リストの順が position になる）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetProductOption {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+// バリエーションをまとめて更新する（None のフィールドは変更しない。在庫は adjust_stock_batch で調整として記録する）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProductVariant {
+    pub id: Uuid,
+    pub sku: Option<String>,
+    // Some(None) で商品の価格に戻す（金額は商品と同じ通貨）
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub price: Option<Option<Decimal>>,
+}
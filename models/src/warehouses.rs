@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::query::double_option;
+
+// 在庫の保管場所（倉庫）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warehouse {
+    pub id: Uuid,
+    pub name: String,
+    pub address: Option<String>,
+    // 保管場所を指定しない入出庫と注文に使う保管場所（常に1つだけ）
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for Warehouse {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Warehouse {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            address: row.try_get("address")?,
+            is_default: row.try_get("is_default")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateWarehouse {
+    pub name: String,
+    pub address: Option<String>,
+    // true なら既定の保管場所をこの保管場所に切り替える
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+// 更新用の構造体（None のフィールドは変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateWarehouse {
+    pub name: Option<String>,
+    // Some(None) で住所を削除する
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub address: Option<Option<String>>,
+    // Some(true) で既定の保管場所にする（既定を外すには別の保管場所を既定にする）
+    pub is_default: Option<bool>,
+}
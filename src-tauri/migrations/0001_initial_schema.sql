@@ -1,8 +1,5 @@
--- PostgreSQL初期化スクリプト
--- このファイルはPostgreSQLコンテナ起動時に自動実行されます
-
--- データベースの設定
-SET timezone = 'UTC';
+-- 初期スキーマ
+-- 既存の Database::init で作成されたテーブルがあっても適用できるよう冪等に記述する
 
 -- 拡張機能を有効化
 CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
@@ -45,20 +42,6 @@ CREATE INDEX IF NOT EXISTS idx_orders_user_id ON orders(user_id);
 CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
 CREATE INDEX IF NOT EXISTS idx_orders_created_at ON orders(created_at);
 
--- サンプルデータを挿入
-INSERT INTO users (name, email) VALUES 
-    ('Alice Johnson', 'alice@example.com'),
-    ('Bob Smith', 'bob@example.com'),
-    ('Carol Davis', 'carol@example.com')
-ON CONFLICT (email) DO NOTHING;
-
-INSERT INTO products (name, description, price, stock) VALUES 
-    ('Laptop Pro', 'High-performance laptop for professionals', 1299.99, 50),
-    ('Wireless Mouse', 'Ergonomic wireless mouse', 29.99, 200),
-    ('Mechanical Keyboard', 'RGB mechanical keyboard', 149.99, 75),
-    ('Monitor 4K', '27-inch 4K monitor', 399.99, 30)
-ON CONFLICT DO NOTHING;
-
 -- 更新日時を自動更新するトリガー関数
 CREATE OR REPLACE FUNCTION update_updated_at_column()
 RETURNS TRIGGER AS $$
@@ -70,23 +53,16 @@ $$ language 'plpgsql';
 
 -- トリガーを作成
 DROP TRIGGER IF EXISTS update_users_updated_at ON users;
-CREATE TRIGGER update_users_updated_at 
-    BEFORE UPDATE ON users 
+CREATE TRIGGER update_users_updated_at
+    BEFORE UPDATE ON users
     FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
 
 DROP TRIGGER IF EXISTS update_products_updated_at ON products;
-CREATE TRIGGER update_products_updated_at 
-    BEFORE UPDATE ON products 
+CREATE TRIGGER update_products_updated_at
+    BEFORE UPDATE ON products
     FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
 
 DROP TRIGGER IF EXISTS update_orders_updated_at ON orders;
-CREATE TRIGGER update_orders_updated_at 
-    BEFORE UPDATE ON orders 
+CREATE TRIGGER update_orders_updated_at
+    BEFORE UPDATE ON orders
     FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
-
--- 完了メッセージ
-DO $$
-BEGIN
-    RAISE NOTICE 'Database initialization completed successfully!';
-    RAISE NOTICE 'Sample data has been inserted.';
-END $$;
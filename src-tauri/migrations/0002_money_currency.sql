@@ -0,0 +1,8 @@
+-- 金額に通貨コードを持たせる
+-- 既存の金額はすべて日本円として扱う
+
+ALTER TABLE products
+    ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'JPY' CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE orders
+    ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'JPY' CHECK (currency ~ '^[A-Z]{3}$');
@@ -0,0 +1,17 @@
+-- 注文明細テーブル
+-- 単価は注文時点の products.price を記録し、後から商品価格が変わっても注文金額は変わらない
+
+CREATE TABLE order_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES products(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    unit_price DECIMAL(10,2) NOT NULL CHECK (unit_price >= 0),
+    discount DECIMAL(10,2) NOT NULL DEFAULT 0 CHECK (discount >= 0),
+    currency CHAR(3) NOT NULL DEFAULT 'JPY' CHECK (currency ~ '^[A-Z]{3}$'),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    CHECK (discount <= unit_price * quantity)
+);
+
+CREATE INDEX idx_order_items_order_id ON order_items(order_id);
+CREATE INDEX idx_order_items_product_id ON order_items(product_id);
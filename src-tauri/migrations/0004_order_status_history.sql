@@ -0,0 +1,24 @@
+-- 注文ステータスの制約と変更履歴
+-- 旧 Database::init で作成されたテーブルには CHECK 制約がないため、ここで付け直す
+
+UPDATE orders SET status = 'pending' WHERE status IS NULL;
+ALTER TABLE orders ALTER COLUMN status SET NOT NULL;
+ALTER TABLE orders DROP CONSTRAINT IF EXISTS orders_status_check;
+ALTER TABLE orders ADD CONSTRAINT orders_status_check
+    CHECK (status IN ('pending', 'processing', 'shipped', 'delivered', 'cancelled'));
+
+CREATE TABLE order_status_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    from_status VARCHAR(50) CHECK (from_status IN ('pending', 'processing', 'shipped', 'delivered', 'cancelled')),
+    to_status VARCHAR(50) NOT NULL CHECK (to_status IN ('pending', 'processing', 'shipped', 'delivered', 'cancelled')),
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    note TEXT,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_order_status_history_order_id ON order_status_history(order_id, changed_at);
+
+-- 既存の注文は現在のステータスを履歴の起点とする
+INSERT INTO order_status_history (order_id, from_status, to_status, changed_at)
+SELECT id, NULL, status, COALESCE(created_at, NOW()) FROM orders;
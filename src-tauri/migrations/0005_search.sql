@@ -0,0 +1,27 @@
+-- 全文検索とあいまい検索
+-- 日本語の名前も扱うため、語幹処理をしない 'simple' 設定で tsvector を作る
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE users ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(email, '')), 'B')
+    ) STORED;
+
+ALTER TABLE products ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'C')
+    ) STORED;
+
+CREATE INDEX idx_users_search_vector ON users USING GIN (search_vector);
+CREATE INDEX idx_products_search_vector ON products USING GIN (search_vector);
+
+-- 部分一致・表記ゆれ用のトライグラムインデックス（ILIKE でも使われる）
+CREATE INDEX idx_users_name_trgm ON users USING GIN (name gin_trgm_ops);
+CREATE INDEX idx_users_email_trgm ON users USING GIN (email gin_trgm_ops);
+CREATE INDEX idx_products_name_trgm ON products USING GIN (name gin_trgm_ops);
+
+-- 画面に表示している注文番号（UUID の先頭8文字）での前方一致検索用
+CREATE INDEX idx_orders_id_text ON orders ((id::text) text_pattern_ops);
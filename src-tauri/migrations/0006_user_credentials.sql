@@ -0,0 +1,4 @@
+-- ログイン用のパスワード
+-- argon2 の PHC 文字列（$argon2id$v=19$...）を保存する。NULL のユーザーはログインできない
+
+ALTER TABLE users ADD COLUMN password_hash TEXT;
@@ -0,0 +1,53 @@
+-- ロールと権限
+-- 権限の値は ws_models::Permission と同じ。ロールごとの権限は role_permissions を変更すれば変えられる
+
+CREATE TABLE roles (
+    name VARCHAR(20) PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+INSERT INTO roles (name, description) VALUES
+    ('admin', 'All operations including user roles and connection settings'),
+    ('manager', 'Manage users, products (including prices) and orders'),
+    ('clerk', 'View data and create or process orders'),
+    ('read_only', 'View data only');
+
+CREATE TABLE role_permissions (
+    role VARCHAR(20) NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission VARCHAR(50) NOT NULL CHECK (permission IN (
+        'view_users', 'manage_users', 'manage_roles',
+        'view_products', 'manage_products',
+        'view_orders', 'manage_orders',
+        'manage_settings'
+    )),
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'view_users'),
+    ('admin', 'manage_users'),
+    ('admin', 'manage_roles'),
+    ('admin', 'view_products'),
+    ('admin', 'manage_products'),
+    ('admin', 'view_orders'),
+    ('admin', 'manage_orders'),
+    ('admin', 'manage_settings'),
+    ('manager', 'view_users'),
+    ('manager', 'manage_users'),
+    ('manager', 'view_products'),
+    ('manager', 'manage_products'),
+    ('manager', 'view_orders'),
+    ('manager', 'manage_orders'),
+    ('clerk', 'view_users'),
+    ('clerk', 'view_products'),
+    ('clerk', 'view_orders'),
+    ('clerk', 'manage_orders'),
+    ('read_only', 'view_users'),
+    ('read_only', 'view_products'),
+    ('read_only', 'view_orders');
+
+ALTER TABLE users ADD COLUMN role VARCHAR(20) NOT NULL DEFAULT 'read_only' REFERENCES roles(name);
+CREATE INDEX idx_users_role ON users(role);
+
+-- すでにログインできるユーザーが操作できなくならないよう admin にする
+UPDATE users SET role = 'admin' WHERE password_hash IS NOT NULL;
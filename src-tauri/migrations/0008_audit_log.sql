@@ -0,0 +1,35 @@
+-- 監査ログ（ユーザー・商品・注文の作成・更新・削除）
+-- changes は変更されたフィールドごとの {"before": ..., "after": ...}（作成時の before、削除時の after は null）
+-- 対象のレコードが削除されても残るよう、entity_id には外部キーを付けない
+
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- 操作したユーザーが削除されても誰の操作か分かるよう名前も残す
+    actor_name VARCHAR(255),
+    entity VARCHAR(20) NOT NULL CHECK (entity IN ('user', 'product', 'order')),
+    entity_id UUID NOT NULL,
+    action VARCHAR(20) NOT NULL CHECK (action IN ('create', 'update', 'delete')),
+    changes JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_entity ON audit_log(entity, entity_id, created_at);
+CREATE INDEX idx_audit_log_actor_id ON audit_log(actor_id, created_at);
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+-- 「注文の合計金額を変更したのは誰か」のような、フィールドでの絞り込み用
+CREATE INDEX idx_audit_log_changes ON audit_log USING GIN (changes);
+
+-- 監査ログの閲覧権限
+ALTER TABLE role_permissions DROP CONSTRAINT role_permissions_permission_check;
+ALTER TABLE role_permissions ADD CONSTRAINT role_permissions_permission_check CHECK (permission IN (
+    'view_users', 'manage_users', 'manage_roles',
+    'view_products', 'manage_products',
+    'view_orders', 'manage_orders',
+    'manage_settings',
+    'view_audit_log'
+));
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'view_audit_log'),
+    ('manager', 'view_audit_log');
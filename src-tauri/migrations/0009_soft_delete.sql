@@ -0,0 +1,35 @@
+-- 論理削除
+-- ユーザー・商品・注文の削除は deleted_at を設定するだけにし、一覧・検索では既定で除外する
+-- 行の削除（完全削除）は purge_deleted コマンドで、保存期間を過ぎたものだけを行う
+
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE products ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE orders ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_products_deleted_at ON products(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_orders_deleted_at ON orders(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- 注文のあるユーザーの行を削除すると注文の履歴から顧客が分からなくなるため、削除できないようにする
+ALTER TABLE orders DROP CONSTRAINT orders_user_id_fkey;
+ALTER TABLE orders ADD CONSTRAINT orders_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT;
+
+-- 監査ログの操作に論理削除の取り消しと完全削除を追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_action_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_action_check
+    CHECK (action IN ('create', 'update', 'delete', 'restore', 'purge'));
+
+-- 完全削除の権限（admin のみ）
+ALTER TABLE role_permissions DROP CONSTRAINT role_permissions_permission_check;
+ALTER TABLE role_permissions ADD CONSTRAINT role_permissions_permission_check CHECK (permission IN (
+    'view_users', 'manage_users', 'manage_roles',
+    'view_products', 'manage_products',
+    'view_orders', 'manage_orders',
+    'manage_settings',
+    'view_audit_log',
+    'purge_records'
+));
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'purge_records');
@@ -0,0 +1,73 @@
+-- 取引先（注文の顧客）
+-- これまで注文の顧客は users（ログインするユーザー）を参照していたが、顧客は取引先として別に管理する
+
+CREATE TABLE clients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    company_name VARCHAR(255) NOT NULL,
+    contact_name VARCHAR(255),
+    email VARCHAR(255),
+    phone VARCHAR(50),
+    billing_address TEXT,
+    shipping_address TEXT,
+    tax_id VARCHAR(50),
+    payment_terms VARCHAR(30) NOT NULL DEFAULT 'prepaid'
+        CHECK (payment_terms IN ('prepaid', 'cash_on_delivery', 'net_30', 'end_of_next_month')),
+    notes TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(company_name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(contact_name, '')), 'B') ||
+        setweight(to_tsvector('simple', coalesce(email, '')), 'B')
+    ) STORED
+);
+
+CREATE INDEX idx_clients_deleted_at ON clients(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_clients_search_vector ON clients USING GIN (search_vector);
+CREATE INDEX idx_clients_company_name_trgm ON clients USING GIN (company_name gin_trgm_ops);
+CREATE INDEX idx_clients_contact_name_trgm ON clients USING GIN (contact_name gin_trgm_ops);
+
+CREATE TRIGGER update_clients_updated_at
+    BEFORE UPDATE ON clients
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- 注文の顧客を users から clients に移す
+-- 注文のあるユーザーごとに同じ ID の取引先を作り、orders.client_id に付け替える
+ALTER TABLE orders ADD COLUMN client_id UUID REFERENCES clients(id) ON DELETE RESTRICT;
+
+INSERT INTO clients (id, company_name, contact_name, email, created_at)
+SELECT u.id, u.name, u.name, u.email, u.created_at
+FROM users u
+WHERE EXISTS (SELECT 1 FROM orders o WHERE o.user_id = u.id);
+
+UPDATE orders SET client_id = user_id WHERE user_id IS NOT NULL;
+
+ALTER TABLE orders DROP COLUMN user_id;
+CREATE INDEX idx_orders_client_id ON orders(client_id);
+
+-- 監査ログの対象に取引先を追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_entity_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_entity_check
+    CHECK (entity IN ('user', 'client', 'product', 'order'));
+
+-- 取引先の権限（閲覧は全員、変更は注文を作成できるロール）
+ALTER TABLE role_permissions DROP CONSTRAINT role_permissions_permission_check;
+ALTER TABLE role_permissions ADD CONSTRAINT role_permissions_permission_check CHECK (permission IN (
+    'view_users', 'manage_users', 'manage_roles',
+    'view_clients', 'manage_clients',
+    'view_products', 'manage_products',
+    'view_orders', 'manage_orders',
+    'manage_settings',
+    'view_audit_log',
+    'purge_records'
+));
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'view_clients'),
+    ('admin', 'manage_clients'),
+    ('manager', 'view_clients'),
+    ('manager', 'manage_clients'),
+    ('clerk', 'view_clients'),
+    ('clerk', 'manage_clients'),
+    ('read_only', 'view_clients');
@@ -0,0 +1,65 @@
+-- 取引先の住所（1つの取引先に複数）と、注文時点の請求先・配送先のスナップショット
+
+CREATE TABLE client_addresses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    label VARCHAR(100),
+    recipient VARCHAR(255),
+    postal_code VARCHAR(20) NOT NULL DEFAULT '',
+    region VARCHAR(100) NOT NULL DEFAULT '',
+    city VARCHAR(100) NOT NULL DEFAULT '',
+    line1 VARCHAR(255) NOT NULL,
+    line2 VARCHAR(255),
+    country CHAR(2) NOT NULL DEFAULT 'JP' CHECK (country ~ '^[A-Z]{2}$'),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_client_addresses_client_id ON client_addresses(client_id);
+
+CREATE TRIGGER update_client_addresses_updated_at
+    BEFORE UPDATE ON client_addresses
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- 既定の請求先・配送先
+ALTER TABLE clients
+    ADD COLUMN default_billing_address_id UUID REFERENCES client_addresses(id) ON DELETE SET NULL,
+    ADD COLUMN default_shipping_address_id UUID REFERENCES client_addresses(id) ON DELETE SET NULL;
+
+-- これまでの自由記述の住所は line1 に移し、既定の請求先・配送先にする
+-- 請求先と配送先が同じ内容なら1件の住所を両方に使う
+-- 郵便番号などは空のまま残るため、編集時に入力し直す
+INSERT INTO client_addresses (client_id, label, line1)
+SELECT id, 'Billing', billing_address
+FROM clients
+WHERE billing_address IS NOT NULL AND billing_address <> '';
+
+INSERT INTO client_addresses (client_id, label, line1)
+SELECT id, 'Shipping', shipping_address
+FROM clients
+WHERE shipping_address IS NOT NULL AND shipping_address <> ''
+  AND shipping_address IS DISTINCT FROM billing_address;
+
+UPDATE clients c
+SET default_billing_address_id = (
+        SELECT a.id FROM client_addresses a
+        WHERE a.client_id = c.id AND a.line1 = c.billing_address
+        ORDER BY a.label LIMIT 1
+    ),
+    default_shipping_address_id = (
+        SELECT a.id FROM client_addresses a
+        WHERE a.client_id = c.id AND a.line1 = c.shipping_address
+        ORDER BY a.label DESC LIMIT 1
+    );
+
+ALTER TABLE clients DROP COLUMN billing_address, DROP COLUMN shipping_address;
+
+-- 注文時点の住所（取引先の住所を後から変更・削除しても過去の注文は変わらない）
+ALTER TABLE orders
+    ADD COLUMN billing_address JSONB,
+    ADD COLUMN shipping_address JSONB;
+
+-- 監査ログの対象に住所を追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_entity_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_entity_check
+    CHECK (entity IN ('user', 'client', 'address', 'product', 'order'));
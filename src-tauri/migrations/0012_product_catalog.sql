@@ -0,0 +1,47 @@
+-- 商品カタログ: カテゴリの階層、SKU・バーコード・単位・取扱終了・発注点
+
+CREATE TABLE product_categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- 下位のカテゴリや商品があるカテゴリは削除できない
+    parent_id UUID REFERENCES product_categories(id) ON DELETE RESTRICT,
+    name VARCHAR(100) NOT NULL CHECK (btrim(name) <> ''),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    CHECK (parent_id <> id)
+);
+
+-- 同じ親の下に同じ名前のカテゴリは作れない（大文字・小文字は区別しない）
+CREATE UNIQUE INDEX product_categories_name_key ON product_categories (
+    COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'::uuid), lower(name)
+);
+CREATE INDEX idx_product_categories_parent_id ON product_categories(parent_id);
+
+CREATE TRIGGER update_product_categories_updated_at
+    BEFORE UPDATE ON product_categories
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE products
+    ADD COLUMN sku VARCHAR(64),
+    ADD COLUMN barcode VARCHAR(13) CHECK (barcode ~ '^([0-9]{8}|[0-9]{12}|[0-9]{13})$'),
+    ADD COLUMN unit VARCHAR(20) NOT NULL DEFAULT 'piece'
+        CHECK (unit IN ('piece', 'box', 'pack', 'set', 'pair', 'kilogram', 'meter', 'liter')),
+    ADD COLUMN category_id UUID REFERENCES product_categories(id) ON DELETE RESTRICT,
+    ADD COLUMN reorder_point INTEGER CHECK (reorder_point >= 0),
+    ADD COLUMN discontinued BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 既存の商品には ID から仮の SKU を付ける（画面から変更できる）
+UPDATE products SET sku = 'SKU-' || upper(left(replace(id::text, '-', ''), 8));
+ALTER TABLE products ALTER COLUMN sku SET NOT NULL;
+
+-- SKU とバーコードは削除されていない商品の間で一意
+-- 制約名を <テーブル>_<列>_key にして、重複エラーを入力欄のエラーとして返せるようにする
+CREATE UNIQUE INDEX products_sku_key ON products(sku) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX products_barcode_key ON products(barcode)
+    WHERE barcode IS NOT NULL AND deleted_at IS NULL;
+CREATE INDEX idx_products_category_id ON products(category_id);
+CREATE INDEX idx_products_sku_trgm ON products USING GIN (sku gin_trgm_ops);
+
+-- 監査ログの対象にカテゴリを追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_entity_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_entity_check
+    CHECK (entity IN ('user', 'client', 'address', 'category', 'product', 'order'));
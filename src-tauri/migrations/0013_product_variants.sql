@@ -0,0 +1,44 @@
+-- 商品のオプション（サイズ・カラーなど）とバリエーション
+
+CREATE TABLE product_options (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL CHECK (btrim(name) <> ''),
+    position INTEGER NOT NULL CHECK (position >= 0),
+    -- 表の行・列に並べる順の値
+    option_values TEXT[] NOT NULL CHECK (cardinality(option_values) > 0),
+    UNIQUE (product_id, position)
+);
+
+CREATE TABLE product_variants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    sku VARCHAR(64) NOT NULL,
+    -- オプションの position の順の値
+    option_values TEXT[] NOT NULL,
+    -- NULL なら商品の価格（通貨は商品の currency）
+    price DECIMAL(10,2) CHECK (price >= 0),
+    stock INTEGER NOT NULL DEFAULT 0 CHECK (stock >= 0),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (product_id, option_values),
+    -- 制約名を <テーブル>_<列>_key にして、重複エラーを入力欄のエラーとして返せるようにする
+    -- 表の一括編集で SKU を入れ替えられるよう、重複の確認はコミット時に行う
+    CONSTRAINT product_variants_sku_key UNIQUE (sku) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX idx_product_variants_product_id ON product_variants(product_id);
+
+CREATE TRIGGER update_product_variants_updated_at
+    BEFORE UPDATE ON product_variants
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- 注文明細のバリエーション（注文に使われたバリエーションは削除できない）
+ALTER TABLE order_items
+    ADD COLUMN variant_id UUID REFERENCES product_variants(id) ON DELETE RESTRICT;
+CREATE INDEX idx_order_items_variant_id ON order_items(variant_id);
+
+-- 監査ログの対象にバリエーションを追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_entity_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_entity_check
+    CHECK (entity IN ('user', 'client', 'address', 'category', 'product', 'variant', 'order'));
@@ -0,0 +1,72 @@
+-- 在庫の入出庫の記録（追記のみ）
+-- products.stock と product_variants.stock は、この記録の quantity の合計をトリガーで保持する
+
+CREATE TABLE stock_movements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- 完全削除された商品の記録は商品と一緒に削除する
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    -- 削除されたバリエーション（在庫 0 のものだけ削除できる）の記録は商品の記録として残す
+    variant_id UUID REFERENCES product_variants(id) ON DELETE SET NULL,
+    movement_type VARCHAR(20) NOT NULL
+        CHECK (movement_type IN ('receipt', 'sale', 'return', 'adjustment', 'transfer')),
+    -- 入庫は正、出庫は負の数
+    quantity INTEGER NOT NULL CHECK (quantity <> 0),
+    reason TEXT,
+    -- 納品書番号などの参照する書類
+    reference VARCHAR(100),
+    order_id UUID REFERENCES orders(id) ON DELETE SET NULL,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- 記録したユーザーが削除されても誰の操作か分かるよう名前も残す
+    actor_name VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_stock_movements_product_id ON stock_movements(product_id, created_at);
+CREATE INDEX idx_stock_movements_variant_id ON stock_movements(variant_id) WHERE variant_id IS NOT NULL;
+CREATE INDEX idx_stock_movements_order_id ON stock_movements(order_id) WHERE order_id IS NOT NULL;
+
+-- これまでの在庫数を最初の記録にする（トリガーを作る前に入れて、在庫数を二重に増やさない）
+INSERT INTO stock_movements (product_id, variant_id, movement_type, quantity, reason)
+SELECT v.product_id, v.id, 'adjustment', v.stock, 'Opening balance'
+FROM product_variants v
+WHERE v.stock > 0;
+
+INSERT INTO stock_movements (product_id, movement_type, quantity, reason)
+SELECT p.id, 'adjustment', p.stock, 'Opening balance'
+FROM products p
+WHERE p.stock > 0
+  AND NOT EXISTS (SELECT 1 FROM product_variants v WHERE v.product_id = p.id);
+
+-- 記録を追加したら商品（とバリエーション）の在庫数に反映する
+-- 在庫が足りなければ stock の CHECK 制約で記録ごとエラーになる
+CREATE OR REPLACE FUNCTION apply_stock_movement()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE products SET stock = stock + NEW.quantity WHERE id = NEW.product_id;
+    IF NEW.variant_id IS NOT NULL THEN
+        UPDATE product_variants SET stock = stock + NEW.quantity WHERE id = NEW.variant_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER apply_stock_movement
+    AFTER INSERT ON stock_movements
+    FOR EACH ROW EXECUTE FUNCTION apply_stock_movement();
+
+-- 記録の更新・削除を禁止する
+-- 外部キーの ON DELETE（商品の完全削除など）による変更はトリガーの中から実行されるため許可する
+CREATE OR REPLACE FUNCTION reject_stock_movement_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF pg_trigger_depth() > 1 THEN
+        RETURN CASE WHEN TG_OP = 'DELETE' THEN OLD ELSE NEW END;
+    END IF;
+    RAISE EXCEPTION 'stock_movements is append-only'
+        USING ERRCODE = 'restrict_violation';
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER reject_stock_movement_change
+    BEFORE UPDATE OR DELETE ON stock_movements
+    FOR EACH ROW EXECUTE FUNCTION reject_stock_movement_change();
@@ -0,0 +1,110 @@
+-- 保管場所（倉庫）と保管場所ごとの在庫、保管場所の間の移動伝票
+-- products.stock と product_variants.stock はすべての保管場所の合計のまま残す
+
+CREATE TABLE warehouses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL CHECK (btrim(name) <> ''),
+    address TEXT,
+    -- 保管場所を指定しない入出庫（最初の在庫など）と注文に使う
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    CONSTRAINT warehouses_name_key UNIQUE (name)
+);
+
+-- 既定の保管場所は1つだけ
+CREATE UNIQUE INDEX warehouses_is_default_key ON warehouses(is_default) WHERE is_default;
+
+CREATE TRIGGER update_warehouses_updated_at
+    BEFORE UPDATE ON warehouses
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- これまでの在庫はすべてこの保管場所にあるものとする
+INSERT INTO warehouses (name, is_default) VALUES ('Main warehouse', TRUE);
+
+-- 移動伝票（明細は transfer_id の付いた入出庫の記録で、移動元の出庫と移動先の入庫の組）
+CREATE TABLE stock_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_warehouse_id UUID NOT NULL REFERENCES warehouses(id) ON DELETE RESTRICT,
+    to_warehouse_id UUID NOT NULL REFERENCES warehouses(id) ON DELETE RESTRICT,
+    reference VARCHAR(100),
+    note TEXT,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    actor_name VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (from_warehouse_id <> to_warehouse_id)
+);
+
+CREATE INDEX idx_stock_transfers_created_at ON stock_transfers(created_at);
+
+-- 入出庫の記録に保管場所と移動伝票を追加する
+ALTER TABLE stock_movements
+    ADD COLUMN warehouse_id UUID REFERENCES warehouses(id) ON DELETE RESTRICT,
+    ADD COLUMN transfer_id UUID REFERENCES stock_transfers(id) ON DELETE RESTRICT;
+
+-- 追記のみのトリガーを止めて、これまでの記録を既定の保管場所の記録にする
+ALTER TABLE stock_movements DISABLE TRIGGER USER;
+UPDATE stock_movements SET warehouse_id = (SELECT id FROM warehouses WHERE is_default);
+ALTER TABLE stock_movements ENABLE TRIGGER USER;
+ALTER TABLE stock_movements ALTER COLUMN warehouse_id SET NOT NULL;
+
+CREATE INDEX idx_stock_movements_warehouse_id ON stock_movements(warehouse_id, product_id);
+CREATE INDEX idx_stock_movements_transfer_id ON stock_movements(transfer_id) WHERE transfer_id IS NOT NULL;
+
+-- 保管場所ごとの在庫数（入出庫の記録の quantity の保管場所ごとの合計をトリガーで保持する）
+-- バリエーションのある商品はバリエーションごと、ない商品は variant_id が NULL の行
+CREATE TABLE stock_levels (
+    warehouse_id UUID NOT NULL REFERENCES warehouses(id) ON DELETE RESTRICT,
+    product_id UUID NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    variant_id UUID REFERENCES product_variants(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL DEFAULT 0 CHECK (quantity >= 0)
+);
+
+-- 保管場所と商品（とバリエーション）ごとに1行
+-- UNIQUE NULLS NOT DISTINCT は PostgreSQL 15 以降でしか使えないため、variant_id の有無で部分インデックスを分ける
+CREATE UNIQUE INDEX stock_levels_product_id_warehouse_id_key
+    ON stock_levels(product_id, warehouse_id) WHERE variant_id IS NULL;
+CREATE UNIQUE INDEX stock_levels_product_id_variant_id_warehouse_id_key
+    ON stock_levels(product_id, variant_id, warehouse_id) WHERE variant_id IS NOT NULL;
+
+CREATE INDEX idx_stock_levels_product_id ON stock_levels(product_id);
+
+INSERT INTO stock_levels (warehouse_id, product_id, variant_id, quantity)
+SELECT warehouse_id, product_id, variant_id, SUM(quantity)
+FROM stock_movements
+GROUP BY warehouse_id, product_id, variant_id;
+
+-- 記録を追加したら商品（とバリエーション）の在庫数と、保管場所ごとの在庫数に反映する
+-- 保管場所の在庫が足りなければ stock_levels の CHECK 制約で記録ごとエラーになる
+-- （INSERT ... ON CONFLICT は負の数の行を挿入しようとして CHECK 制約に反するため、先に UPDATE する）
+CREATE OR REPLACE FUNCTION apply_stock_movement()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE products SET stock = stock + NEW.quantity WHERE id = NEW.product_id;
+    IF NEW.variant_id IS NOT NULL THEN
+        UPDATE product_variants SET stock = stock + NEW.quantity WHERE id = NEW.variant_id;
+    END IF;
+    UPDATE stock_levels SET quantity = quantity + NEW.quantity
+    WHERE warehouse_id = NEW.warehouse_id
+      AND product_id = NEW.product_id
+      AND variant_id IS NOT DISTINCT FROM NEW.variant_id;
+    IF NOT FOUND THEN
+        INSERT INTO stock_levels (warehouse_id, product_id, variant_id, quantity)
+        VALUES (NEW.warehouse_id, NEW.product_id, NEW.variant_id, NEW.quantity);
+    END IF;
+    RETURN NULL;
+END;
+$$ language 'plpgsql';
+
+-- 注文を出荷する保管場所（これまでの注文は既定の保管場所から出荷したものとする）
+ALTER TABLE orders ADD COLUMN warehouse_id UUID REFERENCES warehouses(id) ON DELETE RESTRICT;
+ALTER TABLE orders DISABLE TRIGGER USER;
+UPDATE orders SET warehouse_id = (SELECT id FROM warehouses WHERE is_default);
+ALTER TABLE orders ENABLE TRIGGER USER;
+ALTER TABLE orders ALTER COLUMN warehouse_id SET NOT NULL;
+CREATE INDEX idx_orders_warehouse_id ON orders(warehouse_id);
+
+-- 監査ログの対象に保管場所を追加する
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_entity_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_entity_check
+    CHECK (entity IN ('user', 'client', 'address', 'category', 'product', 'variant', 'order', 'warehouse'));
@@ -0,0 +1,8 @@
+-- 在庫が発注点以下になったことを知らせた商品
+-- 知らせたときに行を追加し、発注点を上回ったら行を削除する（再び下回ったときにもう一度知らせる）
+-- アプリを起動し直しても、すでに知らせた商品は知らせない
+
+CREATE TABLE low_stock_alerts (
+    product_id UUID PRIMARY KEY REFERENCES products(id) ON DELETE CASCADE,
+    alerted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,352 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use ws_models::{
+    Address, AppError, AuditAction, AuditEntity, Client, CreateAddress, FieldError, PostalAddress,
+    UpdateAddress, User, COUNTRY_JP, JP_PREFECTURES,
+};
+
+use crate::{audit, clients};
+
+// 取引先の住所を登録順に取得する
+pub async fn list(pool: &PgPool, client_id: Uuid) -> Result<Vec<Address>, sqlx::Error> {
+    sqlx::query_as::<_, Address>(
+        "SELECT * FROM client_addresses WHERE client_id = $1 ORDER BY created_at, id",
+    )
+    .bind(client_id)
+    .fetch_all(pool)
+    .await
+}
+
+// client_id の取引先の住所（他の取引先の住所や存在しない ID は field のフィールドエラー）
+pub async fn of_client(
+    conn: &mut PgConnection,
+    client_id: Uuid,
+    id: Uuid,
+    field: &str,
+) -> Result<Address, AppError> {
+    sqlx::query_as::<_, Address>(
+        "SELECT * FROM client_addresses WHERE id = $1 AND client_id = $2",
+    )
+    .bind(id)
+    .bind(client_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::field(field, "address not found"))
+}
+
+// 住所と取引先を変更の前にロックする（取引先 → 住所の順。削除済みの取引先の住所は NotFound）
+async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<(Client, Address), AppError> {
+    let client_id: Uuid =
+        sqlx::query_scalar("SELECT client_id FROM client_addresses WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or(AppError::not_found("address", id))?;
+    let client = clients::lock(conn, client_id).await?;
+    let address =
+        sqlx::query_as::<_, Address>("SELECT * FROM client_addresses WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or(AppError::not_found("address", id))?;
+    Ok((client, address))
+}
+
+// 全角の数字とハイフンを半角にし、JP の郵便番号を 123-4567 の形式に揃える
+// 7桁の数字（3桁目の後のハイフンは任意）でなければ None
+pub fn normalize_jp_postal_code(value: &str) -> Option<String> {
+    let value: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '〒')
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '－' | 'ー' | '‐' | '−' => '-',
+            _ => c,
+        })
+        .collect();
+    let digits = match value.split_once('-') {
+        Some((head, tail)) if head.len() == 3 => format!("{}{}", head, tail),
+        Some(_) => return None,
+        None => value,
+    };
+    (digits.len() == 7 && digits.chars().all(|c| c.is_ascii_digit()))
+        .then(|| format!("{}-{}", &digits[..3], &digits[3..]))
+}
+
+// 前後の空白を取り除き、空の値は None にする
+fn optional_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+// 住所を検証して保存する形に揃える
+// JP の住所は郵便番号（123-4567）・都道府県・市区町村が必須
+fn normalize(
+    label: Option<String>,
+    address: PostalAddress,
+) -> Result<(Option<String>, PostalAddress), AppError> {
+    let mut fields = Vec::new();
+    let country = address.country.trim().to_uppercase();
+    let mut postal_code = address.postal_code.trim().to_string();
+    let region = address.region.trim().to_string();
+    let city = address.city.trim().to_string();
+    let line1 = address.line1.trim().to_string();
+
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+        fields.push(FieldError::new("country", "must be a two-letter country code"));
+    }
+    if line1.is_empty() {
+        fields.push(FieldError::new("line1", "must not be empty"));
+    }
+    if country == COUNTRY_JP {
+        match normalize_jp_postal_code(&postal_code) {
+            Some(normalized) => postal_code = normalized,
+            None => fields.push(FieldError::new(
+                "postal_code",
+                "must be a Japanese postal code (123-4567)",
+            )),
+        }
+        if !JP_PREFECTURES.contains(&region.as_str()) {
+            fields.push(FieldError::new("region", "must be a Japanese prefecture"));
+        }
+        if city.is_empty() {
+            fields.push(FieldError::new("city", "must not be empty"));
+        }
+    }
+    AppError::check_fields(fields)?;
+
+    Ok((
+        optional_text(label),
+        PostalAddress {
+            recipient: optional_text(address.recipient),
+            postal_code,
+            region,
+            city,
+            line1,
+            line2: optional_text(address.line2),
+            country,
+        },
+    ))
+}
+
+// 既定の請求先・配送先がまだない取引先は、追加した住所を既定にする
+// 住所を削除するときは、その住所を既定から外す
+async fn update_defaults(
+    conn: &mut PgConnection,
+    actor: &User,
+    before: &Client,
+    billing: Option<Uuid>,
+    shipping: Option<Uuid>,
+) -> Result<(), AppError> {
+    if before.default_billing_address_id == billing && before.default_shipping_address_id == shipping
+    {
+        return Ok(());
+    }
+    let client = sqlx::query_as::<_, Client>(
+        r#"
+        UPDATE clients
+        SET default_billing_address_id = $2,
+            default_shipping_address_id = $3,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(before.id)
+    .bind(billing)
+    .bind(shipping)
+    .fetch_one(&mut *conn)
+    .await?;
+    audit::record_change(
+        &mut *conn,
+        actor,
+        AuditEntity::Client,
+        client.id,
+        AuditAction::Update,
+        Some(before),
+        Some(&client),
+    )
+    .await
+}
+
+pub async fn create(
+    pool: &PgPool,
+    actor: &User,
+    client_id: Uuid,
+    data: CreateAddress,
+) -> Result<Address, AppError> {
+    let (label, address) = normalize(data.label, data.address)?;
+
+    let mut tx = pool.begin().await?;
+    let client = clients::lock(&mut tx, client_id).await?;
+    let created = sqlx::query_as::<_, Address>(
+        r#"
+        INSERT INTO client_addresses (
+            client_id, label, recipient, postal_code, region, city, line1, line2, country
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(client_id)
+    .bind(&label)
+    .bind(&address.recipient)
+    .bind(&address.postal_code)
+    .bind(&address.region)
+    .bind(&address.city)
+    .bind(&address.line1)
+    .bind(&address.line2)
+    .bind(&address.country)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Address,
+        created.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+    )
+    .await?;
+    update_defaults(
+        &mut tx,
+        actor,
+        &client,
+        client.default_billing_address_id.or(Some(created.id)),
+        client.default_shipping_address_id.or(Some(created.id)),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(created)
+}
+
+// 指定されたフィールドだけを更新する（注文に保存済みの住所は変わらない）
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateAddress,
+) -> Result<Address, AppError> {
+    let mut tx = pool.begin().await?;
+    let (_, before) = lock(&mut tx, id).await?;
+    let (label, address) = data.apply(&before);
+    let (label, address) = normalize(label, address)?;
+    let updated = sqlx::query_as::<_, Address>(
+        r#"
+        UPDATE client_addresses
+        SET label = $2,
+            recipient = $3,
+            postal_code = $4,
+            region = $5,
+            city = $6,
+            line1 = $7,
+            line2 = $8,
+            country = $9,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&label)
+    .bind(&address.recipient)
+    .bind(&address.postal_code)
+    .bind(&address.region)
+    .bind(&address.city)
+    .bind(&address.line1)
+    .bind(&address.line2)
+    .bind(&address.country)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Address,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// 住所を削除する（注文に保存済みの住所は残る）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let (client, before) = lock(&mut tx, id).await?;
+    update_defaults(
+        &mut tx,
+        actor,
+        &client,
+        client.default_billing_address_id.filter(|&default| default != id),
+        client.default_shipping_address_id.filter(|&default| default != id),
+    )
+    .await?;
+    sqlx::query("DELETE FROM client_addresses WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Address,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_jp_postal_code;
+
+    #[test]
+    fn accepts_jp_postal_codes() {
+        for (input, expected) in [
+            ("100-0005", "100-0005"),
+            ("1000005", "100-0005"),
+            (" 100-0005 ", "100-0005"),
+            ("〒100-0005", "100-0005"),
+            ("〒 100 0005", "100-0005"),
+            ("１００－０００５", "100-0005"),
+            ("１００ー０００５", "100-0005"),
+            ("100‐0005", "100-0005"),
+            ("100−0005", "100-0005"),
+        ] {
+            assert_eq!(
+                normalize_jp_postal_code(input).as_deref(),
+                Some(expected),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_jp_postal_codes() {
+        for input in [
+            "",
+            "〒",
+            "100-000",
+            "10000055",
+            "1000-005",
+            "10-00005",
+            "100-00-05",
+            "100--0005",
+            "-1000005",
+            "100-000a",
+            "ABC-DEFG",
+        ] {
+            assert_eq!(normalize_jp_postal_code(input), None, "{}", input);
+        }
+    }
+}
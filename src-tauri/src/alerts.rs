@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use ws_models::{LowStockAlert, LOW_STOCK_EVENT};
+
+use crate::connection::Connection;
+
+// 在庫が発注点以下の商品を確認する間隔
+const LOW_STOCK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// 在庫が発注点以下になった商品を確認し続けるバックグラウンドタスク
+// 新しく発注点以下になった商品があれば LOW_STOCK_EVENT を送り、デスクトップ通知を表示する
+// 知らせたかどうかはデータベース（low_stock_alerts）に残すため、起動し直しても同じ商品は知らせない
+// 未接続の間は確認しない
+pub async fn monitor(connection: Arc<Connection>, app: AppHandle) {
+    loop {
+        if let Ok(database) = connection.database() {
+            match database.new_low_stock().await {
+                Ok(alerts) if !alerts.is_empty() => notify(&app, &alerts),
+                Ok(_) => {}
+                Err(e) => eprintln!("❌ Failed to check low stock: {}", e),
+            }
+        }
+        tokio::time::sleep(LOW_STOCK_CHECK_INTERVAL).await;
+    }
+}
+
+fn notify(app: &AppHandle, alerts: &[LowStockAlert]) {
+    if let Err(e) = app.emit(LOW_STOCK_EVENT, alerts) {
+        eprintln!("❌ Failed to emit low stock alert: {}", e);
+    }
+    let body = match alerts {
+        [alert] => format!(
+            "{} ({}): {} left, at or below its reorder point of {}",
+            alert.name, alert.sku, alert.stock, alert.reorder_point
+        ),
+        _ => format!(
+            "{} products are at or below their reorder point",
+            alerts.len()
+        ),
+    };
+    let shown = app
+        .notification()
+        .builder()
+        .title("Low stock")
+        .body(body)
+        .show();
+    if let Err(e) = shown {
+        eprintln!("❌ Failed to show low stock notification: {}", e);
+    }
+}
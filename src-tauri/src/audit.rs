@@ -0,0 +1,217 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+use ws_models::{
+    AppError, AuditAction, AuditEntity, AuditFilter, AuditLogEntry, AuditQuery, AuditSort, Page,
+    User,
+};
+
+use crate::query::{self, Filter, SortField};
+
+// 変更のたびに必ず変わるため、差分に含めないフィールド
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+impl SortField for AuditSort {
+    fn column(&self) -> &'static str {
+        match self {
+            AuditSort::CreatedAt => "created_at",
+        }
+    }
+}
+
+impl Filter for AuditFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(entity) = self.entity {
+            builder.push(" AND entity = ").push_bind(entity.as_str());
+        }
+        if let Some(entity_id) = self.entity_id {
+            builder.push(" AND entity_id = ").push_bind(entity_id);
+        }
+        if let Some(actor_id) = self.actor_id {
+            builder.push(" AND actor_id = ").push_bind(actor_id);
+        }
+        if let Some(action) = self.action {
+            builder.push(" AND action = ").push_bind(action.as_str());
+        }
+        if let Some(field) = self.field.as_deref().filter(|f| !f.trim().is_empty()) {
+            builder.push(" AND changes ? ").push_bind(field.trim().to_string());
+        }
+        if let Some(from) = self.created_from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+    }
+}
+
+pub async fn list(pool: &PgPool, query: &AuditQuery) -> Result<Page<AuditLogEntry>, AppError> {
+    query::fetch_page(pool, "audit_log", query).await
+}
+
+// レコードをフィールド名と値の組にする（構造体は JSON のオブジェクトになる）
+fn snapshot<T: Serialize>(value: Option<&T>) -> Result<Map<String, Value>, AppError> {
+    let Some(value) = value else {
+        return Ok(Map::new());
+    };
+    match serde_json::to_value(value).map_err(|e| AppError::internal(e.to_string()))? {
+        Value::Object(fields) => Ok(fields),
+        other => Err(AppError::internal(format!("cannot audit a non-object value: {}", other))),
+    }
+}
+
+// 変更前と変更後で値が異なるフィールドを {"フィールド名": {"before": ..., "after": ...}} にする
+pub fn diff<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Result<Map<String, Value>, AppError> {
+    let before = snapshot(before)?;
+    let after = snapshot(after)?;
+    let mut changes = Map::new();
+    for field in before.keys().chain(after.keys()) {
+        if IGNORED_FIELDS.contains(&field.as_str()) || changes.contains_key(field) {
+            continue;
+        }
+        let old = before.get(field).cloned().unwrap_or(Value::Null);
+        let new = after.get(field).cloned().unwrap_or(Value::Null);
+        if old != new {
+            changes.insert(field.clone(), json!({ "before": old, "after": new }));
+        }
+    }
+    Ok(changes)
+}
+
+// 変更を記録する（変更と同じトランザクションで呼ぶこと）
+// 更新で値が何も変わらなかった場合は記録しない
+pub async fn record(
+    conn: &mut PgConnection,
+    actor: &User,
+    entity: AuditEntity,
+    entity_id: Uuid,
+    action: AuditAction,
+    changes: Map<String, Value>,
+) -> Result<(), AppError> {
+    if action == AuditAction::Update && changes.is_empty() {
+        return Ok(());
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (actor_id, actor_name, entity, entity_id, action, changes)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(actor.id)
+    .bind(&actor.name)
+    .bind(entity.as_str())
+    .bind(entity_id)
+    .bind(action.as_str())
+    .bind(Json(changes))
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// 変更前と変更後のレコードから差分を作って記録する
+pub async fn record_change<T: Serialize>(
+    conn: &mut PgConnection,
+    actor: &User,
+    entity: AuditEntity,
+    entity_id: Uuid,
+    action: AuditAction,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), AppError> {
+    let changes = diff(before, after)?;
+    record(conn, actor, entity, entity_id, action, changes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::diff;
+
+    #[derive(Serialize)]
+    struct Record {
+        name: &'static str,
+        stock: i32,
+        tags: Vec<&'static str>,
+        note: Option<&'static str>,
+        updated_at: &'static str,
+    }
+
+    fn record() -> Record {
+        Record {
+            name: "Monitor 4K",
+            stock: 30,
+            tags: vec!["display"],
+            note: None,
+            updated_at: "2024-01-01T00:00:00Z",
+        }
+    }
+
+    #[test]
+    fn records_only_changed_fields() {
+        let before = record();
+        let after = Record {
+            stock: 25,
+            note: Some("recounted"),
+            updated_at: "2024-01-02T00:00:00Z",
+            ..record()
+        };
+        let changes = diff(Some(&before), Some(&after)).unwrap();
+        assert_eq!(
+            serde_json::Value::Object(changes),
+            json!({
+                "stock": { "before": 30, "after": 25 },
+                "note": { "before": null, "after": "recounted" },
+            })
+        );
+    }
+
+    #[test]
+    fn compares_nested_values() {
+        let after = Record {
+            tags: vec!["display", "4k"],
+            ..record()
+        };
+        let changes = diff(Some(&record()), Some(&after)).unwrap();
+        assert_eq!(
+            changes["tags"],
+            json!({ "before": ["display"], "after": ["display", "4k"] })
+        );
+        assert_eq!(changes.len(), 1);
+    }
+
+    #[test]
+    fn unchanged_record_has_no_changes() {
+        let after = Record {
+            updated_at: "2024-01-02T00:00:00Z",
+            ..record()
+        };
+        assert!(diff(Some(&record()), Some(&after)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn created_and_deleted_records_list_every_set_field() {
+        let created = diff(None, Some(&record())).unwrap();
+        assert_eq!(
+            created["name"],
+            json!({ "before": null, "after": "Monitor 4K" })
+        );
+        assert_eq!(created["stock"], json!({ "before": null, "after": 30 }));
+        // null から null は変更ではない
+        assert!(!created.contains_key("note"));
+        assert!(!created.contains_key("updated_at"));
+
+        let deleted = diff(Some(&record()), None).unwrap();
+        assert_eq!(deleted["stock"], json!({ "before": 30, "after": null }));
+        assert_eq!(deleted.len(), 3);
+    }
+
+    #[test]
+    fn rejects_values_that_are_not_objects() {
+        assert!(diff(Some(&1), Some(&2)).is_err());
+        assert!(diff::<Record>(None, None).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use uuid::Uuid;
+use ws_models::{AppError, Permission, Session, User};
+
+// 最後にコマンドを呼び出してからセッションが切れるまでの時間
+const SESSION_IDLE_TIMEOUT_HOURS: i64 = 12;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+// パスワードを argon2id でハッシュ化する（PHC 文字列で返す）
+// 計算に時間がかかるため、非同期ランタイムのスレッドを塞がないよう別スレッドで実行する
+pub async fn hash_password(password: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::internal(format!("Failed to hash password: {}", e)))
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+// 保存済みのハッシュと照合する（ハッシュが壊れている場合も一致しない扱い）
+pub async fn verify_password(password: String, hash: String) -> bool {
+    tokio::task::spawn_blocking(move || {
+        PasswordHash::new(&hash)
+            .map(|parsed| {
+                Argon2::default()
+                    .verify_password(password.as_bytes(), &parsed)
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+// 推測できないセッショントークン（32バイトの乱数の16進表記）
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn expires_at() -> DateTime<Utc> {
+    Utc::now() + chrono::Duration::hours(SESSION_IDLE_TIMEOUT_HOURS)
+}
+
+// ログイン中のセッション（メモリ上だけに保持し、アプリを閉じると消える）
+#[derive(Default)]
+pub struct Sessions {
+    sessions: RwLock<HashMap<String, Session>>,
+}
+
+impl Sessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(&self, user: User, permissions: Vec<Permission>) -> Session {
+        let session = Session {
+            token: generate_token(),
+            user,
+            permissions,
+            expires_at: expires_at(),
+        };
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(session.token.clone(), session.clone());
+        session
+    }
+
+    // 有効なセッションを返し、有効期限を延長する（なければ Unauthorized）
+    pub fn require(&self, token: &str) -> Result<Session, AppError> {
+        let mut sessions = self.sessions.write().unwrap();
+        let now = Utc::now();
+        sessions.retain(|_, session| session.expires_at > now);
+        let session = sessions
+            .get_mut(token)
+            .ok_or_else(|| AppError::unauthorized("please log in"))?;
+        session.expires_at = expires_at();
+        Ok(session.clone())
+    }
+
+    // 有効なセッションで、ロールに permission が与えられていれば返す（なければ Forbidden）
+    pub fn authorize(&self, token: &str, permission: Permission) -> Result<Session, AppError> {
+        let session = self.require(token)?;
+        if !session.can(permission) {
+            return Err(AppError::Forbidden {
+                message: format!(
+                    "your role ({}) does not have the {} permission",
+                    session.user.role.label(),
+                    permission
+                ),
+            });
+        }
+        Ok(session)
+    }
+
+    pub fn remove(&self, token: &str) {
+        self.sessions.write().unwrap().remove(token);
+    }
+
+    // ユーザー情報やロールを変更したら、そのユーザーのセッションにも反映する
+    pub fn update_user(&self, user: &User, permissions: &[Permission]) {
+        for session in self.sessions.write().unwrap().values_mut() {
+            if session.user.id == user.id {
+                session.user = user.clone();
+                session.permissions = permissions.to_vec();
+            }
+        }
+    }
+
+    // 削除されたユーザーのセッションを無効にする
+    pub fn remove_user(&self, user_id: Uuid) {
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|_, session| session.user.id != user_id);
+    }
+
+    // パスワードを変更したら、そのユーザーのほかのセッションを無効にする
+    // keep_token のセッション（自分のパスワードを変更したユーザーのセッション）は残す
+    pub fn remove_other_sessions(&self, user_id: Uuid, keep_token: &str) {
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|token, session| session.user.id != user_id || token == keep_token);
+    }
+
+    pub fn clear(&self) {
+        self.sessions.write().unwrap().clear();
+    }
+}
@@ -0,0 +1,216 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, CreateProductCategory, ProductCategory,
+    UpdateProductCategory, User,
+};
+
+use crate::audit;
+
+// 最上位のカテゴリから順にたどり、path と depth を付けた tree を作る
+// sort_key は名前の配列（兄弟の間は名前順、子は親の直後に並ぶ）
+const TREE: &str = r#"
+    WITH RECURSIVE tree AS (
+        SELECT c.*, c.name::text AS path, 0 AS depth, ARRAY[lower(c.name)]::text[] AS sort_key
+        FROM product_categories c
+        WHERE c.parent_id IS NULL
+        UNION ALL
+        SELECT c.*, t.path || ' / ' || c.name, t.depth + 1, t.sort_key || lower(c.name)::text
+        FROM product_categories c
+        JOIN tree t ON c.parent_id = t.id
+    )
+"#;
+
+// すべてのカテゴリを階層順に取得する
+pub async fn list(pool: &PgPool) -> Result<Vec<ProductCategory>, sqlx::Error> {
+    sqlx::query_as::<_, ProductCategory>(&format!("{} SELECT * FROM tree ORDER BY sort_key", TREE))
+        .fetch_all(pool)
+        .await
+}
+
+async fn fetch(conn: &mut PgConnection, id: Uuid) -> Result<ProductCategory, AppError> {
+    sqlx::query_as::<_, ProductCategory>(&format!("{} SELECT * FROM tree WHERE id = $1", TREE))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::not_found("category", id))
+}
+
+// 変更の前に行をロックし、変更前の状態（監査ログ用）を取得する
+async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<ProductCategory, AppError> {
+    sqlx::query("SELECT id FROM product_categories WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::not_found("category", id))?;
+    fetch(conn, id).await
+}
+
+// 商品やカテゴリの親として指定されたカテゴリが存在するか（なければ field のフィールドエラー）
+pub async fn exists(conn: &mut PgConnection, id: Uuid, field: &str) -> Result<(), AppError> {
+    let found: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM product_categories WHERE id = $1)")
+            .bind(id)
+            .fetch_one(conn)
+            .await?;
+    if !found {
+        return Err(AppError::field(field, "category not found"));
+    }
+    Ok(())
+}
+
+// 前後の空白を取り除いた名前（空なら name のフィールドエラー）
+fn normalize_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::field("name", "must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+pub async fn create(
+    pool: &PgPool,
+    actor: &User,
+    data: CreateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    let name = normalize_name(&data.name)?;
+
+    let mut tx = pool.begin().await?;
+    if let Some(parent_id) = data.parent_id {
+        exists(&mut tx, parent_id, "parent_id").await?;
+    }
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO product_categories (parent_id, name) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(data.parent_id)
+    .bind(&name)
+    .fetch_one(&mut *tx)
+    .await?;
+    let created = fetch(&mut tx, id).await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Category,
+        id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(created)
+}
+
+// 名前の変更と、別のカテゴリの下（または最上位）への移動
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    let name = data.name.as_deref().map(normalize_name).transpose()?;
+
+    let mut tx = pool.begin().await?;
+    if let Some(Some(_)) = data.parent_id {
+        // 移動を1つずつにする（A を B の下へ、B を A の下へを同時に確認すると、どちらも通って循環する）
+        sqlx::query("LOCK TABLE product_categories IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+    }
+    let before = lock(&mut tx, id).await?;
+    if let Some(Some(parent_id)) = data.parent_id {
+        exists(&mut tx, parent_id, "parent_id").await?;
+        // 自分自身や自分の下位のカテゴリの下に移動すると階層が循環する
+        // （UNION で重複を除き、循環した行があっても終わるようにする）
+        let is_descendant: bool = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE descendants AS (
+                SELECT id FROM product_categories WHERE id = $1
+                UNION
+                SELECT c.id FROM product_categories c JOIN descendants d ON c.parent_id = d.id
+            )
+            SELECT EXISTS (SELECT 1 FROM descendants WHERE id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(parent_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if is_descendant {
+            return Err(AppError::field(
+                "parent_id",
+                "cannot move a category under itself or its subcategories",
+            ));
+        }
+    }
+    sqlx::query(
+        r#"
+        UPDATE product_categories
+        SET parent_id = CASE WHEN $2 THEN $3 ELSE parent_id END,
+            name = COALESCE($4, name),
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(data.parent_id.is_some())
+    .bind(data.parent_id.flatten())
+    .bind(name)
+    .execute(&mut *tx)
+    .await?;
+    let updated = fetch(&mut tx, id).await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Category,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// カテゴリを削除する（下位のカテゴリや、削除済みを含む商品があるカテゴリは Conflict）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    let (children, products): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT (SELECT COUNT(*) FROM product_categories WHERE parent_id = $1),
+               (SELECT COUNT(*) FROM products WHERE category_id = $1)
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if children > 0 || products > 0 {
+        return Err(AppError::Conflict {
+            message: format!(
+                "the category still has {} subcategories and {} products",
+                children, products
+            ),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    sqlx::query("DELETE FROM product_categories WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Category,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, Client, ClientFilter, ClientQuery, ClientSort,
+    CreateClient, FieldError, Page, UpdateClient, User, PURGE_AFTER_DAYS,
+};
+
+use crate::query::{self, contains_pattern, Filter, SortField};
+use crate::{addresses, audit, users};
+
+impl SortField for ClientSort {
+    fn column(&self) -> &'static str {
+        match self {
+            ClientSort::CreatedAt => "created_at",
+            ClientSort::CompanyName => "company_name",
+        }
+    }
+}
+
+impl Filter for ClientFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(ids) = &self.ids {
+            builder.push(" AND id = ANY(").push_bind(ids.clone()).push(")");
+        }
+        if let Some(search) = self.search.as_deref().filter(|s| !s.trim().is_empty()) {
+            let pattern = contains_pattern(search);
+            builder.push(" AND (company_name ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR contact_name ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR email ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR phone ILIKE ").push_bind(pattern).push(")");
+        }
+        if let Some(terms) = self.payment_terms {
+            builder.push(" AND payment_terms = ").push_bind(terms.as_str());
+        }
+        if !self.include_deleted {
+            builder.push(" AND deleted_at IS NULL");
+        }
+    }
+}
+
+pub async fn list(pool: &PgPool, query: &ClientQuery) -> Result<Page<Client>, AppError> {
+    query::fetch_page(pool, "clients", query).await
+}
+
+pub async fn get(pool: &PgPool, id: Uuid) -> Result<Client, AppError> {
+    sqlx::query_as::<_, Client>("SELECT * FROM clients WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::not_found("client", id))
+}
+
+// 更新・削除の前の状態（監査ログ用）を取得し、トランザクションの終わりまで行をロックする
+// 削除済みの取引先は NotFound
+pub async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<Client, AppError> {
+    let client = lock_any(conn, id).await?;
+    if client.deleted_at.is_some() {
+        return Err(AppError::not_found("client", id));
+    }
+    Ok(client)
+}
+
+// 削除済みの取引先も含めて行をロックする
+async fn lock_any(conn: &mut PgConnection, id: Uuid) -> Result<Client, AppError> {
+    sqlx::query_as::<_, Client>("SELECT * FROM clients WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::not_found("client", id))
+}
+
+// 注文の顧客として指定できる取引先（削除済みの取引先は指定できない）
+pub async fn active(conn: &mut PgConnection, id: Uuid) -> Result<Client, AppError> {
+    sqlx::query_as::<_, Client>("SELECT * FROM clients WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::field("client_id", "client not found"))
+}
+
+// 前後の空白を取り除き、空の値は None にする
+fn optional_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+// 電話番号は数字と + - ( ) 空白だけを受け付ける
+fn is_phone(value: &str) -> bool {
+    value.chars().any(|c| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '(' | ')' | ' '))
+}
+
+// 入力値の検証（None のフィールドは検証しない）
+fn validate(
+    company_name: Option<&str>,
+    email: Option<&str>,
+    phone: Option<&str>,
+) -> Result<(), AppError> {
+    let mut fields = Vec::new();
+    if company_name.is_some_and(|v| v.trim().is_empty()) {
+        fields.push(FieldError::new("company_name", "must not be empty"));
+    }
+    if let Some(email) = email.map(str::trim).filter(|v| !v.is_empty()) {
+        if !users::is_email(email) {
+            fields.push(FieldError::new("email", "is not a valid email address"));
+        }
+    }
+    if let Some(phone) = phone.map(str::trim).filter(|v| !v.is_empty()) {
+        if !is_phone(phone) {
+            fields.push(FieldError::new("phone", "is not a valid phone number"));
+        }
+    }
+    AppError::check_fields(fields)
+}
+
+pub async fn create(pool: &PgPool, actor: &User, data: CreateClient) -> Result<Client, AppError> {
+    validate(
+        Some(&data.company_name),
+        data.email.as_deref(),
+        data.phone.as_deref(),
+    )?;
+
+    let mut tx = pool.begin().await?;
+    let client = sqlx::query_as::<_, Client>(
+        r#"
+        INSERT INTO clients (
+            company_name, contact_name, email, phone, tax_id, payment_terms, notes
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(data.company_name.trim())
+    .bind(optional_text(data.contact_name))
+    .bind(optional_text(data.email))
+    .bind(optional_text(data.phone))
+    .bind(optional_text(data.tax_id))
+    .bind(data.payment_terms.as_str())
+    .bind(optional_text(data.notes))
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Client,
+        client.id,
+        AuditAction::Create,
+        None,
+        Some(&client),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(client)
+}
+
+// 指定されたフィールドだけを更新する
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateClient,
+) -> Result<Client, AppError> {
+    validate(
+        data.company_name.as_deref(),
+        data.email.as_ref().and_then(|v| v.as_deref()),
+        data.phone.as_ref().and_then(|v| v.as_deref()),
+    )?;
+
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    // 既定の住所はこの取引先の住所だけを指定できる
+    if let Some(Some(address_id)) = data.default_billing_address_id {
+        addresses::of_client(&mut tx, id, address_id, "default_billing_address_id").await?;
+    }
+    if let Some(Some(address_id)) = data.default_shipping_address_id {
+        addresses::of_client(&mut tx, id, address_id, "default_shipping_address_id").await?;
+    }
+    let client = sqlx::query_as::<_, Client>(
+        r#"
+        UPDATE clients
+        SET company_name = COALESCE($2, company_name),
+            contact_name = CASE WHEN $3 THEN $4 ELSE contact_name END,
+            email = CASE WHEN $5 THEN $6 ELSE email END,
+            phone = CASE WHEN $7 THEN $8 ELSE phone END,
+            default_billing_address_id = CASE WHEN $9 THEN $10 ELSE default_billing_address_id END,
+            default_shipping_address_id =
+                CASE WHEN $11 THEN $12 ELSE default_shipping_address_id END,
+            tax_id = CASE WHEN $13 THEN $14 ELSE tax_id END,
+            payment_terms = COALESCE($15, payment_terms),
+            notes = CASE WHEN $16 THEN $17 ELSE notes END,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(data.company_name.as_deref().map(str::trim))
+    .bind(data.contact_name.is_some())
+    .bind(optional_text(data.contact_name.flatten()))
+    .bind(data.email.is_some())
+    .bind(optional_text(data.email.flatten()))
+    .bind(data.phone.is_some())
+    .bind(optional_text(data.phone.flatten()))
+    .bind(data.default_billing_address_id.is_some())
+    .bind(data.default_billing_address_id.flatten())
+    .bind(data.default_shipping_address_id.is_some())
+    .bind(data.default_shipping_address_id.flatten())
+    .bind(data.tax_id.is_some())
+    .bind(optional_text(data.tax_id.flatten()))
+    .bind(data.payment_terms.map(|terms| terms.as_str()))
+    .bind(data.notes.is_some())
+    .bind(optional_text(data.notes.flatten()))
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Client,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&client),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(client)
+}
+
+// 論理削除する（注文からは引き続き参照できるが、新しい注文の顧客には指定できなくなる）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    let client = sqlx::query_as::<_, Client>(
+        "UPDATE clients SET deleted_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Client,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        Some(&client),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// 論理削除を取り消す（削除されていない取引先は Conflict）
+pub async fn restore(pool: &PgPool, actor: &User, id: Uuid) -> Result<Client, AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock_any(&mut tx, id).await?;
+    if before.deleted_at.is_none() {
+        return Err(AppError::Conflict {
+            message: "the client is not deleted".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    let client = sqlx::query_as::<_, Client>(
+        "UPDATE clients SET deleted_at = NULL WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Client,
+        id,
+        AuditAction::Restore,
+        Some(&before),
+        Some(&client),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(client)
+}
+
+// 削除から PURGE_AFTER_DAYS 日を過ぎた取引先を完全削除する
+// 注文から参照されている取引先は残す
+pub async fn purge(conn: &mut PgConnection, actor: &User) -> Result<u64, AppError> {
+    let clients = sqlx::query_as::<_, Client>(
+        r#"
+        DELETE FROM clients c
+        WHERE c.deleted_at < NOW() - make_interval(days => $1)
+          AND NOT EXISTS (SELECT 1 FROM orders o WHERE o.client_id = c.id)
+        RETURNING *
+        "#,
+    )
+    .bind(PURGE_AFTER_DAYS)
+    .fetch_all(&mut *conn)
+    .await?;
+    for client in &clients {
+        audit::record_change(
+            &mut *conn,
+            actor,
+            AuditEntity::Client,
+            client.id,
+            AuditAction::Purge,
+            Some(client),
+            None,
+        )
+        .await?;
+    }
+    Ok(clients.len() as u64)
+}
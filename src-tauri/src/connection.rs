@@ -0,0 +1,286 @@
+use sqlx::postgres::{PgConnectOptions, PgSslMode};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+use ws_models::{
+    AppError, ConnectionConfig, ConnectionProfile, ConnectionSettings, ConnectionState,
+    ConnectionStatus, SslMode, CONNECTION_STATUS_EVENT,
+};
+
+use crate::database::Database;
+use crate::profiles::ProfileStore;
+
+// 再接続の待ち時間（失敗するたびに倍にし、MAX_BACKOFF で頭打ち）
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// 接続中に接続プールが使えるかを確認する間隔
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+// データベースへの接続と、その状態の管理
+// 接続できない間もアプリは起動し、データのコマンドは Unavailable を返す
+pub struct Connection {
+    profiles: ProfileStore,
+    inner: RwLock<Inner>,
+    // reconnect() で待機中の再接続を起こす
+    wake: Notify,
+}
+
+struct Inner {
+    // 接続に使うプロファイルとパスワード（status.settings はパスワードなし）
+    profile: ConnectionProfile,
+    password: Option<String>,
+    database: Option<Arc<Database>>,
+    status: ConnectionStatus,
+    // 設定を変更するたびに増やし、古い設定での接続結果を捨てる
+    generation: u64,
+}
+
+fn connect_options(profile: &ConnectionProfile, password: Option<&str>) -> PgConnectOptions {
+    let settings = &profile.connection;
+    let mut options = PgConnectOptions::new()
+        .host(&settings.host)
+        .port(settings.port)
+        .database(&settings.database)
+        .username(&settings.username)
+        .ssl_mode(match settings.ssl_mode {
+            SslMode::Disable => PgSslMode::Disable,
+            SslMode::Allow => PgSslMode::Allow,
+            SslMode::Prefer => PgSslMode::Prefer,
+            SslMode::Require => PgSslMode::Require,
+            SslMode::VerifyCa => PgSslMode::VerifyCa,
+            SslMode::VerifyFull => PgSslMode::VerifyFull,
+        });
+    if let Some(password) = password {
+        options = options.password(password);
+    }
+    // 接続ごとに SET しなくて済むよう、起動パラメータとして渡す
+    if let Some(ms) = profile.pool.statement_timeout_ms {
+        options = options.options([("statement_timeout", format!("{}ms", ms))]);
+    }
+    options
+}
+
+// 接続を始める前の状態
+fn connecting(profile: &ConnectionProfile) -> ConnectionStatus {
+    ConnectionStatus {
+        state: ConnectionState::Connecting,
+        profile: profile.name.clone(),
+        settings: ConnectionSettings {
+            password: None,
+            ..profile.connection.clone()
+        },
+        error: None,
+        attempts: 0,
+        retry_at: None,
+    }
+}
+
+impl Connection {
+    pub fn new(profiles: ProfileStore) -> Self {
+        let (profile, password) = profiles.active();
+        Connection {
+            inner: RwLock::new(Inner {
+                status: connecting(&profile),
+                profile,
+                password,
+                database: None,
+                generation: 0,
+            }),
+            profiles,
+            wake: Notify::new(),
+        }
+    }
+
+    // 接続済みのデータベース（未接続なら Unavailable）
+    pub fn database(&self) -> Result<Arc<Database>, AppError> {
+        let inner = self.inner.read().unwrap();
+        match &inner.database {
+            Some(database) if inner.status.is_connected() => Ok(database.clone()),
+            _ => Err(AppError::Unavailable {
+                message: inner
+                    .status
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "database is not connected".to_string()),
+            }),
+        }
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        self.inner.read().unwrap().status.clone()
+    }
+
+    pub fn config(&self) -> ConnectionConfig {
+        self.profiles.config()
+    }
+
+    // 接続画面から、使用中のプロファイルの接続先を変えてすぐに接続し直す
+    // password が None なら今のパスワードを使い続ける
+    pub fn connect(&self, settings: ConnectionSettings) -> Result<ConnectionStatus, AppError> {
+        let (mut profile, _) = self.profiles.active();
+        let password = settings.password.clone();
+        profile.connection = settings;
+        self.profiles.save_profile(profile, password)?;
+        let (profile, password) = self.profiles.active();
+        Ok(self.reconnect(profile, password))
+    }
+
+    // プロファイルを保存し、使用中のプロファイルの設定が変わったら接続し直す
+    pub fn save_profile(
+        &self,
+        profile: ConnectionProfile,
+        password: Option<String>,
+    ) -> Result<ConnectionConfig, AppError> {
+        let config = self.profiles.save_profile(profile, password)?;
+        let (active, password) = self.profiles.active();
+        let changed = {
+            let inner = self.inner.read().unwrap();
+            inner.profile != active || inner.password != password
+        };
+        if changed {
+            self.reconnect(active, password);
+        }
+        Ok(config)
+    }
+
+    pub fn delete_profile(&self, name: &str) -> Result<ConnectionConfig, AppError> {
+        self.profiles.delete_profile(name)
+    }
+
+    // 使用するプロファイルを切り替えて接続し直す
+    pub fn select_profile(&self, name: &str) -> Result<ConnectionStatus, AppError> {
+        let (profile, password) = self.profiles.select(name)?;
+        Ok(self.reconnect(profile, password))
+    }
+
+    // 接続プールを作り直し、待機中の再接続をすぐに始めさせる
+    fn reconnect(&self, profile: ConnectionProfile, password: Option<String>) -> ConnectionStatus {
+        let status = {
+            let mut inner = self.inner.write().unwrap();
+            inner.status = connecting(&profile);
+            inner.profile = profile;
+            inner.password = password;
+            inner.database = None;
+            inner.generation += 1;
+            inner.status.clone()
+        };
+        self.wake.notify_one();
+        status
+    }
+
+    // 接続・再接続・死活監視を続けるバックグラウンドタスク
+    // 状態が変わるたびに CONNECTION_STATUS_EVENT を送る
+    pub async fn supervise(self: Arc<Self>, app: AppHandle) {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            // 確認中に設定が変わったら、終わるのを待たずに新しい設定で接続し直す
+            let (generation, result) = tokio::select! {
+                checked = self.check() => checked,
+                _ = self.wake.notified() => {
+                    backoff = INITIAL_BACKOFF;
+                    continue;
+                }
+            };
+            let wait = match result {
+                Ok(()) => {
+                    backoff = INITIAL_BACKOFF;
+                    HEALTH_CHECK_INTERVAL
+                }
+                Err(_) => {
+                    let wait = backoff;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    wait
+                }
+            };
+
+            if let Some(status) = self.record(generation, result, wait) {
+                if let Err(e) = app.emit(CONNECTION_STATUS_EVENT, &status) {
+                    eprintln!("❌ Failed to emit connection status: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = self.wake.notified() => backoff = INITIAL_BACKOFF,
+            }
+        }
+    }
+
+    // 未接続なら接続してマイグレーションを適用し、接続済みなら接続プールが使えるか確認する
+    async fn check(&self) -> (u64, Result<(), String>) {
+        let (generation, profile, password, database) = {
+            let inner = self.inner.read().unwrap();
+            (
+                inner.generation,
+                inner.profile.clone(),
+                inner.password.clone(),
+                inner.database.clone(),
+            )
+        };
+
+        if let Some(database) = database {
+            return (generation, database.ping().await.map_err(|e| e.to_string()));
+        }
+
+        let database = match Database::connect(connect_options(&profile, password.as_deref()), &profile.pool).await {
+            Ok(database) => database,
+            Err(e) => return (generation, Err(format!("Failed to connect to database: {}", e))),
+        };
+        match database.migrate().await {
+            Ok(applied) if applied.is_empty() => println!("✅ Database schema is up to date"),
+            Ok(applied) => println!("✅ Applied {} database migration(s)", applied.len()),
+            Err(e) => return (generation, Err(format!("Failed to migrate database: {}", e))),
+        }
+
+        let mut inner = self.inner.write().unwrap();
+        if inner.generation == generation {
+            inner.database = Some(Arc::new(database));
+        }
+        (generation, Ok(()))
+    }
+
+    // 確認の結果を状態に反映し、変わっていれば新しい状態を返す
+    fn record(
+        &self,
+        generation: u64,
+        result: Result<(), String>,
+        wait: Duration,
+    ) -> Option<ConnectionStatus> {
+        let mut inner = self.inner.write().unwrap();
+        // 確認中に設定が変わった場合は結果を捨てる
+        if inner.generation != generation {
+            return None;
+        }
+
+        let status = match result {
+            Ok(()) => ConnectionStatus {
+                state: ConnectionState::Connected,
+                ..connecting(&inner.profile)
+            },
+            Err(error) => ConnectionStatus {
+                state: ConnectionState::Disconnected,
+                error: Some(error),
+                attempts: inner.status.attempts + 1,
+                retry_at: chrono::Duration::from_std(wait)
+                    .ok()
+                    .map(|wait| chrono::Utc::now() + wait),
+                ..connecting(&inner.profile)
+            },
+        };
+        if status == inner.status {
+            return None;
+        }
+
+        match (&status.state, &status.error) {
+            (ConnectionState::Connected, _) => println!("✅ Database connected successfully"),
+            (_, Some(error)) if inner.status.is_connected() || inner.status.attempts == 0 => {
+                eprintln!("❌ {}", error);
+                eprintln!("💡 Make sure PostgreSQL is running and the connection settings are correct");
+            }
+            _ => {}
+        }
+        inner.status = status.clone();
+        Some(status)
+    }
+}
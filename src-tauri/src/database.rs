@@ -1,7 +1,24 @@
-use sqlx::PgPool;
-use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Connection, PgConnection, PgPool};
+use std::time::Duration;
 use uuid::Uuid;
+use ws_models::{
+    Address, AdjustStock, AdjustStockBatch, AppError, AuditLogEntry, AuditQuery, Client,
+    ClientQuery, CreateAddress, CreateClient, CreateOrder, CreateProduct, CreateProductCategory,
+    CreateStockTransfer, CreateUser, CreateWarehouse, LowStockAlert, MigrationStatus, Order,
+    OrderQuery, OrderStatus, OrderStatusHistory, OrderWithItems, Page, Permission, PoolSettings,
+    Product, ProductCategory, ProductQuery, ProductVariants, PurgeReport, ReorderQuery,
+    ReorderSuggestion, Role, SearchHit, SearchKind, SetProductOption, StockDiscrepancy, StockLevel,
+    StockMovement, StockMovementQuery, StockTransfer, UpdateAddress, UpdateClient, UpdateOrder,
+    UpdateProduct, UpdateProductCategory, UpdateProductVariant, UpdateUser, UpdateWarehouse, User,
+    UserQuery, Warehouse,
+};
+
+use crate::migrations::{self, MigrationError};
+use crate::{
+    addresses, audit, categories, clients, orders, products, purge, reorder, roles, search, stock,
+    users, variants, warehouses,
+};
 
 // データベース接続プール
 pub struct Database {
@@ -9,111 +26,396 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
-        let pool = PgPool::connect(database_url).await?;
+    pub async fn connect(
+        options: PgConnectOptions,
+        settings: &PoolSettings,
+    ) -> Result<Self, sqlx::Error> {
+        let acquire_timeout = Duration::from_secs(settings.acquire_timeout_secs);
+        // 接続プールは失敗しても acquire_timeout まで再試行し、理由が PoolTimedOut になるため、
+        // 接続拒否や認証エラーをそのまま返せるよう最初の1本は直接つなぐ
+        let conn = tokio::time::timeout(acquire_timeout, PgConnection::connect_with(&options))
+            .await
+            .map_err(|_| sqlx::Error::PoolTimedOut)??;
+        conn.close().await?;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(settings.max_connections)
+            .min_connections(settings.min_connections)
+            .acquire_timeout(acquire_timeout)
+            .idle_timeout(settings.idle_timeout_secs.map(Duration::from_secs))
+            .connect_lazy_with(options);
         Ok(Database { pool })
     }
 
-    // データベースの初期化（テーブル作成）
-    pub async fn init(&self) -> Result<(), sqlx::Error> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS users (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                name VARCHAR(255) NOT NULL,
-                email VARCHAR(255) UNIQUE NOT NULL,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS products (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                name VARCHAR(255) NOT NULL,
-                description TEXT,
-                price DECIMAL(10,2) NOT NULL,
-                stock INTEGER DEFAULT 0,
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS orders (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                user_id UUID REFERENCES users(id),
-                total_amount DECIMAL(10,2) NOT NULL,
-                status VARCHAR(50) DEFAULT 'pending',
-                created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-                updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
+    // 接続プールからコネクションを取得できるか確認する
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
         Ok(())
     }
-}
 
-// データモデル
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct User {
-    pub id: Uuid,
-    pub name: String,
-    pub email: String,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
+    // 未適用のマイグレーションを適用する（テーブル作成・スキーマ変更）
+    pub async fn migrate(&self) -> Result<Vec<i64>, MigrationError> {
+        migrations::run(&self.pool).await
+    }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Product {
-    pub id: Uuid,
-    pub name: String,
-    pub description: Option<String>,
-    pub price: f64,
-    pub stock: i32,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
+    // マイグレーションの適用状況（適用済み・未適用）を取得する
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, AppError> {
+        Ok(migrations::status(&self.pool).await?)
+    }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Order {
-    pub id: Uuid,
-    pub user_id: Option<Uuid>,
-    pub total_amount: f64,
-    pub status: String,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
+    // ログイン（メールアドレスとパスワードの確認）と最初のユーザーの登録
+    pub async fn authenticate(&self, email: &str, password: &str) -> Result<User, AppError> {
+        users::authenticate(&self.pool, email, password).await
+    }
 
-// 作成用の構造体
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateUser {
-    pub name: String,
-    pub email: String,
-}
+    pub async fn setup_required(&self) -> Result<bool, AppError> {
+        users::setup_required(&self.pool).await
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateProduct {
-    pub name: String,
-    pub description: Option<String>,
-    pub price: f64,
-    pub stock: i32,
-}
+    pub async fn setup_first_user(&self, data: CreateUser) -> Result<User, AppError> {
+        users::setup_first_user(&self.pool, data).await
+    }
+
+    // ロールに与えられている権限
+    pub async fn permissions(&self, role: Role) -> Result<Vec<Permission>, AppError> {
+        roles::permissions(&self.pool, role).await
+    }
+
+    // ユーザーの作成・取得・更新・削除（論理削除）・復元
+    // 変更系は操作したユーザー（actor）を受け取り、同じトランザクションで監査ログに記録する
+    pub async fn create_user(&self, actor: &User, data: CreateUser) -> Result<User, AppError> {
+        users::create(&self.pool, actor, data).await
+    }
+
+    pub async fn get_user_by_id(&self, id: Uuid) -> Result<User, AppError> {
+        users::get(&self.pool, id).await
+    }
+
+    pub async fn update_user(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateUser,
+    ) -> Result<User, AppError> {
+        users::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_user(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        users::delete(&self.pool, actor, id).await
+    }
+
+    pub async fn restore_user(&self, actor: &User, id: Uuid) -> Result<User, AppError> {
+        users::restore(&self.pool, actor, id).await
+    }
+
+    // 取引先の作成・取得・更新・削除（論理削除）・復元
+    pub async fn create_client(&self, actor: &User, data: CreateClient) -> Result<Client, AppError> {
+        clients::create(&self.pool, actor, data).await
+    }
+
+    pub async fn get_client_by_id(&self, id: Uuid) -> Result<Client, AppError> {
+        clients::get(&self.pool, id).await
+    }
+
+    pub async fn update_client(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateClient,
+    ) -> Result<Client, AppError> {
+        clients::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_client(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        clients::delete(&self.pool, actor, id).await
+    }
+
+    pub async fn restore_client(&self, actor: &User, id: Uuid) -> Result<Client, AppError> {
+        clients::restore(&self.pool, actor, id).await
+    }
+
+    // 取引先の住所の取得・作成・更新・削除
+    pub async fn get_client_addresses(&self, client_id: Uuid) -> Result<Vec<Address>, AppError> {
+        Ok(addresses::list(&self.pool, client_id).await?)
+    }
+
+    pub async fn create_client_address(
+        &self,
+        actor: &User,
+        client_id: Uuid,
+        data: CreateAddress,
+    ) -> Result<Address, AppError> {
+        addresses::create(&self.pool, actor, client_id, data).await
+    }
+
+    pub async fn update_client_address(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateAddress,
+    ) -> Result<Address, AppError> {
+        addresses::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_client_address(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        addresses::delete(&self.pool, actor, id).await
+    }
+
+    // 商品の作成・取得・更新・削除（論理削除）・復元
+    pub async fn create_product(
+        &self,
+        actor: &User,
+        data: CreateProduct,
+    ) -> Result<Product, AppError> {
+        products::create(&self.pool, actor, data).await
+    }
+
+    pub async fn get_product_by_id(&self, id: Uuid) -> Result<Product, AppError> {
+        products::get(&self.pool, id).await
+    }
+
+    pub async fn get_product_by_barcode(&self, barcode: &str) -> Result<Product, AppError> {
+        products::get_by_barcode(&self.pool, barcode).await
+    }
+
+    pub async fn update_product(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateProduct,
+    ) -> Result<Product, AppError> {
+        products::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_product(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        products::delete(&self.pool, actor, id).await
+    }
+
+    pub async fn restore_product(&self, actor: &User, id: Uuid) -> Result<Product, AppError> {
+        products::restore(&self.pool, actor, id).await
+    }
+
+    // 商品のオプション・バリエーションの取得と変更
+    pub async fn get_product_variants(&self, product_id: Uuid) -> Result<ProductVariants, AppError> {
+        variants::list(&self.pool, product_id).await
+    }
+
+    pub async fn set_product_options(
+        &self,
+        actor: &User,
+        product_id: Uuid,
+        options: Vec<SetProductOption>,
+    ) -> Result<ProductVariants, AppError> {
+        variants::set_options(&self.pool, actor, product_id, options).await
+    }
+
+    pub async fn update_product_variants(
+        &self,
+        actor: &User,
+        product_id: Uuid,
+        updates: Vec<UpdateProductVariant>,
+    ) -> Result<ProductVariants, AppError> {
+        variants::update_variants(&self.pool, actor, product_id, updates).await
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateOrder {
-    pub user_id: Option<Uuid>,
-    pub total_amount: f64,
-    pub status: Option<String>,
+    // 在庫の入出庫の記録の取得と、入庫・返品・調整の記録
+    pub async fn get_stock_movements(
+        &self,
+        query: StockMovementQuery,
+    ) -> Result<Page<StockMovement>, AppError> {
+        stock::list(&self.pool, &query).await
+    }
+
+    pub async fn adjust_stock(
+        &self,
+        actor: &User,
+        data: AdjustStock,
+    ) -> Result<StockMovement, AppError> {
+        stock::adjust(&self.pool, actor, data).await
+    }
+
+    pub async fn adjust_stock_batch(
+        &self,
+        actor: &User,
+        data: AdjustStockBatch,
+    ) -> Result<Vec<StockMovement>, AppError> {
+        stock::adjust_batch(&self.pool, actor, data).await
+    }
+
+    // 在庫数と入出庫の記録の突き合わせ
+    pub async fn get_stock_discrepancies(&self) -> Result<Vec<StockDiscrepancy>, AppError> {
+        Ok(stock::discrepancies(&self.pool).await?)
+    }
+
+    // 新しく在庫が発注点以下になった商品（バックグラウンドの確認用）と発注の提案
+    pub async fn new_low_stock(&self) -> Result<Vec<LowStockAlert>, sqlx::Error> {
+        reorder::new_low_stock(&self.pool).await
+    }
+
+    pub async fn get_reorder_suggestions(
+        &self,
+        query: ReorderQuery,
+    ) -> Result<Vec<ReorderSuggestion>, AppError> {
+        reorder::suggestions(&self.pool, query).await
+    }
+
+    // 商品の保管場所ごとの在庫数と、保管場所の間の移動
+    pub async fn get_stock_levels(&self, product_id: Uuid) -> Result<Vec<StockLevel>, AppError> {
+        Ok(stock::levels(&self.pool, product_id).await?)
+    }
+
+    pub async fn create_stock_transfer(
+        &self,
+        actor: &User,
+        data: CreateStockTransfer,
+    ) -> Result<StockTransfer, AppError> {
+        stock::transfer(&self.pool, actor, data).await
+    }
+
+    // 保管場所の取得（既定の保管場所が先頭）・作成・更新・削除
+    pub async fn get_warehouses(&self) -> Result<Vec<Warehouse>, AppError> {
+        Ok(warehouses::list(&self.pool).await?)
+    }
+
+    pub async fn create_warehouse(
+        &self,
+        actor: &User,
+        data: CreateWarehouse,
+    ) -> Result<Warehouse, AppError> {
+        warehouses::create(&self.pool, actor, data).await
+    }
+
+    pub async fn update_warehouse(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateWarehouse,
+    ) -> Result<Warehouse, AppError> {
+        warehouses::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_warehouse(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        warehouses::delete(&self.pool, actor, id).await
+    }
+
+    // 商品カテゴリの取得（階層順）・作成・更新・削除
+    pub async fn get_product_categories(&self) -> Result<Vec<ProductCategory>, AppError> {
+        Ok(categories::list(&self.pool).await?)
+    }
+
+    pub async fn create_product_category(
+        &self,
+        actor: &User,
+        data: CreateProductCategory,
+    ) -> Result<ProductCategory, AppError> {
+        categories::create(&self.pool, actor, data).await
+    }
+
+    pub async fn update_product_category(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateProductCategory,
+    ) -> Result<ProductCategory, AppError> {
+        categories::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_product_category(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        categories::delete(&self.pool, actor, id).await
+    }
+
+    // 注文の取得・更新・削除（論理削除）・復元
+    pub async fn get_order_by_id(&self, id: Uuid) -> Result<OrderWithItems, AppError> {
+        orders::get(&self.pool, id).await
+    }
+
+    pub async fn update_order(
+        &self,
+        actor: &User,
+        id: Uuid,
+        data: UpdateOrder,
+    ) -> Result<OrderWithItems, AppError> {
+        orders::update(&self.pool, actor, id, data).await
+    }
+
+    pub async fn delete_order(&self, actor: &User, id: Uuid) -> Result<(), AppError> {
+        orders::delete(&self.pool, actor, id).await
+    }
+
+    pub async fn restore_order(&self, actor: &User, id: Uuid) -> Result<OrderWithItems, AppError> {
+        orders::restore(&self.pool, actor, id).await
+    }
+
+    // 明細付きで注文を作成する（合計金額はサーバー側で計算）
+    pub async fn create_order(
+        &self,
+        actor: &User,
+        data: CreateOrder,
+    ) -> Result<OrderWithItems, AppError> {
+        orders::create(&self.pool, actor, data).await
+    }
+
+    // 注文ステータスを変更する（許可されていない遷移は拒否）
+    pub async fn transition_order(
+        &self,
+        actor: &User,
+        order_id: Uuid,
+        status: OrderStatus,
+        note: Option<String>,
+    ) -> Result<OrderWithItems, AppError> {
+        orders::transition(&self.pool, actor, order_id, status, note).await
+    }
+
+    // 注文ステータスの変更履歴を古い順に取得する
+    pub async fn get_order_status_history(
+        &self,
+        order_id: Uuid,
+    ) -> Result<Vec<OrderStatusHistory>, AppError> {
+        Ok(orders::status_history(&self.pool, order_id).await?)
+    }
+
+    // ユーザー・取引先・商品・注文の横断検索
+    pub async fn search(
+        &self,
+        text: &str,
+        limit: Option<u32>,
+        kinds: &[SearchKind],
+    ) -> Result<Vec<SearchHit>, AppError> {
+        search::search(&self.pool, text, limit, kinds).await
+    }
+
+    // 一覧の取得（ページ・並び替え・フィルター）
+    pub async fn get_users(&self, query: UserQuery) -> Result<Page<User>, AppError> {
+        users::list(&self.pool, &query).await
+    }
+
+    pub async fn get_clients(&self, query: ClientQuery) -> Result<Page<Client>, AppError> {
+        clients::list(&self.pool, &query).await
+    }
+
+    pub async fn get_products(&self, query: ProductQuery) -> Result<Page<Product>, AppError> {
+        products::list(&self.pool, &query).await
+    }
+
+    pub async fn get_orders(&self, query: OrderQuery) -> Result<Page<Order>, AppError> {
+        orders::list(&self.pool, &query).await
+    }
+
+    // 注文一覧を明細付きで取得する
+    pub async fn get_orders_with_items(
+        &self,
+        query: OrderQuery,
+    ) -> Result<Page<OrderWithItems>, AppError> {
+        orders::list_with_items(&self.pool, &query).await
+    }
+
+    // 保存期間を過ぎた削除済みのレコードを完全削除する
+    pub async fn purge_deleted(&self, actor: &User) -> Result<PurgeReport, AppError> {
+        purge::purge_deleted(&self.pool, actor).await
+    }
+
+    // 監査ログを新しい順に取得する
+    pub async fn get_audit_log(&self, query: AuditQuery) -> Result<Page<AuditLogEntry>, AppError> {
+        audit::list(&self.pool, &query).await
+    }
 }
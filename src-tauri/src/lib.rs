@@ -1,11 +1,58 @@
+mod addresses;
+mod alerts;
+mod audit;
+mod auth;
+mod categories;
+mod clients;
+mod connection;
 mod database;
+mod migrations;
+mod orders;
+mod products;
+mod profiles;
+mod purge;
+mod query;
+mod reorder;
+mod roles;
+mod search;
+mod secrets;
+mod stock;
+mod users;
+mod variants;
+mod warehouses;
 
-use database::{Database, User, Product, Order, CreateUser, CreateProduct, CreateOrder};
+use auth::Sessions;
+use connection::Connection;
+use profiles::ProfileStore;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Manager, State};
+use uuid::Uuid;
+use ws_models::{
+    Address, AdjustStock, AdjustStockBatch, AppError, AuditLogEntry, AuditQuery, Client,
+    ClientQuery, ConnectionConfig, ConnectionProfile, ConnectionSettings, ConnectionStatus,
+    CreateAddress, CreateClient, CreateOrder, CreateProduct, CreateProductCategory,
+    CreateStockTransfer, CreateUser, CreateWarehouse, MigrationStatus, Order, OrderQuery,
+    OrderStatus, OrderStatusHistory, OrderWithItems, Page, Permission, Product, ProductCategory,
+    ProductQuery, ProductVariants, PurgeReport, ReorderQuery, ReorderSuggestion, SearchHit,
+    SearchKind, Session, SetProductOption, StockDiscrepancy, StockLevel, StockMovement,
+    StockMovementQuery, StockTransfer, UpdateAddress, UpdateClient, UpdateOrder, UpdateProduct,
+    UpdateProductCategory, UpdateProductVariant, UpdateUser, UpdateWarehouse, User, UserQuery,
+    Warehouse,
+};
 
-// データベース状態
-pub type AppState<'a> = State<'a, Arc<Database>>;
+// データベース接続の状態（未接続のときは各コマンドが Unavailable を返す）
+pub type AppState<'a> = State<'a, Arc<Connection>>;
+
+// ログイン中のセッション
+// データのコマンドは token が有効でなければ Unauthorized、ロールに必要な権限がなければ Forbidden を返す
+pub type SessionState<'a> = State<'a, Sessions>;
+
+// 接続先のデータベースが変わったら、前のデータベースのユーザーでログインしたセッションは破棄する
+fn clear_sessions_if_moved(sessions: &Sessions, before: &ConnectionStatus, after: &ConnectionStatus) {
+    if before.profile != after.profile || before.settings != after.settings {
+        sessions.clear();
+    }
+}
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -13,92 +60,814 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+// 接続関連のコマンド
+#[tauri::command]
+fn get_connection_status(state: AppState<'_>) -> ConnectionStatus {
+    state.status()
+}
+
+#[tauri::command]
+fn connect_database(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    settings: ConnectionSettings,
+) -> Result<ConnectionStatus, AppError> {
+    let before = state.status();
+    let status = state.connect(settings)?;
+    clear_sessions_if_moved(&sessions, &before, &status);
+    Ok(status)
+}
+
+#[tauri::command]
+fn get_connection_config(state: AppState<'_>) -> ConnectionConfig {
+    state.config()
+}
+
+// プロファイルの保存・削除は設定画面から行うためログインが必要
+// 接続画面（ログイン前）からは接続先の変更と切り替えだけができる
+#[tauri::command]
+fn save_connection_profile(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    profile: ConnectionProfile,
+    password: Option<String>,
+) -> Result<ConnectionConfig, AppError> {
+    sessions.authorize(&token, Permission::ManageSettings)?;
+    let before = state.status();
+    let config = state.save_profile(profile, password)?;
+    clear_sessions_if_moved(&sessions, &before, &state.status());
+    Ok(config)
+}
+
+#[tauri::command]
+fn delete_connection_profile(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    name: String,
+) -> Result<ConnectionConfig, AppError> {
+    sessions.authorize(&token, Permission::ManageSettings)?;
+    state.delete_profile(&name)
+}
+
+#[tauri::command]
+fn select_connection_profile(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    name: String,
+) -> Result<ConnectionStatus, AppError> {
+    let before = state.status();
+    let status = state.select_profile(&name)?;
+    clear_sessions_if_moved(&sessions, &before, &status);
+    Ok(status)
+}
+
+// 認証関連のコマンド
+#[tauri::command]
+async fn is_setup_required(state: AppState<'_>) -> Result<bool, AppError> {
+    state.database()?.setup_required().await
+}
+
+// パスワードを持つユーザーがいないときだけ使え、登録したユーザーでログインする
+#[tauri::command]
+async fn setup_first_user(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    user_data: CreateUser,
+) -> Result<Session, AppError> {
+    let database = state.database()?;
+    let user = database.setup_first_user(user_data).await?;
+    let permissions = database.permissions(user.role).await?;
+    Ok(sessions.create(user, permissions))
+}
+
+#[tauri::command]
+async fn login(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    email: String,
+    password: String,
+) -> Result<Session, AppError> {
+    let database = state.database()?;
+    let user = database.authenticate(&email, &password).await?;
+    let permissions = database.permissions(user.role).await?;
+    Ok(sessions.create(user, permissions))
+}
+
+#[tauri::command]
+fn logout(sessions: SessionState<'_>, token: String) {
+    sessions.remove(&token);
+}
+
 // ユーザー関連のコマンド
 #[tauri::command]
-async fn get_users(state: AppState<'_>) -> Result<Vec<User>, String> {
-    let users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY created_at DESC")
-        .fetch_all(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(users)
+async fn get_users(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<UserQuery>,
+) -> Result<Page<User>, AppError> {
+    sessions.authorize(&token, Permission::ViewUsers)?;
+    state.database()?.get_users(query.unwrap_or_default()).await
 }
 
 #[tauri::command]
-async fn create_user(state: AppState<'_>, user_data: CreateUser) -> Result<User, String> {
-    let user = sqlx::query_as::<_, User>(
-        "INSERT INTO users (name, email) VALUES ($1, $2) RETURNING *"
-    )
-    .bind(&user_data.name)
-    .bind(&user_data.email)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+async fn create_user(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    user_data: CreateUser,
+) -> Result<User, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageUsers)?;
+    if user_data.role.is_some() {
+        sessions.authorize(&token, Permission::ManageRoles)?;
+    }
+    state.database()?.create_user(&session.user, user_data).await
+}
+
+#[tauri::command]
+async fn get_user_by_id(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<User, AppError> {
+    sessions.authorize(&token, Permission::ViewUsers)?;
+    state.database()?.get_user_by_id(id).await
+}
+
+#[tauri::command]
+async fn update_user(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    user_data: UpdateUser,
+) -> Result<User, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageUsers)?;
+    if user_data.role.is_some() {
+        sessions.authorize(&token, Permission::ManageRoles)?;
+    }
+    let password_changed = user_data.password.is_some();
+    let database = state.database()?;
+    let user = database.update_user(&session.user, id, user_data).await?;
+    let permissions = database.permissions(user.role).await?;
+    sessions.update_user(&user, &permissions);
+    if password_changed {
+        sessions.remove_other_sessions(id, &token);
+    }
     Ok(user)
 }
 
+#[tauri::command]
+async fn delete_user(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageUsers)?;
+    state.database()?.delete_user(&session.user, id).await?;
+    sessions.remove_user(id);
+    Ok(())
+}
+
+#[tauri::command]
+async fn restore_user(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<User, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageUsers)?;
+    state.database()?.restore_user(&session.user, id).await
+}
+
+// 取引先関連のコマンド
+#[tauri::command]
+async fn get_clients(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<ClientQuery>,
+) -> Result<Page<Client>, AppError> {
+    sessions.authorize(&token, Permission::ViewClients)?;
+    state.database()?.get_clients(query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn create_client(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    client_data: CreateClient,
+) -> Result<Client, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.create_client(&session.user, client_data).await
+}
+
+#[tauri::command]
+async fn get_client_by_id(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<Client, AppError> {
+    sessions.authorize(&token, Permission::ViewClients)?;
+    state.database()?.get_client_by_id(id).await
+}
+
+#[tauri::command]
+async fn update_client(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    client_data: UpdateClient,
+) -> Result<Client, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.update_client(&session.user, id, client_data).await
+}
+
+#[tauri::command]
+async fn delete_client(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.delete_client(&session.user, id).await
+}
+
+#[tauri::command]
+async fn restore_client(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<Client, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.restore_client(&session.user, id).await
+}
+
+// 取引先の住所（既定の請求先・配送先は update_client で変更する）
+#[tauri::command]
+async fn get_client_addresses(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    client_id: Uuid,
+) -> Result<Vec<Address>, AppError> {
+    sessions.authorize(&token, Permission::ViewClients)?;
+    state.database()?.get_client_addresses(client_id).await
+}
+
+#[tauri::command]
+async fn create_client_address(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    client_id: Uuid,
+    address_data: CreateAddress,
+) -> Result<Address, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state
+        .database()?
+        .create_client_address(&session.user, client_id, address_data)
+        .await
+}
+
+#[tauri::command]
+async fn update_client_address(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    address_data: UpdateAddress,
+) -> Result<Address, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.update_client_address(&session.user, id, address_data).await
+}
+
+#[tauri::command]
+async fn delete_client_address(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageClients)?;
+    state.database()?.delete_client_address(&session.user, id).await
+}
+
 // 商品関連のコマンド
 #[tauri::command]
-async fn get_products(state: AppState<'_>) -> Result<Vec<Product>, String> {
-    let products = sqlx::query_as::<_, Product>("SELECT * FROM products ORDER BY created_at DESC")
-        .fetch_all(&state.pool)
+async fn get_products(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<ProductQuery>,
+) -> Result<Page<Product>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_products(query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn create_product(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    product_data: CreateProduct,
+) -> Result<Product, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.create_product(&session.user, product_data).await
+}
+
+#[tauri::command]
+async fn get_product_by_id(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<Product, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_product_by_id(id).await
+}
+
+// バーコードリーダーで読み取った値から商品を探す
+#[tauri::command]
+async fn get_product_by_barcode(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    barcode: String,
+) -> Result<Product, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_product_by_barcode(&barcode).await
+}
+
+#[tauri::command]
+async fn update_product(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    product_data: UpdateProduct,
+) -> Result<Product, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.update_product(&session.user, id, product_data).await
+}
+
+#[tauri::command]
+async fn delete_product(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.delete_product(&session.user, id).await
+}
+
+#[tauri::command]
+async fn restore_product(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<Product, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.restore_product(&session.user, id).await
+}
+
+// 商品のオプションとバリエーション
+#[tauri::command]
+async fn get_product_variants(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    product_id: Uuid,
+) -> Result<ProductVariants, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_product_variants(product_id).await
+}
+
+// オプションを置き換え、値の組み合わせごとのバリエーションを作る
+#[tauri::command]
+async fn set_product_options(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    product_id: Uuid,
+    options: Vec<SetProductOption>,
+) -> Result<ProductVariants, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state
+        .database()?
+        .set_product_options(&session.user, product_id, options)
+        .await
+}
+
+// バリエーションの SKU・価格をまとめて更新する
+#[tauri::command]
+async fn update_product_variants(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    product_id: Uuid,
+    variants: Vec<UpdateProductVariant>,
+) -> Result<ProductVariants, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state
+        .database()?
+        .update_product_variants(&session.user, product_id, variants)
+        .await
+}
+
+// 在庫の入出庫の記録（新しい順。product_id で1つの商品の履歴に絞れる）
+#[tauri::command]
+async fn get_stock_movements(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<StockMovementQuery>,
+) -> Result<Page<StockMovement>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_stock_movements(query.unwrap_or_default()).await
+}
+
+// 入庫・返品・棚卸などの調整を記録する（在庫数は記録からだけ変わる）
+#[tauri::command]
+async fn adjust_stock(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    adjustment: AdjustStock,
+) -> Result<StockMovement, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.adjust_stock(&session.user, adjustment).await
+}
+
+// 在庫の調整をまとめて記録する（バリエーションの表で変更した在庫数）
+#[tauri::command]
+async fn adjust_stock_batch(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    batch: AdjustStockBatch,
+) -> Result<Vec<StockMovement>, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.adjust_stock_batch(&session.user, batch).await
+}
+
+// 在庫数と入出庫の記録の合計が一致しない商品・バリエーション
+#[tauri::command]
+async fn get_stock_discrepancies(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+) -> Result<Vec<StockDiscrepancy>, AppError> {
+    sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.get_stock_discrepancies().await
+}
+
+// 直近の販売の速さから求めた発注の提案（query がなければ既定の日数）
+#[tauri::command]
+async fn get_reorder_suggestions(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<ReorderQuery>,
+) -> Result<Vec<ReorderSuggestion>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state
+        .database()?
+        .get_reorder_suggestions(query.unwrap_or_default())
+        .await
+}
+
+// 商品の保管場所ごとの在庫数（記録のない保管場所は含まない）
+#[tauri::command]
+async fn get_stock_levels(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    product_id: Uuid,
+) -> Result<Vec<StockLevel>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_stock_levels(product_id).await
+}
+
+// 保管場所の間で在庫を移動する（移動伝票のすべての明細を1つのトランザクションで移動する）
+#[tauri::command]
+async fn create_stock_transfer(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    transfer: CreateStockTransfer,
+) -> Result<StockTransfer, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.create_stock_transfer(&session.user, transfer).await
+}
+
+// 保管場所（商品と同じ権限で閲覧・変更する）
+#[tauri::command]
+async fn get_warehouses(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+) -> Result<Vec<Warehouse>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_warehouses().await
+}
+
+#[tauri::command]
+async fn create_warehouse(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    warehouse_data: CreateWarehouse,
+) -> Result<Warehouse, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.create_warehouse(&session.user, warehouse_data).await
+}
+
+#[tauri::command]
+async fn update_warehouse(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    warehouse_data: UpdateWarehouse,
+) -> Result<Warehouse, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state
+        .database()?
+        .update_warehouse(&session.user, id, warehouse_data)
+        .await
+}
+
+#[tauri::command]
+async fn delete_warehouse(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.delete_warehouse(&session.user, id).await
+}
+
+// 商品カテゴリ（商品と同じ権限で閲覧・変更する）
+#[tauri::command]
+async fn get_product_categories(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+) -> Result<Vec<ProductCategory>, AppError> {
+    sessions.authorize(&token, Permission::ViewProducts)?;
+    state.database()?.get_product_categories().await
+}
+
+#[tauri::command]
+async fn create_product_category(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    category_data: CreateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.create_product_category(&session.user, category_data).await
+}
+
+#[tauri::command]
+async fn update_product_category(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    category_data: UpdateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state
+        .database()?
+        .update_product_category(&session.user, id, category_data)
         .await
-        .map_err(|e| e.to_string())?;
-    Ok(products)
 }
 
 #[tauri::command]
-async fn create_product(state: AppState<'_>, product_data: CreateProduct) -> Result<Product, String> {
-    let product = sqlx::query_as::<_, Product>(
-        "INSERT INTO products (name, description, price, stock) VALUES ($1, $2, $3, $4) RETURNING *"
-    )
-    .bind(&product_data.name)
-    .bind(&product_data.description)
-    .bind(product_data.price)
-    .bind(product_data.stock)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-    Ok(product)
+async fn delete_product_category(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageProducts)?;
+    state.database()?.delete_product_category(&session.user, id).await
 }
 
 // 注文関連のコマンド
 #[tauri::command]
-async fn get_orders(state: AppState<'_>) -> Result<Vec<Order>, String> {
-    let orders = sqlx::query_as::<_, Order>("SELECT * FROM orders ORDER BY created_at DESC")
-        .fetch_all(&state.pool)
+async fn get_orders(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<OrderQuery>,
+) -> Result<Page<Order>, AppError> {
+    sessions.authorize(&token, Permission::ViewOrders)?;
+    state.database()?.get_orders(query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn get_orders_with_items(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<OrderQuery>,
+) -> Result<Page<OrderWithItems>, AppError> {
+    sessions.authorize(&token, Permission::ViewOrders)?;
+    state.database()?.get_orders_with_items(query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn create_order(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    order_data: CreateOrder,
+) -> Result<OrderWithItems, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageOrders)?;
+    state.database()?.create_order(&session.user, order_data).await
+}
+
+#[tauri::command]
+async fn get_order_by_id(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<OrderWithItems, AppError> {
+    sessions.authorize(&token, Permission::ViewOrders)?;
+    state.database()?.get_order_by_id(id).await
+}
+
+#[tauri::command]
+async fn update_order(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+    order_data: UpdateOrder,
+) -> Result<OrderWithItems, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageOrders)?;
+    state.database()?.update_order(&session.user, id, order_data).await
+}
+
+#[tauri::command]
+async fn delete_order(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<(), AppError> {
+    let session = sessions.authorize(&token, Permission::ManageOrders)?;
+    state.database()?.delete_order(&session.user, id).await
+}
+
+#[tauri::command]
+async fn restore_order(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    id: Uuid,
+) -> Result<OrderWithItems, AppError> {
+    let session = sessions.authorize(&token, Permission::ManageOrders)?;
+    state.database()?.restore_order(&session.user, id).await
+}
+
+#[tauri::command]
+async fn transition_order(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    order_id: Uuid,
+    status: OrderStatus,
+    note: Option<String>,
+) -> Result<OrderWithItems, AppError> {
+    // 変更したユーザーとしてログイン中のユーザーを記録する
+    let session = sessions.authorize(&token, Permission::ManageOrders)?;
+    state
+        .database()?
+        .transition_order(&session.user, order_id, status, note)
         .await
-        .map_err(|e| e.to_string())?;
-    Ok(orders)
 }
 
 #[tauri::command]
-async fn create_order(state: AppState<'_>, order_data: CreateOrder) -> Result<Order, String> {
-    let status = order_data.status.unwrap_or_else(|| "pending".to_string());
-    let order = sqlx::query_as::<_, Order>(
-        "INSERT INTO orders (user_id, total_amount, status) VALUES ($1, $2, $3) RETURNING *"
-    )
-    .bind(&order_data.user_id)
-    .bind(order_data.total_amount)
-    .bind(&status)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-    Ok(order)
+async fn get_order_status_history(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    order_id: Uuid,
+) -> Result<Vec<OrderStatusHistory>, AppError> {
+    sessions.authorize(&token, Permission::ViewOrders)?;
+    state.database()?.get_order_status_history(order_id).await
 }
 
+// 横断検索コマンド
+#[tauri::command]
+async fn search(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>, AppError> {
+    // 結果は閲覧できる種類のレコードだけに絞る
+    let session = sessions.require(&token)?;
+    let kinds: Vec<SearchKind> = SearchKind::ALL
+        .into_iter()
+        .filter(|kind| session.can(view_permission(*kind)))
+        .collect();
+    state.database()?.search(&query, limit, &kinds).await
+}
+
+fn view_permission(kind: SearchKind) -> Permission {
+    match kind {
+        SearchKind::User => Permission::ViewUsers,
+        SearchKind::Client => Permission::ViewClients,
+        SearchKind::Product => Permission::ViewProducts,
+        SearchKind::Order => Permission::ViewOrders,
+    }
+}
+
+// 監査ログのコマンド（新しい順。entity と entity_id で1件のレコードの履歴に絞れる）
+#[tauri::command]
+async fn get_audit_log(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+    query: Option<AuditQuery>,
+) -> Result<Page<AuditLogEntry>, AppError> {
+    sessions.authorize(&token, Permission::ViewAuditLog)?;
+    state.database()?.get_audit_log(query.unwrap_or_default()).await
+}
+
+// 削除済みレコードの完全削除（保存期間を過ぎたものだけ）
+#[tauri::command]
+async fn purge_deleted(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+) -> Result<PurgeReport, AppError> {
+    let session = sessions.authorize(&token, Permission::PurgeRecords)?;
+    state.database()?.purge_deleted(&session.user).await
+}
+
+// マイグレーション関連のコマンド
+#[tauri::command]
+async fn get_migration_status(
+    state: AppState<'_>,
+    sessions: SessionState<'_>,
+    token: String,
+) -> Result<Vec<MigrationStatus>, AppError> {
+    sessions.authorize(&token, Permission::ManageSettings)?;
+    state.database()?.migration_status().await
+}
+
+// 登録するコマンドは ws_models::commands! の一覧から生成する
+macro_rules! handler {
+    ($($command:ident),* $(,)?) => {
+        tauri::generate_handler![$($command),*]
+    };
+}
+
+// デスクトップ（main.rs）とモバイルの共通エントリーポイント
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            get_users,
-            create_user,
-            get_products,
-            create_product,
-            get_orders,
-            create_order
-        ])
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            // 接続プロファイルはアプリ設定ディレクトリの connections.json から読み込む
+            let config_dir = app
+                .path()
+                .app_config_dir()
+                .map_err(|e| eprintln!("❌ Failed to resolve app config dir: {}", e))
+                .ok();
+            let profiles = ProfileStore::load(config_dir);
+
+            // データベースに接続できなくても画面は表示し、接続はバックグラウンドで続ける
+            let connection = Arc::new(Connection::new(profiles));
+            app.manage(connection.clone());
+            app.manage(Sessions::new());
+            tauri::async_runtime::spawn(connection.clone().supervise(app.handle().clone()));
+            // 在庫が発注点以下になったらイベントとデスクトップ通知で知らせる
+            tauri::async_runtime::spawn(alerts::monitor(connection, app.handle().clone()));
+            Ok(())
+        })
+        .invoke_handler(ws_models::commands!(handler))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,293 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::fmt;
+use ws_models::{AppError, MigrationState, MigrationStatus};
+
+// マイグレーション実行中の同時起動を防ぐアドバイザリロックのキー
+const MIGRATION_LOCK_KEY: i64 = 0x7773_6d69_6772;
+
+// マイグレーションファイルの定義
+// init-scripts/01-migrate.sh も同じディレクトリを参照するため、
+// ファイル名は `<4桁のバージョン>_<名前>.sql` の形式を守ること
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+// バージョン順に並べること
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../migrations/0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "money_currency",
+        sql: include_str!("../migrations/0002_money_currency.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "order_items",
+        sql: include_str!("../migrations/0003_order_items.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "order_status_history",
+        sql: include_str!("../migrations/0004_order_status_history.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "search",
+        sql: include_str!("../migrations/0005_search.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "user_credentials",
+        sql: include_str!("../migrations/0006_user_credentials.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "roles",
+        sql: include_str!("../migrations/0007_roles.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "audit_log",
+        sql: include_str!("../migrations/0008_audit_log.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "soft_delete",
+        sql: include_str!("../migrations/0009_soft_delete.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "clients",
+        sql: include_str!("../migrations/0010_clients.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "client_addresses",
+        sql: include_str!("../migrations/0011_client_addresses.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "product_catalog",
+        sql: include_str!("../migrations/0012_product_catalog.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "product_variants",
+        sql: include_str!("../migrations/0013_product_variants.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "stock_movements",
+        sql: include_str!("../migrations/0014_stock_movements.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "warehouses",
+        sql: include_str!("../migrations/0015_warehouses.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "low_stock_alerts",
+        sql: include_str!("../migrations/0016_low_stock_alerts.sql"),
+    },
+];
+
+impl Migration {
+    // ファイル内容の SHA-256（sha256sum と同じ16進表記）
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.sql.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(sqlx::Error),
+    ChecksumMismatch { version: i64, name: String },
+    UnknownVersion { version: i64, name: String },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Database(e) => write!(f, "{}", e),
+            MigrationError::ChecksumMismatch { version, name } => write!(
+                f,
+                "migration {:04}_{} was modified after it was applied",
+                version, name
+            ),
+            MigrationError::UnknownVersion { version, name } => write!(
+                f,
+                "database has migration {:04}_{} which this app does not know; update the app",
+                version, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<MigrationError> for AppError {
+    fn from(e: MigrationError) -> Self {
+        match e {
+            MigrationError::Database(e) => e.into(),
+            other => AppError::internal(other.to_string()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(e: sqlx::Error) -> Self {
+        MigrationError::Database(e)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AppliedMigration {
+    version: i64,
+    name: String,
+    checksum: String,
+    applied_at: DateTime<Utc>,
+}
+
+async fn ensure_table(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            checksum CHAR(64) NOT NULL,
+            applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+async fn applied(conn: &mut PgConnection) -> Result<Vec<AppliedMigration>, sqlx::Error> {
+    sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version",
+    )
+    .fetch_all(conn)
+    .await
+}
+
+// 未適用のマイグレーションを順番に適用し、適用したバージョンを返す
+pub async fn run(pool: &PgPool) -> Result<Vec<i64>, MigrationError> {
+    let mut conn = pool.acquire().await?;
+
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = apply_pending(&mut conn).await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    result
+}
+
+async fn apply_pending(conn: &mut PgConnection) -> Result<Vec<i64>, MigrationError> {
+    ensure_table(conn).await?;
+
+    let applied = applied(conn).await?;
+    for row in &applied {
+        match MIGRATIONS.iter().find(|m| m.version == row.version) {
+            Some(m) if m.checksum() != row.checksum.trim() => {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: row.version,
+                    name: row.name.clone(),
+                });
+            }
+            Some(_) => {}
+            None => {
+                return Err(MigrationError::UnknownVersion {
+                    version: row.version,
+                    name: row.name.clone(),
+                });
+            }
+        }
+    }
+
+    let mut newly_applied = Vec::new();
+    for m in MIGRATIONS {
+        if applied.iter().any(|row| row.version == m.version) {
+            continue;
+        }
+
+        let mut tx = conn.begin().await?;
+        // raw_sql(..).execute(..) の Future は Send と判定されず、
+        // 接続を監視するバックグラウンドタスクから呼べないため Executor 側から実行する
+        (&mut *tx).execute(sqlx::raw_sql(m.sql)).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(m.version)
+            .bind(m.name)
+            .bind(m.checksum())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        newly_applied.push(m.version);
+    }
+
+    Ok(newly_applied)
+}
+
+// アプリに含まれるマイグレーションとデータベースの適用状況を突き合わせる
+pub async fn status(pool: &PgPool) -> Result<Vec<MigrationStatus>, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    ensure_table(&mut conn).await?;
+    let applied = applied(&mut conn).await?;
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATIONS
+        .iter()
+        .map(|m| match applied.iter().find(|row| row.version == m.version) {
+            Some(row) => MigrationStatus {
+                version: m.version,
+                name: m.name.to_string(),
+                state: if m.checksum() == row.checksum.trim() {
+                    MigrationState::Applied
+                } else {
+                    MigrationState::Modified
+                },
+                applied_at: Some(row.applied_at),
+            },
+            None => MigrationStatus {
+                version: m.version,
+                name: m.name.to_string(),
+                state: MigrationState::Pending,
+                applied_at: None,
+            },
+        })
+        .collect();
+
+    for row in &applied {
+        if !MIGRATIONS.iter().any(|m| m.version == row.version) {
+            statuses.push(MigrationStatus {
+                version: row.version,
+                name: row.name.clone(),
+                state: MigrationState::Unknown,
+                applied_at: Some(row.applied_at),
+            });
+        }
+    }
+
+    statuses.sort_by_key(|s| s.version);
+    Ok(statuses)
+}
@@ -0,0 +1,920 @@
+use rust_decimal::Decimal;
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, Client, CreateOrder, CurrencyCode, FieldError, Money,
+    Order, OrderFilter, OrderItem, OrderQuery, OrderSort, OrderStatus, OrderStatusHistory,
+    OrderWithItems, Page, PostalAddress, StockMovementType, UpdateOrder, User,
+    ORDER_RETENTION_YEARS, PURGE_AFTER_DAYS,
+};
+
+use crate::{addresses, audit, clients, stock, warehouses};
+use crate::query::{self, Filter, SortField};
+
+// 明細ごとの確定済み金額
+struct PricedLine {
+    product_id: Uuid,
+    variant_id: Option<Uuid>,
+    quantity: i32,
+    unit_price: Money,
+    discount: Money,
+}
+
+// 行ロック中の商品
+struct LockedProduct {
+    name: String,
+    price: Money,
+    discontinued: bool,
+}
+
+// 行ロック中のバリエーション
+struct LockedVariant {
+    product_id: Uuid,
+    label: String,
+    price: Option<Decimal>,
+}
+
+// 注文と明細を1つのトランザクションで作成し、出荷する保管場所の在庫を引き当てる
+// 単価は現在の products.price（バリエーションに価格があればその価格）を使い、合計金額もここで計算する
+// 商品行は ID 順に FOR UPDATE でロックするため、同じ商品の同時注文でも在庫を超えて販売しない
+// バリエーションの在庫も商品行のロック中に変更する（商品の在庫はバリエーションの在庫の合計のまま）
+pub async fn create(
+    pool: &PgPool,
+    actor: &User,
+    data: CreateOrder,
+) -> Result<OrderWithItems, AppError> {
+    if data.items.is_empty() {
+        return Err(AppError::field("items", "order must contain at least one item"));
+    }
+    let mut fields = Vec::new();
+    for (i, item) in data.items.iter().enumerate() {
+        if item.quantity <= 0 {
+            fields.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                "must be greater than zero",
+            ));
+        }
+        if item.discount.is_some_and(|d| d < Decimal::ZERO) {
+            fields.push(FieldError::new(
+                format!("items.{}.discount", i),
+                "must not be negative",
+            ));
+        }
+    }
+    AppError::check_fields(fields)?;
+
+    let mut tx = pool.begin().await?;
+    let (billing_address, shipping_address) = match data.client_id {
+        Some(client_id) => {
+            let client = clients::active(&mut tx, client_id).await?;
+            let billing_address = address_snapshot(
+                &mut tx,
+                &client,
+                data.billing_address_id.or(client.default_billing_address_id),
+                "billing_address_id",
+            )
+            .await?;
+            let shipping_address = address_snapshot(
+                &mut tx,
+                &client,
+                data.shipping_address_id.or(client.default_shipping_address_id),
+                "shipping_address_id",
+            )
+            .await?;
+            (billing_address, shipping_address)
+        }
+        None if data.billing_address_id.is_some() || data.shipping_address_id.is_some() => {
+            return Err(AppError::field("client_id", "is required to choose an address"));
+        }
+        None => (None, None),
+    };
+    let warehouse = warehouses::resolve(&mut tx, data.warehouse_id, "warehouse_id").await?;
+
+    let mut product_ids: Vec<Uuid> = data.items.iter().map(|i| i.product_id).collect();
+    product_ids.sort();
+    product_ids.dedup();
+
+    let rows: Vec<(Uuid, String, Decimal, String, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, name, price, currency, discontinued FROM products
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(&product_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut products = HashMap::new();
+    for (id, name, amount, currency, discontinued) in rows {
+        let currency: CurrencyCode = currency.parse().map_err(AppError::internal)?;
+        products.insert(
+            id,
+            LockedProduct {
+                name,
+                price: Money::new(amount, currency),
+                discontinued,
+            },
+        );
+    }
+
+    let missing: Vec<FieldError> = data
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !products.contains_key(&item.product_id))
+        .map(|(i, _)| FieldError::new(format!("items.{}.product_id", i), "product not found"))
+        .collect();
+    AppError::check_fields(missing)?;
+
+    // 取扱終了の商品は新しく注文できない
+    let discontinued: Vec<FieldError> = data
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| products[&item.product_id].discontinued)
+        .map(|(i, item)| {
+            FieldError::new(
+                format!("items.{}.product_id", i),
+                format!("{} is discontinued", products[&item.product_id].name),
+            )
+        })
+        .collect();
+    AppError::check_fields(discontinued)?;
+
+    let mut variant_ids: Vec<Uuid> = data.items.iter().filter_map(|i| i.variant_id).collect();
+    variant_ids.sort();
+    variant_ids.dedup();
+    let variant_rows: Vec<(Uuid, Uuid, String, Option<Decimal>)> = sqlx::query_as(
+        r#"
+        SELECT id, product_id, array_to_string(option_values, ' / '), price
+        FROM product_variants
+        WHERE id = ANY($1)
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(&variant_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    let variants: HashMap<Uuid, LockedVariant> = variant_rows
+        .into_iter()
+        .map(|(id, product_id, label, price)| {
+            (id, LockedVariant { product_id, label, price })
+        })
+        .collect();
+    let with_variants: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT DISTINCT product_id FROM product_variants WHERE product_id = ANY($1)",
+    )
+    .bind(&product_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // バリエーションのある商品はバリエーションの指定が必要（他の商品のバリエーションは指定できない）
+    let invalid_variants: Vec<FieldError> = data
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let field = format!("items.{}.variant_id", i);
+            match item.variant_id {
+                Some(variant_id) => variants
+                    .get(&variant_id)
+                    .filter(|v| v.product_id == item.product_id)
+                    .is_none()
+                    .then(|| FieldError::new(field, "variant not found")),
+                None => with_variants.contains(&item.product_id).then(|| {
+                    FieldError::new(
+                        field,
+                        format!("choose a variant of {}", products[&item.product_id].name),
+                    )
+                }),
+            }
+        })
+        .collect();
+    AppError::check_fields(invalid_variants)?;
+
+    // 出荷する保管場所の在庫（バリエーションのない商品は variant_id が None）
+    let level_rows: Vec<(Uuid, Option<Uuid>, i32)> = sqlx::query_as(
+        r#"
+        SELECT product_id, variant_id, quantity FROM stock_levels
+        WHERE warehouse_id = $1 AND product_id = ANY($2)
+        "#,
+    )
+    .bind(warehouse.id)
+    .bind(&product_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    let levels: HashMap<(Uuid, Option<Uuid>), i32> = level_rows
+        .into_iter()
+        .map(|(product_id, variant_id, quantity)| ((product_id, variant_id), quantity))
+        .collect();
+
+    // 同じ商品（バリエーション）が複数の明細に含まれる場合は数量を合算して在庫と比較する
+    let mut requested: HashMap<(Uuid, Option<Uuid>), i64> = HashMap::new();
+    for item in &data.items {
+        *requested
+            .entry((item.product_id, item.variant_id))
+            .or_default() += i64::from(item.quantity);
+    }
+    // 在庫不足は入力の誤りではなく現在の在庫状態との競合として返す
+    let shortages: Vec<FieldError> = data
+        .items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let key = (item.product_id, item.variant_id);
+            let stock = levels.get(&key).copied().unwrap_or(0);
+            if requested[&key] <= i64::from(stock) {
+                return None;
+            }
+            let product = &products[&item.product_id];
+            let name = match item.variant_id {
+                Some(id) => format!("{} ({})", product.name, variants[&id].label),
+                None => product.name.clone(),
+            };
+            Some(FieldError::new(
+                format!("items.{}.quantity", i),
+                format!("only {} of {} in stock at {}", stock, name, warehouse.name),
+            ))
+        })
+        .collect();
+    if !shortages.is_empty() {
+        return Err(AppError::Conflict {
+            message: format!("insufficient stock at {}", warehouse.name),
+            constraint: None,
+            fields: shortages,
+        });
+    }
+
+    let mut currencies: Vec<CurrencyCode> = products.values().map(|p| p.price.currency).collect();
+    currencies.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    currencies.dedup();
+    let currency = match currencies.as_slice() {
+        [currency] => *currency,
+        _ => {
+            let codes: Vec<&str> = currencies.iter().map(|c| c.as_str()).collect();
+            return Err(AppError::field(
+                "items",
+                format!("order items must share one currency ({})", codes.join(", ")),
+            ));
+        }
+    };
+
+    let mut lines = Vec::with_capacity(data.items.len());
+    let mut total = Money::zero(currency);
+    for (i, item) in data.items.iter().enumerate() {
+        let product_price = products[&item.product_id].price;
+        let unit_price = match item.variant_id.and_then(|id| variants[&id].price) {
+            Some(amount) => Money::new(amount, product_price.currency),
+            None => product_price,
+        };
+        let discount = Money::new(item.discount.unwrap_or(Decimal::ZERO), currency);
+        let subtotal = unit_price
+            .checked_mul(item.quantity.into())
+            .ok_or_else(|| AppError::field(format!("items.{}.quantity", i), "line amount is too large"))?;
+        if discount.amount > subtotal.amount {
+            return Err(AppError::field(
+                format!("items.{}.discount", i),
+                "must not exceed the line amount",
+            ));
+        }
+        total = subtotal
+            .checked_sub(discount)
+            .and_then(|line_total| total.checked_add(line_total))
+            .ok_or_else(|| AppError::field("items", "order total is too large"))?;
+
+        lines.push(PricedLine {
+            product_id: item.product_id,
+            variant_id: item.variant_id,
+            quantity: item.quantity,
+            unit_price,
+            discount,
+        });
+    }
+
+    let order = sqlx::query_as::<_, Order>(
+        r#"
+        INSERT INTO orders (
+            client_id, billing_address, shipping_address, warehouse_id, total_amount, currency,
+            status
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(data.client_id)
+    .bind(billing_address.map(Json))
+    .bind(shipping_address.map(Json))
+    .bind(warehouse.id)
+    .bind(total.amount)
+    .bind(total.currency.as_str())
+    .bind(OrderStatus::Pending.as_str())
+    .fetch_one(&mut *tx)
+    .await?;
+    record_status(&mut tx, order.id, None, OrderStatus::Pending, Some(actor.id), None).await?;
+
+    let mut items = Vec::with_capacity(lines.len());
+    for line in lines {
+        let item = sqlx::query_as::<_, OrderItem>(
+            r#"
+            INSERT INTO order_items (
+                order_id, product_id, variant_id, quantity, unit_price, discount, currency
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(order.id)
+        .bind(line.product_id)
+        .bind(line.variant_id)
+        .bind(line.quantity)
+        .bind(line.unit_price.amount)
+        .bind(line.discount.amount)
+        .bind(line.unit_price.currency.as_str())
+        .fetch_one(&mut *tx)
+        .await?;
+        items.push(item);
+    }
+
+    // 明細ごとに保管場所からの出庫を記録する（商品・バリエーション・保管場所の在庫はトリガーで減る）
+    stock::record_order(&mut tx, actor, order.id, StockMovementType::Sale, "Order placed")
+        .await?;
+
+    let created = OrderWithItems { order, items };
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Order,
+        created.order.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(created)
+}
+
+// 注文を明細付きで1件取得する
+pub async fn get(pool: &PgPool, id: Uuid) -> Result<OrderWithItems, AppError> {
+    let mut conn = pool.acquire().await?;
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::not_found("order", id))?;
+    let items = items_for(&mut conn, &[id]).await?;
+    Ok(OrderWithItems { order, items })
+}
+
+// 更新・削除の前の状態（監査ログ用）を取得し、トランザクションの終わりまで注文行をロックする
+// 削除済みの注文は NotFound
+async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<OrderWithItems, AppError> {
+    let order = lock_any(conn, id).await?;
+    if order.order.deleted_at.is_some() {
+        return Err(AppError::not_found("order", id));
+    }
+    Ok(order)
+}
+
+// 削除済みの注文も含めて注文行をロックする
+async fn lock_any(conn: &mut PgConnection, id: Uuid) -> Result<OrderWithItems, AppError> {
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::not_found("order", id))?;
+    let items = items_for(conn, &[id]).await?;
+    Ok(OrderWithItems { order, items })
+}
+
+// 指定されたフィールドだけを更新する
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateOrder,
+) -> Result<OrderWithItems, AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    let current = &before.order;
+    // 出荷後やキャンセル済みの注文の配送先と保管場所は変更できない（顧客を変更しても配送先はそのまま残す）
+    let can_ship = current.status.holds_stock();
+    let locked = match current.status {
+        _ if can_ship => None,
+        OrderStatus::Cancelled => Some((
+            "cannot be changed on a cancelled order",
+            "the order has been cancelled",
+        )),
+        _ => Some((
+            "cannot be changed after shipping",
+            "the order has already been shipped",
+        )),
+    };
+    if let Some((field_message, message)) = locked {
+        let fields: Vec<FieldError> = [
+            ("shipping_address_id", data.shipping_address_id.is_some()),
+            ("warehouse_id", data.warehouse_id.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, requested)| *requested)
+        .map(|(field, _)| FieldError::new(field, field_message))
+        .collect();
+        if !fields.is_empty() {
+            return Err(AppError::Conflict {
+                message: message.to_string(),
+                constraint: None,
+                fields,
+            });
+        }
+    }
+
+    // 顧客を変更したら、住所は新しい顧客の既定の住所（指定があればその住所）で保存し直す
+    let client_id = data.client_id.unwrap_or(current.client_id);
+    let client_changed = client_id != current.client_id;
+    let mut billing_address = current.billing_address.clone();
+    let mut shipping_address = current.shipping_address.clone();
+    match client_id {
+        Some(client_id)
+            if client_changed
+                || data.billing_address_id.is_some()
+                || data.shipping_address_id.is_some() =>
+        {
+            let client = clients::active(&mut tx, client_id).await?;
+            if client_changed || data.billing_address_id.is_some() {
+                billing_address = address_snapshot(
+                    &mut tx,
+                    &client,
+                    data.billing_address_id.or(client.default_billing_address_id),
+                    "billing_address_id",
+                )
+                .await?;
+            }
+            if (client_changed && can_ship) || data.shipping_address_id.is_some() {
+                shipping_address = address_snapshot(
+                    &mut tx,
+                    &client,
+                    data.shipping_address_id.or(client.default_shipping_address_id),
+                    "shipping_address_id",
+                )
+                .await?;
+            }
+        }
+        Some(_) => {}
+        None if data.billing_address_id.is_some() || data.shipping_address_id.is_some() => {
+            return Err(AppError::field("client_id", "is required to choose an address"));
+        }
+        None => {
+            billing_address = None;
+            if can_ship {
+                shipping_address = None;
+            }
+        }
+    }
+
+    // 保管場所を変更したら、引き当て済みの在庫を元の保管場所に戻して新しい保管場所から引き当て直す
+    let warehouse_id = match data.warehouse_id {
+        Some(warehouse_id) => {
+            warehouses::resolve(&mut tx, Some(warehouse_id), "warehouse_id")
+                .await?
+                .id
+        }
+        None => current.warehouse_id,
+    };
+    let move_stock = warehouse_id != current.warehouse_id;
+    if move_stock {
+        restock(&mut tx, actor, id, "Warehouse changed").await?;
+    }
+
+    let order = sqlx::query_as::<_, Order>(
+        r#"
+        UPDATE orders
+        SET client_id = $2,
+            billing_address = $3,
+            shipping_address = $4,
+            warehouse_id = $5,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(client_id)
+    .bind(billing_address.map(Json))
+    .bind(shipping_address.map(Json))
+    .bind(warehouse_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if move_stock {
+        reserve(&mut tx, actor, id, "Warehouse changed").await?;
+    }
+    let items = items_for(&mut tx, &[id]).await?;
+    let updated = OrderWithItems { order, items };
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Order,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// 注文に保存する住所（取引先の住所の現在の内容）
+async fn address_snapshot(
+    conn: &mut PgConnection,
+    client: &Client,
+    address_id: Option<Uuid>,
+    field: &str,
+) -> Result<Option<PostalAddress>, AppError> {
+    match address_id {
+        Some(address_id) => {
+            let address = addresses::of_client(conn, client.id, address_id, field).await?;
+            Ok(Some(address.address))
+        }
+        None => Ok(None),
+    }
+}
+
+// 注文を論理削除する（明細とステータス履歴は残る）
+// 出荷前の注文は引き当て済みの在庫を戻す
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let before = lock(&mut tx, id).await?;
+    if before.order.status.holds_stock() {
+        restock(&mut tx, actor, id, "Order deleted").await?;
+    }
+
+    let order = sqlx::query_as::<_, Order>(
+        "UPDATE orders SET deleted_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let deleted = OrderWithItems {
+        order,
+        items: before.items.clone(),
+    };
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Order,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        Some(&deleted),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// 論理削除を取り消す（削除されていない注文は Conflict）
+// 出荷前の注文は在庫を引き当て直し、在庫が足りなければ Conflict にする
+pub async fn restore(pool: &PgPool, actor: &User, id: Uuid) -> Result<OrderWithItems, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let before = lock_any(&mut tx, id).await?;
+    if before.order.deleted_at.is_none() {
+        return Err(AppError::Conflict {
+            message: "the order is not deleted".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    if before.order.status.holds_stock() {
+        reserve(&mut tx, actor, id, "Order restored").await?;
+    }
+
+    let order = sqlx::query_as::<_, Order>(
+        "UPDATE orders SET deleted_at = NULL WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let restored = OrderWithItems {
+        order,
+        items: before.items.clone(),
+    };
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Order,
+        id,
+        AuditAction::Restore,
+        Some(&before),
+        Some(&restored),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(restored)
+}
+
+// 削除から PURGE_AFTER_DAYS 日を過ぎ、作成から ORDER_RETENTION_YEARS 年を過ぎた注文を完全削除する
+// 明細とステータス履歴も削除される
+pub async fn purge(conn: &mut PgConnection, actor: &User) -> Result<u64, AppError> {
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM orders
+        WHERE deleted_at < NOW() - make_interval(days => $1)
+          AND created_at < NOW() - make_interval(years => $2)
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(PURGE_AFTER_DAYS)
+    .bind(ORDER_RETENTION_YEARS)
+    .fetch_all(&mut *conn)
+    .await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    // 監査ログに残すため、明細も含めて削除前の値を取得する
+    let orders = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = ANY($1)")
+        .bind(&ids)
+        .fetch_all(&mut *conn)
+        .await?;
+    let mut items_by_order: HashMap<Uuid, Vec<OrderItem>> = HashMap::new();
+    for item in items_for(&mut *conn, &ids).await? {
+        items_by_order.entry(item.order_id).or_default().push(item);
+    }
+
+    sqlx::query("DELETE FROM orders WHERE id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *conn)
+        .await?;
+    for order in orders {
+        let items = items_by_order.remove(&order.id).unwrap_or_default();
+        let purged = OrderWithItems { order, items };
+        audit::record_change(
+            &mut *conn,
+            actor,
+            AuditEntity::Order,
+            purged.order.id,
+            AuditAction::Purge,
+            Some(&purged),
+            None,
+        )
+        .await?;
+    }
+    Ok(ids.len() as u64)
+}
+
+// 注文ステータスを変更し、変更履歴を記録する
+// キャンセルへの遷移では明細の数量を在庫に戻す
+pub async fn transition(
+    pool: &PgPool,
+    actor: &User,
+    order_id: Uuid,
+    to: OrderStatus,
+    note: Option<String>,
+) -> Result<OrderWithItems, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let before = lock(&mut tx, order_id).await?;
+    let from = before.order.status;
+    if !from.can_transition_to(to) {
+        return Err(AppError::Conflict {
+            message: format!("order status cannot change from {} to {}", from, to),
+            constraint: None,
+            fields: vec![FieldError::new("status", format!("cannot change from {}", from))],
+        });
+    }
+
+    if to == OrderStatus::Cancelled {
+        restock(&mut tx, actor, order_id, "Order cancelled").await?;
+    }
+
+    let order = sqlx::query_as::<_, Order>("UPDATE orders SET status = $2 WHERE id = $1 RETURNING *")
+        .bind(order_id)
+        .bind(to.as_str())
+        .fetch_one(&mut *tx)
+        .await?;
+    record_status(&mut tx, order_id, Some(from), to, Some(actor.id), note).await?;
+    let items = items_for(&mut tx, &[order_id]).await?;
+    let updated = OrderWithItems { order, items };
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Order,
+        order_id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+// 注文明細の商品行を ID 順にロックする（注文作成とロック順をそろえる）
+async fn lock_products(conn: &mut PgConnection, order_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        SELECT id FROM products
+        WHERE id IN (SELECT product_id FROM order_items WHERE order_id = $1)
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(order_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+// 明細の数量を注文の保管場所の在庫に戻す（返品として記録し、バリエーションの明細はバリエーションの在庫にも戻る）
+async fn restock(
+    conn: &mut PgConnection,
+    actor: &User,
+    order_id: Uuid,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    lock_products(conn, order_id).await?;
+    stock::record_order(conn, actor, order_id, StockMovementType::Return, reason).await
+}
+
+// 明細の数量を注文の保管場所の在庫から引き当て直す
+// 1つでも保管場所の在庫が足りない商品・バリエーションがあれば Conflict
+async fn reserve(
+    conn: &mut PgConnection,
+    actor: &User,
+    order_id: Uuid,
+    reason: &str,
+) -> Result<(), AppError> {
+    lock_products(conn, order_id).await?;
+
+    let short: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT w.name
+        FROM orders o
+        JOIN warehouses w ON w.id = o.warehouse_id
+        WHERE o.id = $1
+          AND EXISTS (
+            SELECT 1
+            FROM (
+                SELECT product_id, variant_id, SUM(quantity) AS quantity
+                FROM order_items
+                WHERE order_id = $1
+                GROUP BY product_id, variant_id
+            ) reserve
+            LEFT JOIN stock_levels l
+                ON l.warehouse_id = o.warehouse_id
+               AND l.product_id = reserve.product_id
+               AND l.variant_id IS NOT DISTINCT FROM reserve.variant_id
+            WHERE COALESCE(l.quantity, 0) < reserve.quantity
+          )
+        "#,
+    )
+    .bind(order_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(warehouse) = short {
+        return Err(AppError::Conflict {
+            message: format!("not enough stock at {} for the order", warehouse),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    stock::record_order(conn, actor, order_id, StockMovementType::Sale, reason).await?;
+    Ok(())
+}
+
+async fn record_status(
+    conn: &mut PgConnection,
+    order_id: Uuid,
+    from: Option<OrderStatus>,
+    to: OrderStatus,
+    changed_by: Option<Uuid>,
+    note: Option<String>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO order_status_history (order_id, from_status, to_status, changed_by, note)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(order_id)
+    .bind(from.map(|s| s.as_str()))
+    .bind(to.as_str())
+    .bind(changed_by)
+    .bind(note)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// 注文ステータスの変更履歴を古い順に取得する
+pub async fn status_history(
+    pool: &PgPool,
+    order_id: Uuid,
+) -> Result<Vec<OrderStatusHistory>, sqlx::Error> {
+    sqlx::query_as::<_, OrderStatusHistory>(
+        r#"
+        SELECT h.*, u.name AS changed_by_name
+        FROM order_status_history h
+        LEFT JOIN users u ON u.id = h.changed_by
+        WHERE h.order_id = $1
+        ORDER BY h.changed_at, h.id
+        "#,
+    )
+    .bind(order_id)
+    .fetch_all(pool)
+    .await
+}
+
+// 指定した注文の明細をまとめて取得する
+pub async fn items_for(
+    conn: &mut PgConnection,
+    order_ids: &[Uuid],
+) -> Result<Vec<OrderItem>, sqlx::Error> {
+    sqlx::query_as::<_, OrderItem>(
+        "SELECT * FROM order_items WHERE order_id = ANY($1) ORDER BY created_at, id",
+    )
+    .bind(order_ids)
+    .fetch_all(conn)
+    .await
+}
+
+impl SortField for OrderSort {
+    fn column(&self) -> &'static str {
+        match self {
+            OrderSort::CreatedAt => "created_at",
+            OrderSort::TotalAmount => "total_amount",
+            OrderSort::Status => "status",
+        }
+    }
+}
+
+impl Filter for OrderFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(ids) = &self.ids {
+            builder.push(" AND id = ANY(").push_bind(ids.clone()).push(")");
+        }
+        if let Some(statuses) = self.status.as_ref().filter(|s| !s.is_empty()) {
+            let statuses: Vec<&str> = statuses.iter().map(|s| s.as_str()).collect();
+            builder.push(" AND status = ANY(").push_bind(statuses).push(")");
+        }
+        if let Some(client_id) = self.client_id {
+            builder.push(" AND client_id = ").push_bind(client_id);
+        }
+        if let Some(warehouse_id) = self.warehouse_id {
+            builder.push(" AND warehouse_id = ").push_bind(warehouse_id);
+        }
+        if let Some(from) = self.created_from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+        if let Some(min) = self.min_total {
+            builder.push(" AND total_amount >= ").push_bind(min);
+        }
+        if let Some(max) = self.max_total {
+            builder.push(" AND total_amount <= ").push_bind(max);
+        }
+        if !self.include_deleted {
+            builder.push(" AND deleted_at IS NULL");
+        }
+    }
+}
+
+pub async fn list(pool: &PgPool, query: &OrderQuery) -> Result<Page<Order>, AppError> {
+    query::fetch_page(pool, "orders", query).await
+}
+
+// 注文一覧を明細付きで取得する（明細は1ページ分の注文についてまとめて取得）
+pub async fn list_with_items(
+    pool: &PgPool,
+    query: &OrderQuery,
+) -> Result<Page<OrderWithItems>, AppError> {
+    let page = list(pool, query).await?;
+
+    let mut conn = pool.acquire().await?;
+    let order_ids: Vec<Uuid> = page.items.iter().map(|o| o.id).collect();
+    let mut items_by_order: HashMap<Uuid, Vec<OrderItem>> = HashMap::new();
+    for item in items_for(&mut conn, &order_ids).await? {
+        items_by_order.entry(item.order_id).or_default().push(item);
+    }
+
+    Ok(page.map(|order| {
+        let items = items_by_order.remove(&order.id).unwrap_or_default();
+        OrderWithItems { order, items }
+    }))
+}
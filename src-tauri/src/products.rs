@@ -0,0 +1,550 @@
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use ws_models::{
+    AdjustStock, AppError, AuditAction, AuditEntity, CreateProduct, FieldError, Money, Page,
+    Product, ProductFilter, ProductQuery, ProductSort, StockMovementType, UpdateProduct, User,
+    PURGE_AFTER_DAYS,
+};
+
+use crate::query::{self, contains_pattern, Filter, SortField};
+use crate::{audit, categories, stock};
+
+impl SortField for ProductSort {
+    fn column(&self) -> &'static str {
+        match self {
+            ProductSort::CreatedAt => "created_at",
+            ProductSort::Name => "name",
+            ProductSort::Sku => "sku",
+            ProductSort::Price => "price",
+            ProductSort::Stock => "stock",
+        }
+    }
+}
+
+impl Filter for ProductFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(ids) = &self.ids {
+            builder.push(" AND id = ANY(").push_bind(ids.clone()).push(")");
+        }
+        if let Some(search) = self.search.as_deref().filter(|s| !s.trim().is_empty()) {
+            let pattern = contains_pattern(search);
+            builder.push(" AND (name ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR sku ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR barcode ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR description ILIKE ").push_bind(pattern.clone());
+            // バリエーションの SKU でも探せる
+            builder
+                .push(" OR id IN (SELECT product_id FROM product_variants WHERE sku ILIKE ")
+                .push_bind(pattern)
+                .push("))");
+        }
+        if let Some(category_id) = self.category_id {
+            builder.push(
+                r#" AND category_id IN (
+                    WITH RECURSIVE subtree AS (
+                        SELECT id FROM product_categories WHERE id = "#,
+            );
+            builder.push_bind(category_id).push(
+                r#"
+                        UNION
+                        SELECT c.id FROM product_categories c JOIN subtree s ON c.parent_id = s.id
+                    )
+                    SELECT id FROM subtree
+                )"#,
+            );
+        }
+        if let Some(barcode) = self.barcode.as_deref().filter(|b| !b.trim().is_empty()) {
+            builder.push(" AND barcode = ").push_bind(normalize_barcode(barcode));
+        }
+        if let Some(discontinued) = self.discontinued {
+            builder.push(" AND discontinued = ").push_bind(discontinued);
+        }
+        if let Some(min) = self.min_price {
+            builder.push(" AND price >= ").push_bind(min);
+        }
+        if let Some(max) = self.max_price {
+            builder.push(" AND price <= ").push_bind(max);
+        }
+        if let Some(threshold) = self.low_stock_threshold {
+            builder.push(" AND stock <= ").push_bind(threshold);
+        }
+        if self.below_reorder_point {
+            builder.push(" AND stock <= reorder_point");
+        }
+        if !self.include_deleted {
+            builder.push(" AND deleted_at IS NULL");
+        }
+    }
+}
+
+pub async fn list(pool: &PgPool, query: &ProductQuery) -> Result<Page<Product>, AppError> {
+    query::fetch_page(pool, "products", query).await
+}
+
+pub async fn get(pool: &PgPool, id: Uuid) -> Result<Product, AppError> {
+    sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::not_found("product", id))
+}
+
+// バーコードで商品を1件取得する（読み取った値の空白・ハイフンは無視する）
+pub async fn get_by_barcode(pool: &PgPool, barcode: &str) -> Result<Product, AppError> {
+    sqlx::query_as::<_, Product>(
+        "SELECT * FROM products WHERE barcode = $1 AND deleted_at IS NULL",
+    )
+    .bind(normalize_barcode(barcode))
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound {
+        entity: "product".to_string(),
+        id: None,
+    })
+}
+
+// 更新・削除の前の状態（監査ログ用）を取得し、トランザクションの終わりまで行をロックする
+// 削除済みの商品は NotFound
+pub async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<Product, AppError> {
+    let product = lock_any(conn, id).await?;
+    if product.deleted_at.is_some() {
+        return Err(AppError::not_found("product", id));
+    }
+    Ok(product)
+}
+
+// 削除済みの商品も含めて行をロックする
+async fn lock_any(conn: &mut PgConnection, id: Uuid) -> Result<Product, AppError> {
+    sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::not_found("product", id))
+}
+
+// SKU は前後の空白を取り除いて大文字に揃える
+pub fn normalize_sku(value: &str) -> String {
+    value.trim().to_uppercase()
+}
+
+// SKU に使える文字（英数字と - _ . /）
+pub fn is_sku(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+// バーコードリーダーや手入力で混ざる空白とハイフンを取り除く
+fn normalize_barcode(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect()
+}
+
+// JAN/EAN-8・EAN-13・UPC-A の桁数とチェックディジット（モジュラス10 ウェイト3）を確認する
+fn is_gtin(value: &str) -> bool {
+    if !matches!(value.len(), 8 | 12 | 13) || !value.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (check, body) = digits.split_last().expect("barcode has at least 8 digits");
+    // チェックディジットの左隣から順に 3, 1, 3, 1... を掛ける
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+        .sum();
+    (10 - sum % 10) % 10 == *check
+}
+
+// 入力値の検証（None のフィールドは検証しない。SKU とバーコードは正規化済みの値）
+fn validate(
+    name: Option<&str>,
+    sku: Option<&str>,
+    barcode: Option<&str>,
+    price: Option<&Money>,
+    stock: Option<i32>,
+    reorder_point: Option<i32>,
+) -> Result<(), AppError> {
+    let mut fields = Vec::new();
+    if name.is_some_and(|v| v.trim().is_empty()) {
+        fields.push(FieldError::new("name", "must not be empty"));
+    }
+    if sku.is_some_and(|v| !is_sku(v)) {
+        fields.push(FieldError::new(
+            "sku",
+            "must be 1 to 64 letters, digits or - _ . /",
+        ));
+    }
+    if barcode.is_some_and(|v| !is_gtin(v)) {
+        fields.push(FieldError::new(
+            "barcode",
+            "must be a JAN/EAN (8 or 13 digits) or UPC (12 digits) code with a valid check digit",
+        ));
+    }
+    if price.is_some_and(|p| p.amount < Decimal::ZERO) {
+        fields.push(FieldError::new("price", "must not be negative"));
+    }
+    if stock.is_some_and(|s| s < 0) {
+        fields.push(FieldError::new("stock", "must not be negative"));
+    }
+    if reorder_point.is_some_and(|r| r < 0) {
+        fields.push(FieldError::new("reorder_point", "must not be negative"));
+    }
+    AppError::check_fields(fields)
+}
+
+// SKU とバーコードが削除されていない他の商品やバリエーションで使われていれば Conflict
+// 同時に登録された場合は一意インデックス（products_sku_key・products_barcode_key）で防ぐ
+async fn check_unique(
+    conn: &mut PgConnection,
+    id: Option<Uuid>,
+    sku: Option<&str>,
+    barcode: Option<&str>,
+) -> Result<(), AppError> {
+    let used: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT name, sku, barcode FROM products
+        WHERE deleted_at IS NULL
+          AND id IS DISTINCT FROM $1
+          AND (sku = $2 OR barcode = $3)
+        UNION ALL
+        SELECT p.name || ' (' || array_to_string(v.option_values, ' / ') || ')', v.sku, NULL
+        FROM product_variants v
+        JOIN products p ON p.id = v.product_id
+        WHERE p.deleted_at IS NULL AND v.sku = $2
+        "#,
+    )
+    .bind(id)
+    .bind(sku)
+    .bind(barcode)
+    .fetch_all(conn)
+    .await?;
+
+    let mut fields = Vec::new();
+    for (name, used_sku, used_barcode) in &used {
+        if sku == Some(used_sku.as_str()) {
+            fields.push(FieldError::new("sku", format!("is already used by {}", name)));
+        }
+        if barcode.is_some() && barcode == used_barcode.as_deref() {
+            fields.push(FieldError::new("barcode", format!("is already used by {}", name)));
+        }
+    }
+    if !fields.is_empty() {
+        return Err(AppError::Conflict {
+            message: "a product with the same SKU or barcode already exists".to_string(),
+            constraint: None,
+            fields,
+        });
+    }
+    Ok(())
+}
+
+pub async fn create(pool: &PgPool, actor: &User, data: CreateProduct) -> Result<Product, AppError> {
+    let sku = normalize_sku(&data.sku);
+    let barcode = data
+        .barcode
+        .as_deref()
+        .map(normalize_barcode)
+        .filter(|b| !b.is_empty());
+    validate(
+        Some(&data.name),
+        Some(&sku),
+        barcode.as_deref(),
+        Some(&data.price),
+        Some(data.stock),
+        data.reorder_point,
+    )?;
+
+    let mut tx = pool.begin().await?;
+    check_unique(&mut tx, None, Some(&sku), barcode.as_deref()).await?;
+    if let Some(category_id) = data.category_id {
+        categories::exists(&mut tx, category_id, "category_id").await?;
+    }
+    let mut product = sqlx::query_as::<_, Product>(
+        r#"
+        INSERT INTO products (
+            name, sku, barcode, description, unit, category_id, price, currency, reorder_point
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(data.name.trim())
+    .bind(&sku)
+    .bind(&barcode)
+    .bind(data.description)
+    .bind(data.unit.as_str())
+    .bind(data.category_id)
+    .bind(data.price.amount)
+    .bind(data.price.currency.as_str())
+    .bind(data.reorder_point)
+    .fetch_one(&mut *tx)
+    .await?;
+    // 最初の在庫は既定の保管場所への入庫として記録する（在庫数はトリガーで反映される）
+    if data.stock > 0 {
+        let receipt = AdjustStock {
+            product_id: product.id,
+            movement_type: StockMovementType::Receipt,
+            quantity: data.stock,
+            reason: Some("Initial stock".to_string()),
+            ..Default::default()
+        };
+        stock::record(&mut tx, actor, &receipt).await?;
+        product = lock(&mut tx, product.id).await?;
+    }
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Product,
+        product.id,
+        AuditAction::Create,
+        None,
+        Some(&product),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(product)
+}
+
+// 指定されたフィールドだけを更新する（在庫数は adjust_stock で入出庫として記録する）
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateProduct,
+) -> Result<Product, AppError> {
+    let sku = data.sku.as_deref().map(normalize_sku);
+    // Some(None) と空の値はバーコードの削除
+    let barcode = data.barcode.as_ref().map(|b| {
+        b.as_deref()
+            .map(normalize_barcode)
+            .filter(|b| !b.is_empty())
+    });
+    validate(
+        data.name.as_deref(),
+        sku.as_deref(),
+        barcode.as_ref().and_then(|b| b.as_deref()),
+        data.price.as_ref(),
+        None,
+        data.reorder_point.flatten(),
+    )?;
+
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    check_unique(
+        &mut tx,
+        Some(id),
+        sku.as_deref(),
+        barcode.as_ref().and_then(|b| b.as_deref()),
+    )
+    .await?;
+    if let Some(Some(category_id)) = data.category_id {
+        categories::exists(&mut tx, category_id, "category_id").await?;
+    }
+    let product = sqlx::query_as::<_, Product>(
+        r#"
+        UPDATE products
+        SET name = COALESCE($2, name),
+            sku = COALESCE($3, sku),
+            barcode = CASE WHEN $4 THEN $5 ELSE barcode END,
+            description = CASE WHEN $6 THEN $7 ELSE description END,
+            unit = COALESCE($8, unit),
+            category_id = CASE WHEN $9 THEN $10 ELSE category_id END,
+            price = COALESCE($11, price),
+            currency = COALESCE($12, currency),
+            reorder_point = CASE WHEN $13 THEN $14 ELSE reorder_point END,
+            discontinued = COALESCE($15, discontinued),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(data.name.as_deref().map(str::trim))
+    .bind(sku)
+    .bind(barcode.is_some())
+    .bind(barcode.flatten())
+    .bind(data.description.is_some())
+    .bind(data.description.flatten())
+    .bind(data.unit.map(|unit| unit.as_str()))
+    .bind(data.category_id.is_some())
+    .bind(data.category_id.flatten())
+    .bind(data.price.map(|p| p.amount))
+    .bind(data.price.map(|p| p.currency.as_str().to_string()))
+    .bind(data.reorder_point.is_some())
+    .bind(data.reorder_point.flatten())
+    .bind(data.discontinued)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Product,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&product),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(product)
+}
+
+// 論理削除する（注文明細からは引き続き参照できるが、新しい注文には使えなくなる）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    let product = sqlx::query_as::<_, Product>(
+        "UPDATE products SET deleted_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Product,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        Some(&product),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// 論理削除を取り消す（削除されていない商品は Conflict）
+pub async fn restore(pool: &PgPool, actor: &User, id: Uuid) -> Result<Product, AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock_any(&mut tx, id).await?;
+    if before.deleted_at.is_none() {
+        return Err(AppError::Conflict {
+            message: "the product is not deleted".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    let product = sqlx::query_as::<_, Product>(
+        "UPDATE products SET deleted_at = NULL WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Product,
+        id,
+        AuditAction::Restore,
+        Some(&before),
+        Some(&product),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(product)
+}
+
+// 削除から PURGE_AFTER_DAYS 日を過ぎた商品を完全削除する
+// 注文明細から参照されている商品は残す
+pub async fn purge(conn: &mut PgConnection, actor: &User) -> Result<u64, AppError> {
+    let products = sqlx::query_as::<_, Product>(
+        r#"
+        DELETE FROM products p
+        WHERE p.deleted_at < NOW() - make_interval(days => $1)
+          AND NOT EXISTS (SELECT 1 FROM order_items i WHERE i.product_id = p.id)
+        RETURNING *
+        "#,
+    )
+    .bind(PURGE_AFTER_DAYS)
+    .fetch_all(&mut *conn)
+    .await?;
+    for product in &products {
+        audit::record_change(
+            &mut *conn,
+            actor,
+            AuditEntity::Product,
+            product.id,
+            AuditAction::Purge,
+            Some(product),
+            None,
+        )
+        .await?;
+    }
+    Ok(products.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_gtin, is_sku, normalize_barcode, normalize_sku};
+
+    #[test]
+    fn normalizes_skus() {
+        assert_eq!(normalize_sku("  lap-pro-001 "), "LAP-PRO-001");
+        assert_eq!(normalize_sku("Acc_mou.1/b"), "ACC_MOU.1/B");
+        assert!(is_sku(&normalize_sku("lap-pro-001")));
+        assert!(is_sku(&"A".repeat(64)));
+    }
+
+    #[test]
+    fn rejects_invalid_skus() {
+        for value in ["", "LAP PRO", "LAP#1", "ラップ", "LAP+PRO"] {
+            assert!(!is_sku(&normalize_sku(value)), "{:?}", value);
+        }
+        assert!(!is_sku(&"A".repeat(65)));
+    }
+
+    #[test]
+    fn normalizes_barcodes() {
+        assert_eq!(normalize_barcode(" 4900000000016 "), "4900000000016");
+        assert_eq!(normalize_barcode("49-0000 0000016"), "4900000000016");
+        assert_eq!(normalize_barcode("0 36000 29145 2"), "036000291452");
+    }
+
+    #[test]
+    fn accepts_valid_gtins() {
+        for value in [
+            // EAN-8
+            "96385074",
+            "49123456",
+            // UPC-A
+            "036000291452",
+            "012345678905",
+            // JAN/EAN-13
+            "4900000000016",
+            "4006381333931",
+            "0000000000000",
+        ] {
+            assert!(is_gtin(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_gtins() {
+        for value in [
+            "",
+            // チェックディジットの誤り
+            "96385075",
+            "036000291453",
+            "4900000000017",
+            "4006381333930",
+            // 桁数の誤り（GTIN-14 は扱わない）
+            "1234567",
+            "123456789",
+            "10012345678902",
+            // 数字以外
+            "49000000000A6",
+            "4900-00000016",
+            "４９００００００００016",
+        ] {
+            assert!(!is_gtin(value), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use sqlx::PgPool;
+use ws_models::{AppError, PurgeReport, User};
+
+use crate::{clients, orders, products, users};
+
+// 保存期間を過ぎた削除済みのレコードを1つのトランザクションで完全削除する
+// 注文を先に削除し、その注文からだけ参照されていたユーザー・取引先・商品も同じ実行で削除できるようにする
+pub async fn purge_deleted(pool: &PgPool, actor: &User) -> Result<PurgeReport, AppError> {
+    let mut tx = pool.begin().await?;
+    let orders = orders::purge(&mut tx, actor).await?;
+    let clients = clients::purge(&mut tx, actor).await?;
+    let products = products::purge(&mut tx, actor).await?;
+    let users = users::purge(&mut tx, actor).await?;
+    tx.commit().await?;
+    Ok(PurgeReport {
+        users,
+        clients,
+        products,
+        orders,
+    })
+}
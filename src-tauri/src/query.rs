@@ -0,0 +1,74 @@
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use ws_models::{AppError, ListQuery, Page, SortDirection};
+
+// SQL の並び順
+fn direction_sql(direction: SortDirection) -> &'static str {
+    match direction {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    }
+}
+
+// 並び替えに使える列（任意の文字列を ORDER BY に渡さないよう列挙型で限定する）
+pub trait SortField: Copy + Default {
+    fn column(&self) -> &'static str;
+}
+
+// WHERE 句に条件を追加する（呼び出し側で "WHERE TRUE" まで組み立て済み）
+pub trait Filter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>);
+}
+
+// 部分一致検索用の ILIKE パターン（% と _ はそのまま文字として扱う）
+pub fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+// table の行をフィルター・並び替えして1ページ分取得する
+pub async fn fetch_page<T, S, F>(
+    pool: &PgPool,
+    table: &str,
+    query: &ListQuery<S, F>,
+) -> Result<Page<T>, AppError>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    S: SortField,
+    F: Filter,
+{
+    query.validate()?;
+
+    let mut count = QueryBuilder::new(format!("SELECT COUNT(*) FROM {} WHERE TRUE", table));
+    query.filter.push_conditions(&mut count);
+    let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
+
+    let direction = direction_sql(query.direction.unwrap_or_default());
+    let page_size = query.page_size();
+    let mut select = QueryBuilder::new(format!("SELECT * FROM {} WHERE TRUE", table));
+    query.filter.push_conditions(&mut select);
+    // 同じ値の行でもページ間で順序が揺れないよう id を第2キーにする
+    select.push(format!(
+        " ORDER BY {} {}, id {}",
+        query.sort.unwrap_or_default().column(),
+        direction,
+        direction
+    ));
+    select.push(" LIMIT ").push_bind(i64::from(page_size));
+    select
+        .push(" OFFSET ")
+        .push_bind(i64::from(query.page() - 1) * i64::from(page_size));
+    let items = select.build_query_as::<T>().fetch_all(pool).await?;
+
+    Ok(Page {
+        items,
+        page: query.page(),
+        page_size,
+        total,
+        total_pages: (total as u64).div_ceil(u64::from(page_size)) as u32,
+    })
+}
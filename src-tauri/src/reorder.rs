@@ -0,0 +1,98 @@
+use sqlx::PgPool;
+
+use ws_models::{
+    AppError, FieldError, LowStockAlert, ReorderQuery, ReorderSuggestion, MAX_REORDER_DAYS,
+};
+
+// 新しく在庫が発注点以下になった商品（削除済み・取扱終了の商品は除く。在庫はすべての保管場所の合計）
+// 返した商品は low_stock_alerts に記録し、発注点を上回った商品の記録は削除する
+// 複数のアプリから同時に確認しても、同じ商品を返すのは1つのアプリだけ
+pub async fn new_low_stock(pool: &PgPool) -> Result<Vec<LowStockAlert>, sqlx::Error> {
+    sqlx::query_as::<_, LowStockAlert>(
+        r#"
+        WITH low AS (
+            SELECT id, name, sku, stock, reorder_point
+            FROM products
+            WHERE deleted_at IS NULL AND NOT discontinued AND stock <= reorder_point
+        ),
+        recovered AS (
+            DELETE FROM low_stock_alerts
+            WHERE product_id NOT IN (SELECT id FROM low)
+        ),
+        alerted AS (
+            INSERT INTO low_stock_alerts (product_id)
+            SELECT id FROM low
+            ON CONFLICT DO NOTHING
+            RETURNING product_id
+        )
+        SELECT l.id AS product_id, l.name, l.sku, l.stock, l.reorder_point
+        FROM low l
+        JOIN alerted a ON a.product_id = l.id
+        ORDER BY l.name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// 直近 sales_days 日間の販売（返品を差し引いた数）から、cover_days 日分の販売と発注点をまかなう発注数を求める
+// 発注点以下の商品は、販売がなくても発注点を上回る数を提案する
+// 今の在庫でまかなえる商品は含まない（在庫がなくなるまでの日数が短い順）
+pub async fn suggestions(
+    pool: &PgPool,
+    query: ReorderQuery,
+) -> Result<Vec<ReorderSuggestion>, AppError> {
+    let mut fields = Vec::new();
+    for (field, days) in [
+        ("sales_days", query.sales_days),
+        ("cover_days", query.cover_days),
+    ] {
+        if !(1..=MAX_REORDER_DAYS).contains(&days) {
+            fields.push(FieldError::new(
+                field,
+                format!("must be between 1 and {} days", MAX_REORDER_DAYS),
+            ));
+        }
+    }
+    AppError::check_fields(fields)?;
+
+    let suggestions = sqlx::query_as::<_, ReorderSuggestion>(
+        r#"
+        WITH sales AS (
+            SELECT product_id, -SUM(quantity) AS sold
+            FROM stock_movements
+            WHERE movement_type IN ('sale', 'return')
+              AND created_at >= NOW() - make_interval(days => $1)
+            GROUP BY product_id
+        ),
+        velocity AS (
+            SELECT p.id AS product_id, p.name, p.sku, p.stock, p.reorder_point,
+                   GREATEST(COALESCE(s.sold, 0), 0)::bigint AS sold,
+                   GREATEST(COALESCE(s.sold, 0), 0)::numeric / $1 AS daily
+            FROM products p
+            LEFT JOIN sales s ON s.product_id = p.id
+            WHERE p.deleted_at IS NULL AND NOT p.discontinued
+        ),
+        suggested AS (
+            SELECT *,
+                   GREATEST(
+                       CEIL(daily * $2) + COALESCE(reorder_point, 0) - stock,
+                       COALESCE(reorder_point + 1 - stock, 0)
+                   )::integer AS suggested_quantity
+            FROM velocity
+        )
+        SELECT product_id, name, sku, stock, reorder_point, sold,
+               ROUND(daily, 2) AS daily_sales,
+               CASE WHEN daily > 0 THEN FLOOR(stock / daily)::integer END AS days_of_stock,
+               suggested_quantity
+        FROM suggested
+        WHERE suggested_quantity > 0
+        ORDER BY days_of_stock NULLS LAST, name
+        "#,
+    )
+    .bind(query.sales_days)
+    .bind(query.cover_days)
+    .fetch_all(pool)
+    .await?;
+    Ok(suggestions)
+}
@@ -0,0 +1,83 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use ws_models::{AppError, Permission, Role, User};
+
+// ロールに与えられている権限（role_permissions）
+pub async fn permissions(pool: &PgPool, role: Role) -> Result<Vec<Permission>, AppError> {
+    let values: Vec<String> =
+        sqlx::query_scalar("SELECT permission FROM role_permissions WHERE role = $1 ORDER BY permission")
+            .bind(role.as_str())
+            .fetch_all(pool)
+            .await?;
+    values
+        .iter()
+        .map(|value| value.parse().map_err(AppError::internal))
+        .collect()
+}
+
+// admin や、操作するユーザーのロールにない権限を持つユーザーの更新・削除・復元には manage_roles が必要
+// （manage_users だけのユーザーが上位のユーザーのパスワードやメールアドレスを変えて乗っ取れないように）
+// 対象のユーザーの行をロックしてから呼ぶ
+pub async fn ensure_can_manage(
+    conn: &mut PgConnection,
+    actor: &User,
+    target: &User,
+) -> Result<(), AppError> {
+    let (above, can_manage_roles): (bool, bool) = sqlx::query_as(
+        r#"
+        SELECT $2 = 'admin' OR EXISTS (
+                   SELECT permission FROM role_permissions WHERE role = $2
+                   EXCEPT
+                   SELECT permission FROM role_permissions WHERE role = $1
+               ),
+               EXISTS (
+                   SELECT 1 FROM role_permissions WHERE role = $1 AND permission = $3
+               )
+        "#,
+    )
+    .bind(actor.role.as_str())
+    .bind(target.role.as_str())
+    .bind(Permission::ManageRoles.as_str())
+    .fetch_one(conn)
+    .await?;
+    if above && !can_manage_roles {
+        return Err(AppError::Forbidden {
+            message: format!(
+                "your role ({}) cannot change a user with the {} role",
+                actor.role.label(),
+                target.role.label()
+            ),
+        });
+    }
+    Ok(())
+}
+
+// 最後の admin のロールを外したり削除したりすると誰もロールを変更できなくなるため、
+// ログインできる（パスワードを持ち、削除されていない）admin が他にいなければ Conflict にする
+// 同時に別々の admin を変更しても admin がいなくならないよう、削除されていない admin の行をすべてロックする
+// 対象のユーザーの行をロックしたトランザクションの中で呼ぶ
+pub async fn ensure_admin_remains(conn: &mut PgConnection, user_id: Uuid) -> Result<(), AppError> {
+    let admins: Vec<(Uuid, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, password_hash IS NOT NULL
+        FROM users
+        WHERE role = 'admin' AND deleted_at IS NULL
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .fetch_all(conn)
+    .await?;
+    let is_admin = admins.iter().any(|(id, _)| *id == user_id);
+    let remains = admins
+        .iter()
+        .any(|(id, can_log_in)| *id != user_id && *can_log_in);
+    if is_admin && !remains {
+        return Err(AppError::Conflict {
+            message: "at least one admin who can log in is required".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    Ok(())
+}
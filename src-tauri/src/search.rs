@@ -0,0 +1,126 @@
+use sqlx::PgPool;
+use ws_models::{AppError, SearchHit, SearchKind};
+
+use crate::query::contains_pattern;
+
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+pub const MAX_SEARCH_LIMIT: u32 = 100;
+
+// 入力を単語ごとの前方一致 tsquery（例: "yamada tar" → "yamada:* & tar:*"）にする
+// to_tsquery の演算子として解釈される記号は取り除く
+fn prefix_tsquery(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, '@' | '.' | '-' | '_'))
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word))
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+// 注文番号（UUID の先頭部分）として扱える入力なら LIKE の前方一致パターンを返す
+fn order_id_prefix(text: &str) -> Option<String> {
+    let text = text.trim().trim_start_matches('#').to_lowercase();
+    let is_id = !text.is_empty() && text.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    is_id.then(|| format!("{}%", text))
+}
+
+// ユーザー・取引先・商品・注文（削除済みのものを除く）を横断して検索する
+// 全文検索（search_vector）とトライグラムの類似度のうち高い方を rank とし、
+// 注文番号の前方一致は8文字すべて一致で最上位になる
+// kinds にない種類は LIMIT の前に除く（閲覧できない種類の結果で件数が埋まらないように）
+pub async fn search(
+    pool: &PgPool,
+    text: &str,
+    limit: Option<u32>,
+    kinds: &[SearchKind],
+) -> Result<Vec<SearchHit>, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if limit == 0 || limit > MAX_SEARCH_LIMIT {
+        return Err(AppError::field(
+            "limit",
+            format!("must be between 1 and {}", MAX_SEARCH_LIMIT),
+        ));
+    }
+    let text = text.trim();
+    if text.is_empty() || kinds.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tsquery = prefix_tsquery(text);
+    let hits = sqlx::query_as::<_, SearchHit>(
+        r#"
+        WITH q AS (
+            SELECT CASE WHEN $2 = '' THEN NULL ELSE to_tsquery('simple', $2) END AS tsq
+        )
+        SELECT kind, id, title, subtitle, rank FROM (
+            SELECT 'user' AS kind, u.id, u.name AS title, u.email AS subtitle,
+                   GREATEST(
+                       ts_rank(u.search_vector, q.tsq),
+                       word_similarity($1, u.name),
+                       word_similarity($1, u.email)
+                   )::real AS rank
+            FROM users u, q
+            WHERE 'user' = ANY($6) AND u.deleted_at IS NULL
+              AND (u.search_vector @@ q.tsq
+                   OR u.name ILIKE $3 OR u.email ILIKE $3
+                   OR $1 <% u.name OR $1 <% u.email)
+
+            UNION ALL
+
+            SELECT 'client', c.id, c.company_name, COALESCE(c.contact_name, c.email),
+                   GREATEST(
+                       ts_rank(c.search_vector, q.tsq),
+                       word_similarity($1, c.company_name),
+                       word_similarity($1, COALESCE(c.contact_name, ''))
+                   )::real
+            FROM clients c, q
+            WHERE 'client' = ANY($6) AND c.deleted_at IS NULL
+              AND (c.search_vector @@ q.tsq
+                   OR c.company_name ILIKE $3 OR c.contact_name ILIKE $3
+                   OR $1 <% c.company_name OR $1 <% c.contact_name)
+
+            UNION ALL
+
+            -- SKU やバーコードが一致した商品は最上位に表示する
+            SELECT 'product', p.id, p.name, p.sku,
+                   GREATEST(
+                       ts_rank(p.search_vector, q.tsq),
+                       word_similarity($1, p.name),
+                       word_similarity($1, p.sku),
+                       CASE WHEN upper($1) = p.sku OR $1 = p.barcode THEN 1.0 ELSE 0.0 END
+                   )::real
+            FROM products p, q
+            WHERE 'product' = ANY($6) AND p.deleted_at IS NULL
+              AND (p.search_vector @@ q.tsq
+                   OR p.name ILIKE $3 OR p.sku ILIKE $3 OR p.barcode = $1
+                   OR $1 <% p.name
+                   OR p.id IN (SELECT product_id FROM product_variants WHERE sku ILIKE $3))
+
+            UNION ALL
+
+            -- 入力が長いほど注文番号として確からしいので rank を上げる
+            SELECT 'order', o.id, '#' || left(o.id::text, 8), c.company_name,
+                   LEAST((length($4) - 1) / 8.0, 1.0)::real
+            FROM orders o
+            LEFT JOIN clients c ON c.id = o.client_id
+            WHERE 'order' = ANY($6) AND o.deleted_at IS NULL AND o.id::text LIKE $4
+        ) hits
+        ORDER BY rank DESC, title
+        LIMIT $5
+        "#,
+    )
+    .bind(text)
+    .bind(tsquery)
+    .bind(contains_pattern(text))
+    .bind(order_id_prefix(text))
+    .bind(i64::from(limit))
+    .bind(kinds.iter().map(SearchKind::as_str).collect::<Vec<_>>())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hits)
+}
@@ -0,0 +1,29 @@
+use keyring::Entry;
+
+// 接続プロファイルのパスワードを OS の資格情報ストア
+// （Windows 資格情報マネージャー・macOS キーチェーン・Secret Service）に保存する
+// 設定ファイル（connections.json）にはパスワードを書かない
+const SERVICE: &str = "com.user69.ws";
+
+fn entry(profile: &str) -> Result<Entry, keyring::Error> {
+    Entry::new(SERVICE, &format!("connection:{}", profile))
+}
+
+pub fn get_password(profile: &str) -> Result<Option<String>, keyring::Error> {
+    match entry(profile)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn set_password(profile: &str, password: &str) -> Result<(), keyring::Error> {
+    entry(profile)?.set_password(password)
+}
+
+pub fn delete_password(profile: &str) -> Result<(), keyring::Error> {
+    match entry(profile)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,561 @@
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use ws_models::{
+    AdjustStock, AdjustStockBatch, AppError, CreateStockTransfer, FieldError, Page, Product,
+    StockDiscrepancy, StockLevel, StockMovement, StockMovementFilter, StockMovementQuery,
+    StockMovementSort, StockMovementType, StockTransfer, User,
+};
+
+use crate::query::{self, Filter, SortField};
+use crate::{products, variants, warehouses};
+
+// 参照する書類（納品書番号など）の最大文字数
+const MAX_REFERENCE_LENGTH: usize = 100;
+
+impl SortField for StockMovementSort {
+    fn column(&self) -> &'static str {
+        match self {
+            StockMovementSort::CreatedAt => "created_at",
+        }
+    }
+}
+
+impl Filter for StockMovementFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(product_id) = self.product_id {
+            builder.push(" AND product_id = ").push_bind(product_id);
+        }
+        if let Some(variant_id) = self.variant_id {
+            builder.push(" AND variant_id = ").push_bind(variant_id);
+        }
+        if let Some(warehouse_id) = self.warehouse_id {
+            builder.push(" AND warehouse_id = ").push_bind(warehouse_id);
+        }
+        if let Some(movement_type) = self.movement_type {
+            builder
+                .push(" AND movement_type = ")
+                .push_bind(movement_type.as_str());
+        }
+        if let Some(order_id) = self.order_id {
+            builder.push(" AND order_id = ").push_bind(order_id);
+        }
+        if let Some(transfer_id) = self.transfer_id {
+            builder.push(" AND transfer_id = ").push_bind(transfer_id);
+        }
+        if let Some(from) = self.created_from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+    }
+}
+
+pub async fn list(
+    pool: &PgPool,
+    query: &StockMovementQuery,
+) -> Result<Page<StockMovement>, AppError> {
+    query::fetch_page(pool, "stock_movements", query).await
+}
+
+// 入出庫を記録する（在庫数への反映は stock_movements のトリガーで行う）
+// 保管場所の指定がなければ既定の保管場所の記録にする
+// 呼び出し側で商品行をロックし、保管場所の在庫が足りることを確認しておくこと
+pub async fn record(
+    conn: &mut PgConnection,
+    actor: &User,
+    data: &AdjustStock,
+) -> Result<StockMovement, sqlx::Error> {
+    sqlx::query_as::<_, StockMovement>(
+        r#"
+        INSERT INTO stock_movements (
+            product_id, variant_id, warehouse_id, movement_type, quantity, reason, reference,
+            actor_id, actor_name
+        )
+        VALUES (
+            $1, $2, COALESCE($3, (SELECT id FROM warehouses WHERE is_default)),
+            $4, $5, $6, $7, $8, $9
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(data.product_id)
+    .bind(data.variant_id)
+    .bind(data.warehouse_id)
+    .bind(data.movement_type.as_str())
+    .bind(data.quantity)
+    .bind(data.reason.as_deref())
+    .bind(data.reference.as_deref())
+    .bind(actor.id)
+    .bind(&actor.name)
+    .fetch_one(conn)
+    .await
+}
+
+// 注文の明細ごとに、注文の保管場所の入出庫を記録する（販売は出庫、それ以外は入庫）
+// 呼び出し側で明細の商品行をロックしておくこと
+pub async fn record_order(
+    conn: &mut PgConnection,
+    actor: &User,
+    order_id: Uuid,
+    movement_type: StockMovementType,
+    reason: &str,
+) -> Result<(), sqlx::Error> {
+    let sign = if movement_type == StockMovementType::Sale {
+        -1
+    } else {
+        1
+    };
+    sqlx::query(
+        r#"
+        INSERT INTO stock_movements (
+            product_id, variant_id, warehouse_id, movement_type, quantity, reason, order_id,
+            actor_id, actor_name
+        )
+        SELECT i.product_id, i.variant_id, o.warehouse_id, $2, i.quantity * $3, $4, i.order_id,
+               $5, $6
+        FROM order_items i
+        JOIN orders o ON o.id = i.order_id
+        WHERE i.order_id = $1
+        ORDER BY i.product_id, i.id
+        "#,
+    )
+    .bind(order_id)
+    .bind(movement_type.as_str())
+    .bind(sign)
+    .bind(reason)
+    .bind(actor.id)
+    .bind(&actor.name)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+// 入庫・返品・調整を記録する
+// 販売と移動は注文・移動伝票から記録されるため、ここでは受け付けない
+pub async fn adjust(
+    pool: &PgPool,
+    actor: &User,
+    data: AdjustStock,
+) -> Result<StockMovement, AppError> {
+    let data = AdjustStock {
+        reason: non_empty(data.reason.as_deref()),
+        reference: non_empty(data.reference.as_deref()),
+        ..data
+    };
+    let mut fields = Vec::new();
+    if !data.movement_type.is_manual() {
+        fields.push(FieldError::new(
+            "movement_type",
+            format!(
+                "{} is recorded by orders and transfers",
+                data.movement_type.label()
+            ),
+        ));
+    }
+    if data.quantity == 0 {
+        fields.push(FieldError::new("quantity", "must not be 0"));
+    } else if data.quantity < 0 && data.movement_type != StockMovementType::Adjustment {
+        fields.push(FieldError::new(
+            "quantity",
+            format!(
+                "must be positive for a {}",
+                data.movement_type.label().to_lowercase()
+            ),
+        ));
+    }
+    if data.movement_type == StockMovementType::Adjustment && data.reason.is_none() {
+        fields.push(FieldError::new("reason", "is required for adjustments"));
+    }
+    if data
+        .reference
+        .as_deref()
+        .is_some_and(|r| r.chars().count() > MAX_REFERENCE_LENGTH)
+    {
+        fields.push(FieldError::new(
+            "reference",
+            format!("must be at most {} characters", MAX_REFERENCE_LENGTH),
+        ));
+    }
+    AppError::check_fields(fields)?;
+
+    let mut tx = pool.begin().await?;
+    // 商品行をロックして、注文による在庫の引き当てと同時に変更しないようにする
+    let product = products::lock(&mut tx, data.product_id).await?;
+    let label = item_label(&mut tx, &product, data.variant_id, "variant_id").await?;
+    let warehouse = warehouses::resolve(&mut tx, data.warehouse_id, "warehouse_id").await?;
+    let stock = level(&mut tx, warehouse.id, product.id, data.variant_id).await?;
+    if i64::from(stock) + i64::from(data.quantity) < 0 {
+        return Err(AppError::Conflict {
+            message: format!("only {} of {} in stock at {}", stock, label, warehouse.name),
+            constraint: None,
+            fields: vec![FieldError::new(
+                "quantity",
+                format!("only {} in stock at {}", stock, warehouse.name),
+            )],
+        });
+    }
+
+    let data = AdjustStock {
+        warehouse_id: Some(warehouse.id),
+        ..data
+    };
+    let movement = record(&mut tx, actor, &data).await?;
+    tx.commit().await?;
+    Ok(movement)
+}
+
+// 在庫の調整をまとめて記録する（バリエーションの表で変更した在庫数の差分）
+// 明細ごとに調整の記録を追加し、1つでも保管場所の在庫が足りなければ何も記録しない
+pub async fn adjust_batch(
+    pool: &PgPool,
+    actor: &User,
+    data: AdjustStockBatch,
+) -> Result<Vec<StockMovement>, AppError> {
+    let reason = non_empty(data.reason.as_deref());
+    let reference = non_empty(data.reference.as_deref());
+    let mut fields = Vec::new();
+    if data.items.is_empty() {
+        fields.push(FieldError::new("items", "must contain at least one item"));
+    }
+    for (i, item) in data.items.iter().enumerate() {
+        if item.quantity == 0 {
+            fields.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                "must not be 0",
+            ));
+        }
+    }
+    if reason.is_none() {
+        fields.push(FieldError::new("reason", "is required for adjustments"));
+    }
+    if reference
+        .as_deref()
+        .is_some_and(|r| r.chars().count() > MAX_REFERENCE_LENGTH)
+    {
+        fields.push(FieldError::new(
+            "reference",
+            format!("must be at most {} characters", MAX_REFERENCE_LENGTH),
+        ));
+    }
+    AppError::check_fields(fields)?;
+
+    let mut tx = pool.begin().await?;
+    let warehouse = warehouses::resolve(&mut tx, data.warehouse_id, "warehouse_id").await?;
+
+    // 注文と同じく商品行を ID 順にロックする
+    let mut product_ids: Vec<Uuid> = data.items.iter().map(|i| i.product_id).collect();
+    product_ids.sort();
+    product_ids.dedup();
+    let mut locked = HashMap::new();
+    for product_id in product_ids {
+        locked.insert(product_id, products::lock(&mut tx, product_id).await?);
+    }
+
+    // 同じ商品（バリエーション）が複数の明細に含まれる場合は数量を合算して在庫と比較する
+    let mut requested: HashMap<(Uuid, Option<Uuid>), i64> = HashMap::new();
+    for item in &data.items {
+        *requested
+            .entry((item.product_id, item.variant_id))
+            .or_default() += i64::from(item.quantity);
+    }
+    let mut shortages = Vec::new();
+    for (i, item) in data.items.iter().enumerate() {
+        let product = &locked[&item.product_id];
+        let field = format!("items.{}.variant_id", i);
+        let label = item_label(&mut tx, product, item.variant_id, &field).await?;
+        let stock = level(&mut tx, warehouse.id, item.product_id, item.variant_id).await?;
+        if i64::from(stock) + requested[&(item.product_id, item.variant_id)] < 0 {
+            shortages.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                format!("only {} of {} in stock at {}", stock, label, warehouse.name),
+            ));
+        }
+    }
+    if !shortages.is_empty() {
+        return Err(AppError::Conflict {
+            message: format!("insufficient stock at {}", warehouse.name),
+            constraint: None,
+            fields: shortages,
+        });
+    }
+
+    let mut movements = Vec::with_capacity(data.items.len());
+    for item in &data.items {
+        let adjustment = AdjustStock {
+            product_id: item.product_id,
+            variant_id: item.variant_id,
+            warehouse_id: Some(warehouse.id),
+            movement_type: StockMovementType::Adjustment,
+            quantity: item.quantity,
+            reason: reason.clone(),
+            reference: reference.clone(),
+        };
+        movements.push(record(&mut tx, actor, &adjustment).await?);
+    }
+    tx.commit().await?;
+    Ok(movements)
+}
+
+// 入出庫する商品・バリエーションの表示名（例: "T-shirt (S / Red)"）
+// バリエーションのある商品ではバリエーションの指定が必要（指定の誤りは field のフィールドエラー）
+async fn item_label(
+    conn: &mut PgConnection,
+    product: &Product,
+    variant_id: Option<Uuid>,
+    field: &str,
+) -> Result<String, AppError> {
+    let has_variants = variants::exists_for(&mut *conn, product.id).await?;
+    match (variant_id, has_variants) {
+        (None, true) => Err(AppError::field(field, "choose a variant")),
+        (Some(_), false) => Err(AppError::field(field, "the product has no variants")),
+        (None, false) => Ok(product.name.clone()),
+        (Some(variant_id), true) => {
+            let values: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT array_to_string(option_values, ' / ')
+                FROM product_variants
+                WHERE id = $1 AND product_id = $2
+                "#,
+            )
+            .bind(variant_id)
+            .bind(product.id)
+            .fetch_optional(conn)
+            .await?;
+            let Some(values) = values else {
+                return Err(AppError::field(field, "variant not found"));
+            };
+            Ok(format!("{} ({})", product.name, values))
+        }
+    }
+}
+
+// 保管場所にある商品（バリエーション）の在庫数（記録がなければ 0）
+async fn level(
+    conn: &mut PgConnection,
+    warehouse_id: Uuid,
+    product_id: Uuid,
+    variant_id: Option<Uuid>,
+) -> Result<i32, sqlx::Error> {
+    let quantity: Option<i32> = sqlx::query_scalar(
+        r#"
+        SELECT quantity FROM stock_levels
+        WHERE warehouse_id = $1 AND product_id = $2 AND variant_id IS NOT DISTINCT FROM $3
+        "#,
+    )
+    .bind(warehouse_id)
+    .bind(product_id)
+    .bind(variant_id)
+    .fetch_optional(conn)
+    .await?;
+    Ok(quantity.unwrap_or(0))
+}
+
+// 商品の保管場所ごとの在庫数（既定の保管場所から順に、保管場所の名前順）
+pub async fn levels(pool: &PgPool, product_id: Uuid) -> Result<Vec<StockLevel>, sqlx::Error> {
+    sqlx::query_as::<_, StockLevel>(
+        r#"
+        SELECT l.*
+        FROM stock_levels l
+        JOIN warehouses w ON w.id = l.warehouse_id
+        WHERE l.product_id = $1
+        ORDER BY w.is_default DESC, w.name, l.variant_id
+        "#,
+    )
+    .bind(product_id)
+    .fetch_all(pool)
+    .await
+}
+
+// 保管場所の間で在庫を移動する
+// 明細ごとに移動元の出庫と移動先の入庫を同じ伝票の記録として追加し、1つでも在庫が足りなければ何も移動しない
+pub async fn transfer(
+    pool: &PgPool,
+    actor: &User,
+    data: CreateStockTransfer,
+) -> Result<StockTransfer, AppError> {
+    let reference = non_empty(data.reference.as_deref());
+    let note = non_empty(data.note.as_deref());
+    let mut fields = Vec::new();
+    if data.from_warehouse_id == data.to_warehouse_id {
+        fields.push(FieldError::new(
+            "to_warehouse_id",
+            "must differ from the source warehouse",
+        ));
+    }
+    if data.items.is_empty() {
+        fields.push(FieldError::new(
+            "items",
+            "transfer must contain at least one item",
+        ));
+    }
+    for (i, item) in data.items.iter().enumerate() {
+        if item.quantity <= 0 {
+            fields.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                "must be greater than zero",
+            ));
+        }
+    }
+    if reference
+        .as_deref()
+        .is_some_and(|r| r.chars().count() > MAX_REFERENCE_LENGTH)
+    {
+        fields.push(FieldError::new(
+            "reference",
+            format!("must be at most {} characters", MAX_REFERENCE_LENGTH),
+        ));
+    }
+    AppError::check_fields(fields)?;
+
+    let mut tx = pool.begin().await?;
+    let from =
+        warehouses::resolve(&mut tx, Some(data.from_warehouse_id), "from_warehouse_id").await?;
+    let to = warehouses::resolve(&mut tx, Some(data.to_warehouse_id), "to_warehouse_id").await?;
+
+    // 注文と同じく商品行を ID 順にロックする
+    let mut product_ids: Vec<Uuid> = data.items.iter().map(|i| i.product_id).collect();
+    product_ids.sort();
+    product_ids.dedup();
+    let mut locked = HashMap::new();
+    for product_id in product_ids {
+        locked.insert(product_id, products::lock(&mut tx, product_id).await?);
+    }
+
+    // 同じ商品（バリエーション）が複数の明細に含まれる場合は数量を合算して在庫と比較する
+    let mut requested: HashMap<(Uuid, Option<Uuid>), i64> = HashMap::new();
+    for item in &data.items {
+        *requested
+            .entry((item.product_id, item.variant_id))
+            .or_default() += i64::from(item.quantity);
+    }
+    let mut shortages = Vec::new();
+    for (i, item) in data.items.iter().enumerate() {
+        let product = &locked[&item.product_id];
+        let field = format!("items.{}.variant_id", i);
+        let label = item_label(&mut tx, product, item.variant_id, &field).await?;
+        let stock = level(&mut tx, from.id, item.product_id, item.variant_id).await?;
+        if requested[&(item.product_id, item.variant_id)] > i64::from(stock) {
+            shortages.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                format!("only {} of {} in stock at {}", stock, label, from.name),
+            ));
+        }
+    }
+    if !shortages.is_empty() {
+        return Err(AppError::Conflict {
+            message: format!("insufficient stock at {}", from.name),
+            constraint: None,
+            fields: shortages,
+        });
+    }
+
+    let (id, created_at) = sqlx::query_as(
+        r#"
+        INSERT INTO stock_transfers (
+            from_warehouse_id, to_warehouse_id, reference, note, actor_id, actor_name
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, created_at
+        "#,
+    )
+    .bind(from.id)
+    .bind(to.id)
+    .bind(reference.as_deref())
+    .bind(note.as_deref())
+    .bind(actor.id)
+    .bind(&actor.name)
+    .fetch_one(&mut *tx)
+    .await?;
+    for item in &data.items {
+        sqlx::query(
+            r#"
+            INSERT INTO stock_movements (
+                product_id, variant_id, warehouse_id, movement_type, quantity, reason, reference,
+                transfer_id, actor_id, actor_name
+            )
+            VALUES ($1, $2, $3, $5, -$6, $7, $9, $10, $11, $12),
+                   ($1, $2, $4, $5, $6, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(item.product_id)
+        .bind(item.variant_id)
+        .bind(from.id)
+        .bind(to.id)
+        .bind(StockMovementType::Transfer.as_str())
+        .bind(item.quantity)
+        .bind(format!("Transfer to {}", to.name))
+        .bind(format!("Transfer from {}", from.name))
+        .bind(reference.as_deref())
+        .bind(id)
+        .bind(actor.id)
+        .bind(&actor.name)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(StockTransfer {
+        id,
+        from_warehouse_id: from.id,
+        to_warehouse_id: to.id,
+        reference,
+        note,
+        actor_id: Some(actor.id),
+        actor_name: Some(actor.name.clone()),
+        created_at,
+        items: data.items,
+    })
+}
+
+// 在庫数と入出庫の記録の合計が一致しない商品・バリエーション（削除済みの商品も含む）
+// 保管場所ごとの在庫数も、その保管場所の記録の合計と突き合わせる
+// 在庫数はトリガーでしか変わらないため、通常は空になる
+pub async fn discrepancies(pool: &PgPool) -> Result<Vec<StockDiscrepancy>, sqlx::Error> {
+    sqlx::query_as::<_, StockDiscrepancy>(
+        r#"
+        SELECT p.id AS product_id, NULL::uuid AS variant_id, p.name, p.stock,
+               COALESCE(SUM(m.quantity), 0)::bigint AS ledger_stock
+        FROM products p
+        LEFT JOIN stock_movements m ON m.product_id = p.id
+        GROUP BY p.id
+        HAVING p.stock <> COALESCE(SUM(m.quantity), 0)
+        UNION ALL
+        SELECT v.product_id, v.id, p.name || ' (' || array_to_string(v.option_values, ' / ') || ')',
+               v.stock, COALESCE(SUM(m.quantity), 0)::bigint
+        FROM product_variants v
+        JOIN products p ON p.id = v.product_id
+        LEFT JOIN stock_movements m ON m.variant_id = v.id
+        GROUP BY v.id, p.name
+        HAVING v.stock <> COALESCE(SUM(m.quantity), 0)
+        UNION ALL
+        SELECT l.product_id, l.variant_id,
+               p.name || COALESCE(' (' || array_to_string(v.option_values, ' / ') || ')', '')
+                   || ' at ' || w.name,
+               l.quantity, COALESCE(SUM(m.quantity), 0)::bigint
+        FROM stock_levels l
+        JOIN products p ON p.id = l.product_id
+        JOIN warehouses w ON w.id = l.warehouse_id
+        LEFT JOIN product_variants v ON v.id = l.variant_id
+        LEFT JOIN stock_movements m
+            ON m.warehouse_id = l.warehouse_id
+           AND m.product_id = l.product_id
+           AND m.variant_id IS NOT DISTINCT FROM l.variant_id
+        GROUP BY l.warehouse_id, l.product_id, l.variant_id, l.quantity, p.name, v.option_values,
+                 w.name
+        HAVING l.quantity <> COALESCE(SUM(m.quantity), 0)
+        ORDER BY name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
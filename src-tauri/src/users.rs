@@ -0,0 +1,440 @@
+use serde_json::json;
+use sqlx::{FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Row};
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, CreateUser, FieldError, Page, Role, UpdateUser, User,
+    UserFilter, UserQuery, UserSort, PURGE_AFTER_DAYS,
+};
+
+use crate::auth::{self, MIN_PASSWORD_LENGTH};
+use crate::{audit, roles};
+use crate::query::{self, contains_pattern, Filter, SortField};
+
+impl SortField for UserSort {
+    fn column(&self) -> &'static str {
+        match self {
+            UserSort::CreatedAt => "created_at",
+            UserSort::Name => "name",
+            UserSort::Email => "email",
+        }
+    }
+}
+
+impl Filter for UserFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(ids) = &self.ids {
+            builder.push(" AND id = ANY(").push_bind(ids.clone()).push(")");
+        }
+        if let Some(search) = self.search.as_deref().filter(|s| !s.trim().is_empty()) {
+            let pattern = contains_pattern(search);
+            builder.push(" AND (name ILIKE ").push_bind(pattern.clone());
+            builder.push(" OR email ILIKE ").push_bind(pattern).push(")");
+        }
+        if let Some(from) = self.created_from {
+            builder.push(" AND created_at >= ").push_bind(from);
+        }
+        if let Some(to) = self.created_to {
+            builder.push(" AND created_at < ").push_bind(to);
+        }
+        if !self.include_deleted {
+            builder.push(" AND deleted_at IS NULL");
+        }
+    }
+}
+
+pub async fn list(pool: &PgPool, query: &UserQuery) -> Result<Page<User>, AppError> {
+    query::fetch_page(pool, "users", query).await
+}
+
+pub async fn get(pool: &PgPool, id: Uuid) -> Result<User, AppError> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::not_found("user", id))
+}
+
+// 更新・削除の前の状態（監査ログ用）を取得し、トランザクションの終わりまで行をロックする
+// 削除済みのユーザーは NotFound
+async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<User, AppError> {
+    let user = lock_any(conn, id).await?;
+    if user.deleted_at.is_some() {
+        return Err(AppError::not_found("user", id));
+    }
+    Ok(user)
+}
+
+// 削除済みのユーザーも含めて行をロックする
+async fn lock_any(conn: &mut PgConnection, id: Uuid) -> Result<User, AppError> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::not_found("user", id))
+}
+
+// 簡易的な形式チェック（到達可能かどうかまでは確認しない）
+pub fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+// 入力値の検証（None のフィールドは検証しない）
+fn validate(name: Option<&str>, email: Option<&str>, password: Option<&str>) -> Result<(), AppError> {
+    let mut fields = Vec::new();
+    if name.is_some_and(|v| v.trim().is_empty()) {
+        fields.push(FieldError::new("name", "must not be empty"));
+    }
+    if let Some(email) = email.map(str::trim) {
+        if email.is_empty() {
+            fields.push(FieldError::new("email", "must not be empty"));
+        } else if !is_email(email) {
+            fields.push(FieldError::new("email", "is not a valid email address"));
+        }
+    }
+    if password.is_some_and(|p| p.chars().count() < MIN_PASSWORD_LENGTH) {
+        fields.push(FieldError::new(
+            "password",
+            format!("must be at least {} characters", MIN_PASSWORD_LENGTH),
+        ));
+    }
+    AppError::check_fields(fields)
+}
+
+async fn hash_optional(password: Option<String>) -> Result<Option<String>, AppError> {
+    match password {
+        Some(password) => Ok(Some(auth::hash_password(password).await?)),
+        None => Ok(None),
+    }
+}
+
+// メールアドレスの重複は Conflict（fields に email）
+pub async fn create(pool: &PgPool, actor: &User, data: CreateUser) -> Result<User, AppError> {
+    validate(Some(&data.name), Some(&data.email), data.password.as_deref())?;
+    let has_password = data.password.is_some();
+    let password_hash = hash_optional(data.password).await?;
+
+    let mut tx = pool.begin().await?;
+    let user = sqlx::query_as::<_, User>(
+        "INSERT INTO users (name, email, password_hash, role) VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(data.name.trim())
+    .bind(data.email.trim())
+    .bind(password_hash)
+    .bind(data.role.unwrap_or_default().as_str())
+    .fetch_one(&mut *tx)
+    .await?;
+    let mut changes = audit::diff(None, Some(&user))?;
+    if has_password {
+        changes.insert("password".to_string(), password_change());
+    }
+    audit::record(&mut tx, actor, AuditEntity::User, user.id, AuditAction::Create, changes).await?;
+    tx.commit().await?;
+    Ok(user)
+}
+
+// パスワードの値は監査ログにも残さず、変更されたことだけを記録する
+fn password_change() -> serde_json::Value {
+    json!({ "before": null, "after": "(changed)" })
+}
+
+// 指定されたフィールドだけを更新する
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateUser,
+) -> Result<User, AppError> {
+    validate(data.name.as_deref(), data.email.as_deref(), data.password.as_deref())?;
+    let has_password = data.password.is_some();
+    let password_hash = hash_optional(data.password).await?;
+
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    roles::ensure_can_manage(&mut tx, actor, &before).await?;
+    if data.role.is_some_and(|role| role != Role::Admin) {
+        roles::ensure_admin_remains(&mut tx, id).await?;
+    }
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users
+        SET name = COALESCE($2, name),
+            email = COALESCE($3, email),
+            password_hash = COALESCE($4, password_hash),
+            role = COALESCE($5, role),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(data.name.as_deref().map(str::trim))
+    .bind(data.email.as_deref().map(str::trim))
+    .bind(password_hash)
+    .bind(data.role.map(|role| role.as_str()))
+    .fetch_one(&mut *tx)
+    .await?;
+    let mut changes = audit::diff(Some(&before), Some(&user))?;
+    if has_password {
+        changes.insert("password".to_string(), password_change());
+    }
+    audit::record(&mut tx, actor, AuditEntity::User, id, AuditAction::Update, changes).await?;
+    tx.commit().await?;
+    Ok(user)
+}
+
+// 論理削除する（ログインできなくなるが、注文の履歴からは参照できる）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    roles::ensure_can_manage(&mut tx, actor, &before).await?;
+    roles::ensure_admin_remains(&mut tx, id).await?;
+    let user = sqlx::query_as::<_, User>(
+        "UPDATE users SET deleted_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::User,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        Some(&user),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// 論理削除を取り消す（削除されていないユーザーは Conflict）
+pub async fn restore(pool: &PgPool, actor: &User, id: Uuid) -> Result<User, AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock_any(&mut tx, id).await?;
+    roles::ensure_can_manage(&mut tx, actor, &before).await?;
+    if before.deleted_at.is_none() {
+        return Err(AppError::Conflict {
+            message: "the user is not deleted".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    let user = sqlx::query_as::<_, User>(
+        "UPDATE users SET deleted_at = NULL WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::User,
+        id,
+        AuditAction::Restore,
+        Some(&before),
+        Some(&user),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(user)
+}
+
+// 削除から PURGE_AFTER_DAYS 日を過ぎたユーザーを完全削除する
+// 注文ステータスの変更履歴から参照されているユーザーは残す
+pub async fn purge(conn: &mut PgConnection, actor: &User) -> Result<u64, AppError> {
+    let users = sqlx::query_as::<_, User>(
+        r#"
+        DELETE FROM users u
+        WHERE u.deleted_at < NOW() - make_interval(days => $1)
+          AND NOT EXISTS (SELECT 1 FROM order_status_history h WHERE h.changed_by = u.id)
+        RETURNING *
+        "#,
+    )
+    .bind(PURGE_AFTER_DAYS)
+    .fetch_all(&mut *conn)
+    .await?;
+    for user in &users {
+        audit::record_change(
+            &mut *conn,
+            actor,
+            AuditEntity::User,
+            user.id,
+            AuditAction::Purge,
+            Some(user),
+            None,
+        )
+        .await?;
+    }
+    Ok(users.len() as u64)
+}
+
+// メールアドレスとパスワードでユーザーを確認する
+// どちらが誤っているかは返さない（登録済みのメールアドレスを探られないように）
+pub async fn authenticate(pool: &PgPool, email: &str, password: &str) -> Result<User, AppError> {
+    let invalid = || AppError::unauthorized("invalid email or password");
+    let row = sqlx::query(
+        "SELECT * FROM users WHERE email = $1 AND password_hash IS NOT NULL AND deleted_at IS NULL",
+    )
+        .bind(email.trim())
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(invalid)?;
+    let hash: String = row.try_get("password_hash")?;
+    if !auth::verify_password(password.to_string(), hash).await {
+        return Err(invalid());
+    }
+    Ok(User::from_row(&row)?)
+}
+
+// ログインできるユーザー（パスワードを持ち、削除されていない）が一人もいなければ、最初のユーザーの登録が必要
+pub async fn setup_required(pool: &PgPool) -> Result<bool, AppError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM users WHERE password_hash IS NOT NULL AND deleted_at IS NULL)",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(!exists)
+}
+
+// 最初のユーザーを admin として登録する（ログインできるユーザーがいる場合は Conflict）
+// 同じメールアドレスのユーザーがいれば、そのユーザーにパスワードを設定する（削除済みなら元に戻す）
+// 監査ログには登録したユーザー自身の操作として記録する
+pub async fn setup_first_user(pool: &PgPool, data: CreateUser) -> Result<User, AppError> {
+    let Some(password) = data.password else {
+        return Err(AppError::field("password", "must not be empty"));
+    };
+    validate(Some(&data.name), Some(&data.email), Some(&password))?;
+    let password_hash = auth::hash_password(password).await?;
+
+    let mut tx = pool.begin().await?;
+    // 登録を1つずつにする（別々のメールアドレスで同時に登録すると、どちらもログインできるユーザーがいないと判断して admin が2人できる）
+    sqlx::query("LOCK TABLE users IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await?;
+    let before = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1 FOR UPDATE")
+        .bind(data.email.trim())
+        .fetch_optional(&mut *tx)
+        .await?;
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (name, email, password_hash, role)
+        SELECT $1, $2, $3, 'admin'
+        WHERE NOT EXISTS (
+            SELECT 1 FROM users WHERE password_hash IS NOT NULL AND deleted_at IS NULL
+        )
+        ON CONFLICT (email) DO UPDATE
+        SET name = EXCLUDED.name,
+            password_hash = EXCLUDED.password_hash,
+            role = EXCLUDED.role,
+            deleted_at = NULL,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(data.name.trim())
+    .bind(data.email.trim())
+    .bind(password_hash)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict {
+        message: "the first user has already been set up".to_string(),
+        constraint: None,
+        fields: Vec::new(),
+    })?;
+    let action = if before.is_some() {
+        AuditAction::Update
+    } else {
+        AuditAction::Create
+    };
+    let mut changes = audit::diff(before.as_ref(), Some(&user))?;
+    changes.insert("password".to_string(), password_change());
+    audit::record(&mut tx, &user, AuditEntity::User, user.id, action, changes).await?;
+    tx.commit().await?;
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use sqlx::{ConnectOptions, Executor};
+    use uuid::Uuid;
+    use ws_models::{AppError, CreateUser};
+
+    use super::setup_first_user;
+    use crate::migrations;
+
+    fn first_user(email: &str) -> CreateUser {
+        CreateUser {
+            name: "Admin".to_string(),
+            email: email.to_string(),
+            password: Some("correct horse battery".to_string()),
+            role: None,
+        }
+    }
+
+    // TEST_DATABASE_URL のサーバーに使い捨てのデータベースを作って確かめる（未設定なら何もしない）
+    #[tokio::test(flavor = "multi_thread")]
+    async fn only_one_concurrent_setup_creates_an_admin() {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL is not set, skipping");
+            return;
+        };
+        let options = PgConnectOptions::from_str(&url).unwrap();
+        let name = format!("ws_test_{}", Uuid::new_v4().simple());
+        let mut admin = options.connect().await.unwrap();
+        admin
+            .execute(format!("CREATE DATABASE {}", name).as_str())
+            .await
+            .unwrap();
+
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .connect_with(options.clone().database(&name))
+            .await
+            .unwrap();
+        migrations::run(&pool).await.unwrap();
+        // 1回では競合しないこともあるため、ログインできるユーザーをなくして何度か繰り返す
+        let mut results = Vec::new();
+        for round in 0..10 {
+            sqlx::query("UPDATE users SET password_hash = NULL")
+                .execute(&pool)
+                .await
+                .unwrap();
+            let (a, b) = tokio::join!(
+                setup_first_user(&pool, first_user(&format!("a{}@example.com", round))),
+                setup_first_user(&pool, first_user(&format!("b{}@example.com", round))),
+            );
+            let admins: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE password_hash IS NOT NULL")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            results.push((admins, a, b));
+        }
+        pool.close().await;
+        admin
+            .execute(format!("DROP DATABASE {} WITH (FORCE)", name).as_str())
+            .await
+            .unwrap();
+
+        for (admins, a, b) in results {
+            assert_eq!(admins, 1);
+            assert!(a.is_ok() != b.is_ok(), "{:?} {:?}", a, b);
+            let conflict = if a.is_err() { a } else { b };
+            assert!(matches!(conflict, Err(AppError::Conflict { .. })));
+        }
+    }
+}
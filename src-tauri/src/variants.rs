@@ -0,0 +1,481 @@
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, FieldError, ProductOption, ProductVariant, ProductVariants,
+    SetProductOption, UpdateProductVariant, User, MAX_PRODUCT_OPTIONS,
+};
+
+use crate::{audit, products};
+
+// オプションの値の最大文字数
+const MAX_OPTION_VALUE_LENGTH: usize = 50;
+
+// 商品のオプションとバリエーションを取得する
+pub async fn list(pool: &PgPool, product_id: Uuid) -> Result<ProductVariants, AppError> {
+    let mut conn = pool.acquire().await?;
+    let found: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM products WHERE id = $1 AND deleted_at IS NULL)",
+    )
+    .bind(product_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    if !found {
+        return Err(AppError::not_found("product", product_id));
+    }
+    Ok(fetch(&mut conn, product_id).await?)
+}
+
+// バリエーションはオプションの値の順（1つ目のオプションの値ごとに、2つ目のオプションの値の順）に並べる
+async fn fetch(conn: &mut PgConnection, product_id: Uuid) -> Result<ProductVariants, sqlx::Error> {
+    let options = sqlx::query_as::<_, ProductOption>(
+        "SELECT * FROM product_options WHERE product_id = $1 ORDER BY position",
+    )
+    .bind(product_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut variants = sqlx::query_as::<_, ProductVariant>(
+        r#"
+        SELECT v.*, p.currency
+        FROM product_variants v
+        JOIN products p ON p.id = v.product_id
+        WHERE v.product_id = $1
+        "#,
+    )
+    .bind(product_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    variants.sort_by_key(|variant| {
+        options
+            .iter()
+            .zip(&variant.option_values)
+            .map(|(option, value)| option.values.iter().position(|v| v == value))
+            .collect::<Vec<_>>()
+    });
+    Ok(ProductVariants { options, variants })
+}
+
+// 商品にバリエーションがあるか（あれば商品の在庫はバリエーションの在庫の合計）
+pub async fn exists_for(conn: &mut PgConnection, product_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM product_variants WHERE product_id = $1)")
+        .bind(product_id)
+        .fetch_one(conn)
+        .await
+}
+
+// オプションの名前と値の空白を取り除き、空の値を除く
+// 名前や値の重複、値のないオプションは options.{i}.name・options.{i}.values のフィールドエラー
+fn normalize_options(options: &[SetProductOption]) -> Result<Vec<SetProductOption>, AppError> {
+    if options.len() > MAX_PRODUCT_OPTIONS {
+        return Err(AppError::field(
+            "options",
+            format!("a product can have at most {} options", MAX_PRODUCT_OPTIONS),
+        ));
+    }
+    let mut fields = Vec::new();
+    let mut names = HashSet::new();
+    let mut normalized = Vec::with_capacity(options.len());
+    for (i, option) in options.iter().enumerate() {
+        let name = option.name.trim().to_string();
+        if name.is_empty() {
+            fields.push(FieldError::new(format!("options.{}.name", i), "must not be empty"));
+        } else if !names.insert(name.to_lowercase()) {
+            fields.push(FieldError::new(format!("options.{}.name", i), "is used twice"));
+        }
+        let mut values: Vec<String> = Vec::new();
+        for value in option.values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            if value.chars().count() > MAX_OPTION_VALUE_LENGTH {
+                fields.push(FieldError::new(
+                    format!("options.{}.values", i),
+                    format!("{} is longer than {} characters", value, MAX_OPTION_VALUE_LENGTH),
+                ));
+            } else if values.iter().any(|v| v == value) {
+                fields.push(FieldError::new(
+                    format!("options.{}.values", i),
+                    format!("contains {} twice", value),
+                ));
+            } else {
+                values.push(value.to_string());
+            }
+        }
+        if values.is_empty() {
+            fields.push(FieldError::new(
+                format!("options.{}.values", i),
+                "must have at least one value",
+            ));
+        }
+        normalized.push(SetProductOption { name, values });
+    }
+    AppError::check_fields(fields)?;
+    Ok(normalized)
+}
+
+// オプションの値のすべての組み合わせ（オプションがなければバリエーションもない）
+fn combinations(options: &[SetProductOption]) -> Vec<Vec<String>> {
+    if options.is_empty() {
+        return Vec::new();
+    }
+    options.iter().fold(vec![Vec::new()], |combos, option| {
+        combos
+            .iter()
+            .flat_map(|combo| {
+                option.values.iter().map(move |value| {
+                    let mut next = combo.clone();
+                    next.push(value.clone());
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+// 監査ログに記録するオプションの内容
+fn options_snapshot<'a>(options: impl Iterator<Item = (&'a str, &'a [String])>) -> Value {
+    let options: Vec<Value> = options
+        .map(|(name, values)| json!({ "name": name, "values": values }))
+        .collect();
+    json!({ "options": options })
+}
+
+// SKU が削除されていない商品かバリエーションで使われているか
+// except_product を指定すると、その商品のバリエーションは除く
+async fn sku_used(
+    conn: &mut PgConnection,
+    sku: &str,
+    except_product: Option<Uuid>,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT name FROM products WHERE sku = $1 AND deleted_at IS NULL
+        UNION ALL
+        SELECT p.name || ' (' || array_to_string(v.option_values, ' / ') || ')'
+        FROM product_variants v
+        JOIN products p ON p.id = v.product_id
+        WHERE v.sku = $1 AND v.product_id IS DISTINCT FROM $2
+        LIMIT 1
+        "#,
+    )
+    .bind(sku)
+    .bind(except_product)
+    .fetch_optional(conn)
+    .await
+}
+
+// 新しいバリエーションの SKU（商品の SKU と値をハイフンでつなぐ。例: TSHIRT-M-RED）
+// SKU に使えない文字は取り除き、何も残らない値は値の番号にする
+// 既に使われていれば末尾に -2, -3... を付ける
+async fn generate_sku(
+    conn: &mut PgConnection,
+    product_sku: &str,
+    options: &[SetProductOption],
+    values: &[String],
+) -> Result<String, sqlx::Error> {
+    let mut base = product_sku.to_string();
+    for (option, value) in options.iter().zip(values) {
+        let part: String = products::normalize_sku(value)
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+            .collect();
+        let part = if part.is_empty() {
+            let index = option.values.iter().position(|v| v == value).unwrap_or(0);
+            (index + 1).to_string()
+        } else {
+            part
+        };
+        base.push('-');
+        base.push_str(&part);
+    }
+    let mut suffix = 1;
+    loop {
+        let tail = if suffix == 1 { String::new() } else { format!("-{}", suffix) };
+        let head: String = base.chars().take(64 - tail.len()).collect();
+        let sku = format!("{}{}", head, tail);
+        if sku_used(conn, &sku, None).await?.is_none() {
+            return Ok(sku);
+        }
+        suffix += 1;
+    }
+}
+
+// オプションを置き換え、値の組み合わせごとのバリエーションを作る
+// 残った組み合わせのバリエーションは SKU・価格・在庫をそのまま引き継ぐ
+// 在庫が残っているか注文に使われたバリエーションの組み合わせは削除できない（Conflict）
+pub async fn set_options(
+    pool: &PgPool,
+    actor: &User,
+    product_id: Uuid,
+    options: Vec<SetProductOption>,
+) -> Result<ProductVariants, AppError> {
+    let options = normalize_options(&options)?;
+    let combos = combinations(&options);
+
+    let mut tx = pool.begin().await?;
+    // 商品行をロックして、注文による在庫の引き当てと同時に変更しないようにする
+    let product = products::lock(&mut tx, product_id).await?;
+    let before = fetch(&mut tx, product_id).await?;
+    if before.variants.is_empty() && !combos.is_empty() && product.stock > 0 {
+        return Err(AppError::field(
+            "options",
+            format!(
+                "adjust the stock of {} to 0 before adding variants (stock is kept per variant)",
+                product.name
+            ),
+        ));
+    }
+
+    let removed: Vec<&ProductVariant> = before
+        .variants
+        .iter()
+        .filter(|v| !combos.contains(&v.option_values))
+        .collect();
+    let removed_ids: Vec<Uuid> = removed.iter().map(|v| v.id).collect();
+    let ordered: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT DISTINCT variant_id FROM order_items WHERE variant_id = ANY($1)",
+    )
+    .bind(&removed_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    let blocked: Vec<String> = removed
+        .iter()
+        .filter_map(|v| {
+            if ordered.contains(&v.id) {
+                Some(format!("{} is used by orders", v.label()))
+            } else if v.stock > 0 {
+                Some(format!("{} still has {} in stock", v.label(), v.stock))
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !blocked.is_empty() {
+        return Err(AppError::Conflict {
+            message: format!("cannot remove variants: {}", blocked.join(", ")),
+            constraint: None,
+            fields: vec![FieldError::new("options", blocked.join(", "))],
+        });
+    }
+
+    for variant in &removed {
+        sqlx::query("DELETE FROM product_variants WHERE id = $1")
+            .bind(variant.id)
+            .execute(&mut *tx)
+            .await?;
+        audit::record_change(
+            &mut tx,
+            actor,
+            AuditEntity::Variant,
+            variant.id,
+            AuditAction::Delete,
+            Some(*variant),
+            None,
+        )
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM product_options WHERE product_id = $1")
+        .bind(product_id)
+        .execute(&mut *tx)
+        .await?;
+    for (position, option) in options.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO product_options (product_id, name, position, option_values)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(product_id)
+        .bind(&option.name)
+        .bind(position as i32)
+        .bind(&option.values)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for values in combos
+        .iter()
+        .filter(|values| !before.variants.iter().any(|v| &v.option_values == *values))
+    {
+        let sku = generate_sku(&mut tx, &product.sku, &options, values).await?;
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO product_variants (product_id, sku, option_values)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+        )
+        .bind(product_id)
+        .bind(&sku)
+        .bind(values)
+        .fetch_one(&mut *tx)
+        .await?;
+        let created = fetch_variant(&mut tx, id).await?;
+        audit::record_change(
+            &mut tx,
+            actor,
+            AuditEntity::Variant,
+            id,
+            AuditAction::Create,
+            None,
+            Some(&created),
+        )
+        .await?;
+    }
+
+    // オプションの変更は商品の変更履歴に記録する
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Product,
+        product_id,
+        AuditAction::Update,
+        Some(&options_snapshot(
+            before.options.iter().map(|o| (o.name.as_str(), o.values.as_slice())),
+        )),
+        Some(&options_snapshot(
+            options.iter().map(|o| (o.name.as_str(), o.values.as_slice())),
+        )),
+    )
+    .await?;
+
+    let after = fetch(&mut tx, product_id).await?;
+    tx.commit().await?;
+    Ok(after)
+}
+
+async fn fetch_variant(conn: &mut PgConnection, id: Uuid) -> Result<ProductVariant, sqlx::Error> {
+    sqlx::query_as::<_, ProductVariant>(
+        r#"
+        SELECT v.*, p.currency
+        FROM product_variants v
+        JOIN products p ON p.id = v.product_id
+        WHERE v.id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(conn)
+    .await
+}
+
+// バリエーションの SKU・価格をまとめて更新する（表での一括編集）
+// 在庫は adjust_stock_batch で調整として記録する
+// 入力の誤りは variants.{i}.sku などのフィールドエラー（i は updates の順番）
+pub async fn update_variants(
+    pool: &PgPool,
+    actor: &User,
+    product_id: Uuid,
+    updates: Vec<UpdateProductVariant>,
+) -> Result<ProductVariants, AppError> {
+    let mut fields = Vec::new();
+    let mut skus = HashSet::new();
+    let updates: Vec<UpdateProductVariant> = updates
+        .into_iter()
+        .map(|update| UpdateProductVariant {
+            sku: update.sku.as_deref().map(products::normalize_sku),
+            ..update
+        })
+        .collect();
+    for (i, update) in updates.iter().enumerate() {
+        if let Some(sku) = update.sku.as_deref() {
+            if !products::is_sku(sku) {
+                fields.push(FieldError::new(
+                    format!("variants.{}.sku", i),
+                    "must be 1 to 64 letters, digits or - _ . /",
+                ));
+            } else if !skus.insert(sku.to_string()) {
+                fields.push(FieldError::new(format!("variants.{}.sku", i), "is used twice"));
+            }
+        }
+        if update.price.flatten().is_some_and(|p| p < Decimal::ZERO) {
+            fields.push(FieldError::new(format!("variants.{}.price", i), "must not be negative"));
+        }
+    }
+    AppError::check_fields(fields)?;
+
+    let mut tx = pool.begin().await?;
+    let product = products::lock(&mut tx, product_id).await?;
+    let before = fetch(&mut tx, product_id).await?;
+
+    // 重複は一括で更新した後の SKU で確かめる（バリエーションの間で SKU を入れ替えたり、譲ったりできる）
+    let skus_after: Vec<(&ProductVariant, &str)> = before
+        .variants
+        .iter()
+        .map(|variant| {
+            let sku = updates
+                .iter()
+                .find(|update| update.id == variant.id)
+                .and_then(|update| update.sku.as_deref())
+                .unwrap_or(&variant.sku);
+            (variant, sku)
+        })
+        .collect();
+    let mut fields = Vec::new();
+    for (i, update) in updates.iter().enumerate() {
+        let Some(current) = before.variants.iter().find(|v| v.id == update.id) else {
+            fields.push(FieldError::new(format!("variants.{}.id", i), "variant not found"));
+            continue;
+        };
+        if let Some(sku) = update.sku.as_deref().filter(|sku| *sku != current.sku) {
+            let owner = match skus_after
+                .iter()
+                .find(|(variant, after)| variant.id != current.id && *after == sku)
+            {
+                Some((variant, _)) => Some(format!("{} ({})", product.name, variant.label())),
+                None => sku_used(&mut tx, sku, Some(product_id)).await?,
+            };
+            if let Some(owner) = owner {
+                fields.push(FieldError::new(
+                    format!("variants.{}.sku", i),
+                    format!("is already used by {}", owner),
+                ));
+            }
+        }
+    }
+    if !fields.is_empty() {
+        return Err(AppError::Conflict {
+            message: "some variants cannot be updated".to_string(),
+            constraint: None,
+            fields,
+        });
+    }
+
+    for update in &updates {
+        let updated = sqlx::query_as::<_, ProductVariant>(
+            r#"
+            WITH updated AS (
+                UPDATE product_variants
+                SET sku = COALESCE($2, sku),
+                    price = CASE WHEN $3 THEN $4 ELSE price END
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT updated.*, p.currency
+            FROM updated
+            JOIN products p ON p.id = updated.product_id
+            "#,
+        )
+        .bind(update.id)
+        .bind(update.sku.as_deref())
+        .bind(update.price.is_some())
+        .bind(update.price.flatten())
+        .fetch_one(&mut *tx)
+        .await?;
+        let current = before.variants.iter().find(|v| v.id == update.id);
+        audit::record_change(
+            &mut tx,
+            actor,
+            AuditEntity::Variant,
+            update.id,
+            AuditAction::Update,
+            current,
+            Some(&updated),
+        )
+        .await?;
+    }
+
+    let after = fetch(&mut tx, product_id).await?;
+    tx.commit().await?;
+    Ok(after)
+}
@@ -0,0 +1,234 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use ws_models::{
+    AppError, AuditAction, AuditEntity, CreateWarehouse, UpdateWarehouse, User, Warehouse,
+};
+
+use crate::audit;
+
+// 保管場所の名前の最大文字数（warehouses.name の VARCHAR と同じ）
+const MAX_NAME_LENGTH: usize = 100;
+
+// すべての保管場所を取得する（既定の保管場所が先頭、以降は名前順）
+pub async fn list(pool: &PgPool) -> Result<Vec<Warehouse>, sqlx::Error> {
+    sqlx::query_as::<_, Warehouse>("SELECT * FROM warehouses ORDER BY is_default DESC, name")
+        .fetch_all(pool)
+        .await
+}
+
+// 変更の前に行をロックし、変更前の状態（監査ログ用）を取得する
+async fn lock(conn: &mut PgConnection, id: Uuid) -> Result<Warehouse, AppError> {
+    sqlx::query_as::<_, Warehouse>("SELECT * FROM warehouses WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::not_found("warehouse", id))
+}
+
+// 入出庫や注文に指定された保管場所（None なら既定の保管場所）
+// 見つからなければ field のフィールドエラー
+pub async fn resolve(
+    conn: &mut PgConnection,
+    id: Option<Uuid>,
+    field: &str,
+) -> Result<Warehouse, AppError> {
+    sqlx::query_as::<_, Warehouse>(
+        "SELECT * FROM warehouses WHERE CASE WHEN $1::uuid IS NULL THEN is_default ELSE id = $1 END",
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| match id {
+        Some(_) => AppError::field(field, "warehouse not found"),
+        None => AppError::field(field, "choose a warehouse"),
+    })
+}
+
+// 前後の空白を取り除いた名前（空や長すぎる名前は name のフィールドエラー）
+fn normalize_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::field("name", "must not be empty"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::field(
+            "name",
+            format!("must be at most {} characters", MAX_NAME_LENGTH),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+// 現在の既定の保管場所の既定を外す（一意インデックスに反しないよう、新しい既定を設定する前に呼ぶ）
+async fn clear_default(conn: &mut PgConnection, actor: &User) -> Result<(), AppError> {
+    let current =
+        sqlx::query_as::<_, Warehouse>("SELECT * FROM warehouses WHERE is_default FOR UPDATE")
+            .fetch_optional(&mut *conn)
+            .await?;
+    let Some(before) = current else {
+        return Ok(());
+    };
+    let updated = sqlx::query_as::<_, Warehouse>(
+        "UPDATE warehouses SET is_default = FALSE WHERE id = $1 RETURNING *",
+    )
+    .bind(before.id)
+    .fetch_one(&mut *conn)
+    .await?;
+    audit::record_change(
+        conn,
+        actor,
+        AuditEntity::Warehouse,
+        before.id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn create(
+    pool: &PgPool,
+    actor: &User,
+    data: CreateWarehouse,
+) -> Result<Warehouse, AppError> {
+    let name = normalize_name(&data.name)?;
+
+    let mut tx = pool.begin().await?;
+    if data.is_default {
+        clear_default(&mut tx, actor).await?;
+    }
+    let created = sqlx::query_as::<_, Warehouse>(
+        "INSERT INTO warehouses (name, address, is_default) VALUES ($1, $2, $3) RETURNING *",
+    )
+    .bind(&name)
+    .bind(non_empty(data.address.as_deref()))
+    .bind(data.is_default)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Warehouse,
+        created.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(created)
+}
+
+// 名前・住所の変更と、既定の保管場所の切り替え
+pub async fn update(
+    pool: &PgPool,
+    actor: &User,
+    id: Uuid,
+    data: UpdateWarehouse,
+) -> Result<Warehouse, AppError> {
+    let name = data.name.as_deref().map(normalize_name).transpose()?;
+
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    let make_default = match data.is_default {
+        Some(false) if before.is_default => {
+            return Err(AppError::field(
+                "is_default",
+                "make another warehouse the default instead",
+            ));
+        }
+        Some(true) => !before.is_default,
+        _ => false,
+    };
+    if make_default {
+        clear_default(&mut tx, actor).await?;
+    }
+    let updated = sqlx::query_as::<_, Warehouse>(
+        r#"
+        UPDATE warehouses
+        SET name = COALESCE($2, name),
+            address = CASE WHEN $3 THEN $4 ELSE address END,
+            is_default = is_default OR $5,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(name)
+    .bind(data.address.is_some())
+    .bind(non_empty(data.address.flatten().as_deref()))
+    .bind(make_default)
+    .fetch_one(&mut *tx)
+    .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Warehouse,
+        id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// 保管場所を削除する
+// 既定の保管場所と、入出庫の記録や注文のある保管場所は Conflict（記録は保管場所を参照し続ける）
+pub async fn delete(pool: &PgPool, actor: &User, id: Uuid) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let before = lock(&mut tx, id).await?;
+    if before.is_default {
+        return Err(AppError::Conflict {
+            message: "the default warehouse cannot be deleted".to_string(),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    let (movements, orders): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT (SELECT COUNT(*) FROM stock_movements WHERE warehouse_id = $1),
+               (SELECT COUNT(*) FROM orders WHERE warehouse_id = $1)
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if movements > 0 || orders > 0 {
+        return Err(AppError::Conflict {
+            message: format!(
+                "the warehouse has {} stock movements and {} orders",
+                movements, orders
+            ),
+            constraint: None,
+            fields: Vec::new(),
+        });
+    }
+    sqlx::query("DELETE FROM warehouses WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    audit::record_change(
+        &mut tx,
+        actor,
+        AuditEntity::Warehouse,
+        id,
+        AuditAction::Delete,
+        Some(&before),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
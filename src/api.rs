@@ -0,0 +1,613 @@
+// バックエンドの Tauri コマンドを呼び出す型付きクライアント
+// コマンドごとに関数を用意し、引数名（camelCase）と戻り値の型をここで一元管理する
+// 画面から使っていないコマンドの関数もあるため dead_code は許可する
+#![allow(dead_code)]
+
+use dioxus::prelude::{GlobalSignal, Readable, Signal};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+use ws_models::{
+    Address, AdjustStock, AdjustStockBatch, AppError, AuditLogEntry, AuditQuery, Client,
+    ClientQuery, ConnectionConfig, ConnectionProfile, ConnectionSettings, ConnectionStatus,
+    CreateAddress, CreateClient, CreateOrder, CreateProduct, CreateProductCategory,
+    CreateStockTransfer, CreateUser, CreateWarehouse, MigrationStatus, Order, OrderQuery,
+    OrderStatus, OrderStatusHistory, OrderWithItems, Page, Product, ProductCategory, ProductQuery,
+    ProductVariants, PurgeReport, ReorderQuery, ReorderSuggestion, SearchHit, Session,
+    SetProductOption, StockDiscrepancy, StockLevel, StockMovement, StockMovementQuery,
+    StockTransfer, UpdateAddress, UpdateClient, UpdateOrder, UpdateProduct, UpdateProductCategory,
+    UpdateProductVariant, UpdateUser, UpdateWarehouse, User, UserQuery, Warehouse,
+};
+
+use crate::error::from_js;
+
+#[wasm_bindgen]
+extern "C" {
+    // コマンドがエラーを返すと Promise が reject されるため catch で受け取る
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"])]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> Result<JsValue, JsValue>;
+}
+
+// バックエンドの generate_handler! と同じ一覧から生成したコマンド名
+macro_rules! command_names {
+    ($($command:ident),* $(,)?) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy, Debug)]
+        enum Command {
+            $($command),*
+        }
+
+        impl Command {
+            fn name(self) -> &'static str {
+                match self {
+                    $(Command::$command => stringify!($command)),*
+                }
+            }
+        }
+    };
+}
+
+ws_models::commands!(command_names);
+
+// ログイン中のセッション（None ならログイン画面を表示する）
+pub static SESSION: GlobalSignal<Option<Session>> = Signal::global(|| None);
+
+// コマンドを呼び出し、結果を T として受け取る
+// ログイン中はすべてのコマンドの引数に token を付ける
+async fn call<A: Serialize, T: DeserializeOwned>(command: Command, args: &A) -> Result<T, AppError> {
+    let args = serde_wasm_bindgen::to_value(args)
+        .map_err(|e| AppError::internal(format!("{}: failed to encode arguments: {}", command.name(), e)))?;
+    if let Some(session) = SESSION.peek().as_ref() {
+        js_sys::Reflect::set(&args, &JsValue::from_str("token"), &JsValue::from_str(&session.token))
+            .map_err(from_js)?;
+    }
+    let result = match invoke(command.name(), args).await {
+        Ok(result) => result,
+        Err(e) => {
+            let error = from_js(e);
+            // セッションが切れていたらログイン画面に戻す
+            if matches!(error, AppError::Unauthorized { .. }) && SESSION.peek().is_some() {
+                *SESSION.write() = None;
+            }
+            return Err(error);
+        }
+    };
+    serde_wasm_bindgen::from_value(result)
+        .map_err(|e| AppError::internal(format!("{}: unexpected response: {}", command.name(), e)))
+}
+
+// バックエンドが送るイベント（payload 以外のフィールドは使わない）
+#[derive(Deserialize)]
+struct Event<T> {
+    payload: T,
+}
+
+// バックエンドのイベントを購読する（アプリを閉じるまで受け取り続ける）
+pub async fn listen_event<T: DeserializeOwned + 'static>(
+    event: &str,
+    mut handler: impl FnMut(T) + 'static,
+) -> Result<(), AppError> {
+    let closure = Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+        if let Ok(event) = serde_wasm_bindgen::from_value::<Event<T>>(value) {
+            handler(event.payload);
+        }
+    });
+    listen(event, &closure).await.map_err(from_js)?;
+    // JS 側から呼ばれ続けるため解放しない
+    closure.forget();
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+#[derive(Serialize)]
+struct IdArgs {
+    id: Uuid,
+}
+
+#[derive(Serialize)]
+struct QueryArgs<'a, Q> {
+    query: &'a Q,
+}
+
+pub async fn greet(name: &str) -> Result<String, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        name: &'a str,
+    }
+    call(Command::greet, &Args { name }).await
+}
+
+// データベース接続
+pub async fn get_connection_status() -> Result<ConnectionStatus, AppError> {
+    call(Command::get_connection_status, &NoArgs {}).await
+}
+
+// password が None なら今のパスワードのまま接続し直す
+pub async fn connect_database(settings: &ConnectionSettings) -> Result<ConnectionStatus, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        settings: &'a ConnectionSettings,
+    }
+    call(Command::connect_database, &Args { settings }).await
+}
+
+pub async fn get_connection_config() -> Result<ConnectionConfig, AppError> {
+    call(Command::get_connection_config, &NoArgs {}).await
+}
+
+// password が None なら保存済みのパスワードを使い続ける
+pub async fn save_connection_profile(
+    profile: &ConnectionProfile,
+    password: Option<String>,
+) -> Result<ConnectionConfig, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        profile: &'a ConnectionProfile,
+        password: Option<String>,
+    }
+    call(Command::save_connection_profile, &Args { profile, password }).await
+}
+
+pub async fn delete_connection_profile(name: &str) -> Result<ConnectionConfig, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        name: &'a str,
+    }
+    call(Command::delete_connection_profile, &Args { name }).await
+}
+
+pub async fn select_connection_profile(name: &str) -> Result<ConnectionStatus, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        name: &'a str,
+    }
+    call(Command::select_connection_profile, &Args { name }).await
+}
+
+// 認証
+pub async fn is_setup_required() -> Result<bool, AppError> {
+    call(Command::is_setup_required, &NoArgs {}).await
+}
+
+// 最初のユーザーを登録してログインする
+pub async fn setup_first_user(user_data: &CreateUser) -> Result<Session, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        user_data: &'a CreateUser,
+    }
+    let session: Session = call(Command::setup_first_user, &Args { user_data }).await?;
+    *SESSION.write() = Some(session.clone());
+    Ok(session)
+}
+
+pub async fn login(email: &str, password: &str) -> Result<Session, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        email: &'a str,
+        password: &'a str,
+    }
+    let session: Session = call(Command::login, &Args { email, password }).await?;
+    *SESSION.write() = Some(session.clone());
+    Ok(session)
+}
+
+// バックエンドのセッションを破棄できなくても、画面はログアウトした状態にする
+pub async fn logout() -> Result<(), AppError> {
+    let result = call(Command::logout, &NoArgs {}).await;
+    *SESSION.write() = None;
+    result
+}
+
+// ユーザー
+pub async fn get_users(query: &UserQuery) -> Result<Page<User>, AppError> {
+    call(Command::get_users, &QueryArgs { query }).await
+}
+
+pub async fn get_user_by_id(id: Uuid) -> Result<User, AppError> {
+    call(Command::get_user_by_id, &IdArgs { id }).await
+}
+
+pub async fn create_user(user_data: &CreateUser) -> Result<User, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        user_data: &'a CreateUser,
+    }
+    call(Command::create_user, &Args { user_data }).await
+}
+
+pub async fn update_user(id: Uuid, user_data: &UpdateUser) -> Result<User, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        user_data: &'a UpdateUser,
+    }
+    call(Command::update_user, &Args { id, user_data }).await
+}
+
+pub async fn delete_user(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_user, &IdArgs { id }).await
+}
+
+pub async fn restore_user(id: Uuid) -> Result<User, AppError> {
+    call(Command::restore_user, &IdArgs { id }).await
+}
+
+// 取引先
+pub async fn get_clients(query: &ClientQuery) -> Result<Page<Client>, AppError> {
+    call(Command::get_clients, &QueryArgs { query }).await
+}
+
+pub async fn get_client_by_id(id: Uuid) -> Result<Client, AppError> {
+    call(Command::get_client_by_id, &IdArgs { id }).await
+}
+
+pub async fn create_client(client_data: &CreateClient) -> Result<Client, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        client_data: &'a CreateClient,
+    }
+    call(Command::create_client, &Args { client_data }).await
+}
+
+pub async fn update_client(id: Uuid, client_data: &UpdateClient) -> Result<Client, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        client_data: &'a UpdateClient,
+    }
+    call(Command::update_client, &Args { id, client_data }).await
+}
+
+pub async fn delete_client(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_client, &IdArgs { id }).await
+}
+
+pub async fn restore_client(id: Uuid) -> Result<Client, AppError> {
+    call(Command::restore_client, &IdArgs { id }).await
+}
+
+// 取引先の住所
+pub async fn get_client_addresses(client_id: Uuid) -> Result<Vec<Address>, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args {
+        client_id: Uuid,
+    }
+    call(Command::get_client_addresses, &Args { client_id }).await
+}
+
+pub async fn create_client_address(
+    client_id: Uuid,
+    address_data: &CreateAddress,
+) -> Result<Address, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        client_id: Uuid,
+        address_data: &'a CreateAddress,
+    }
+    call(Command::create_client_address, &Args { client_id, address_data }).await
+}
+
+pub async fn update_client_address(
+    id: Uuid,
+    address_data: &UpdateAddress,
+) -> Result<Address, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        address_data: &'a UpdateAddress,
+    }
+    call(Command::update_client_address, &Args { id, address_data }).await
+}
+
+pub async fn delete_client_address(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_client_address, &IdArgs { id }).await
+}
+
+// 商品
+pub async fn get_products(query: &ProductQuery) -> Result<Page<Product>, AppError> {
+    call(Command::get_products, &QueryArgs { query }).await
+}
+
+pub async fn get_product_by_id(id: Uuid) -> Result<Product, AppError> {
+    call(Command::get_product_by_id, &IdArgs { id }).await
+}
+
+// 見つからなければ NotFound（id は None）
+pub async fn get_product_by_barcode(barcode: &str) -> Result<Product, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        barcode: &'a str,
+    }
+    call(Command::get_product_by_barcode, &Args { barcode }).await
+}
+
+pub async fn create_product(product_data: &CreateProduct) -> Result<Product, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        product_data: &'a CreateProduct,
+    }
+    call(Command::create_product, &Args { product_data }).await
+}
+
+pub async fn update_product(id: Uuid, product_data: &UpdateProduct) -> Result<Product, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        product_data: &'a UpdateProduct,
+    }
+    call(Command::update_product, &Args { id, product_data }).await
+}
+
+pub async fn delete_product(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_product, &IdArgs { id }).await
+}
+
+pub async fn restore_product(id: Uuid) -> Result<Product, AppError> {
+    call(Command::restore_product, &IdArgs { id }).await
+}
+
+// 商品のオプションとバリエーション
+pub async fn get_product_variants(product_id: Uuid) -> Result<ProductVariants, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args {
+        product_id: Uuid,
+    }
+    call(Command::get_product_variants, &Args { product_id }).await
+}
+
+// 入力の誤りは options.{i}.name・options.{i}.values のフィールドエラー
+pub async fn set_product_options(
+    product_id: Uuid,
+    options: &[SetProductOption],
+) -> Result<ProductVariants, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        product_id: Uuid,
+        options: &'a [SetProductOption],
+    }
+    call(Command::set_product_options, &Args { product_id, options }).await
+}
+
+// 入力の誤りは variants.{i}.sku などのフィールドエラー（i は variants の順番）
+pub async fn update_product_variants(
+    product_id: Uuid,
+    variants: &[UpdateProductVariant],
+) -> Result<ProductVariants, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        product_id: Uuid,
+        variants: &'a [UpdateProductVariant],
+    }
+    call(Command::update_product_variants, &Args { product_id, variants }).await
+}
+
+// 在庫の入出庫の記録（新しい順）
+pub async fn get_stock_movements(
+    query: &StockMovementQuery,
+) -> Result<Page<StockMovement>, AppError> {
+    call(Command::get_stock_movements, &QueryArgs { query }).await
+}
+
+// 入庫・返品・調整を記録する（在庫が足りなければ Conflict）
+pub async fn adjust_stock(adjustment: &AdjustStock) -> Result<StockMovement, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        adjustment: &'a AdjustStock,
+    }
+    call(Command::adjust_stock, &Args { adjustment }).await
+}
+
+// 在庫の調整をまとめて記録する（在庫が足りなければ Conflict で、何も記録しない）
+pub async fn adjust_stock_batch(batch: &AdjustStockBatch) -> Result<Vec<StockMovement>, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        batch: &'a AdjustStockBatch,
+    }
+    call(Command::adjust_stock_batch, &Args { batch }).await
+}
+
+pub async fn get_stock_discrepancies() -> Result<Vec<StockDiscrepancy>, AppError> {
+    call(Command::get_stock_discrepancies, &NoArgs {}).await
+}
+
+// 直近の販売の速さから求めた発注の提案
+pub async fn get_reorder_suggestions(
+    query: &ReorderQuery,
+) -> Result<Vec<ReorderSuggestion>, AppError> {
+    call(Command::get_reorder_suggestions, &QueryArgs { query }).await
+}
+
+// 商品の保管場所ごとの在庫数（記録のない保管場所は含まない）
+pub async fn get_stock_levels(product_id: Uuid) -> Result<Vec<StockLevel>, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args {
+        product_id: Uuid,
+    }
+    call(Command::get_stock_levels, &Args { product_id }).await
+}
+
+// 移動元の在庫が足りなければ Conflict（items.{i}.quantity のフィールドエラー）
+pub async fn create_stock_transfer(
+    transfer: &CreateStockTransfer,
+) -> Result<StockTransfer, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        transfer: &'a CreateStockTransfer,
+    }
+    call(Command::create_stock_transfer, &Args { transfer }).await
+}
+
+// 保管場所（既定の保管場所が先頭）
+pub async fn get_warehouses() -> Result<Vec<Warehouse>, AppError> {
+    call(Command::get_warehouses, &NoArgs {}).await
+}
+
+pub async fn create_warehouse(warehouse_data: &CreateWarehouse) -> Result<Warehouse, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        warehouse_data: &'a CreateWarehouse,
+    }
+    call(Command::create_warehouse, &Args { warehouse_data }).await
+}
+
+pub async fn update_warehouse(
+    id: Uuid,
+    warehouse_data: &UpdateWarehouse,
+) -> Result<Warehouse, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        warehouse_data: &'a UpdateWarehouse,
+    }
+    call(Command::update_warehouse, &Args { id, warehouse_data }).await
+}
+
+// 既定の保管場所と、入出庫の記録や注文のある保管場所は削除できない（Conflict）
+pub async fn delete_warehouse(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_warehouse, &IdArgs { id }).await
+}
+
+// 商品カテゴリ（階層順。path に上位のカテゴリ名を含む）
+pub async fn get_product_categories() -> Result<Vec<ProductCategory>, AppError> {
+    call(Command::get_product_categories, &NoArgs {}).await
+}
+
+pub async fn create_product_category(
+    category_data: &CreateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        category_data: &'a CreateProductCategory,
+    }
+    call(Command::create_product_category, &Args { category_data }).await
+}
+
+pub async fn update_product_category(
+    id: Uuid,
+    category_data: &UpdateProductCategory,
+) -> Result<ProductCategory, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        category_data: &'a UpdateProductCategory,
+    }
+    call(Command::update_product_category, &Args { id, category_data }).await
+}
+
+pub async fn delete_product_category(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_product_category, &IdArgs { id }).await
+}
+
+// 注文
+pub async fn get_orders(query: &OrderQuery) -> Result<Page<Order>, AppError> {
+    call(Command::get_orders, &QueryArgs { query }).await
+}
+
+pub async fn get_orders_with_items(query: &OrderQuery) -> Result<Page<OrderWithItems>, AppError> {
+    call(Command::get_orders_with_items, &QueryArgs { query }).await
+}
+
+pub async fn get_order_by_id(id: Uuid) -> Result<OrderWithItems, AppError> {
+    call(Command::get_order_by_id, &IdArgs { id }).await
+}
+
+pub async fn create_order(order_data: &CreateOrder) -> Result<OrderWithItems, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        order_data: &'a CreateOrder,
+    }
+    call(Command::create_order, &Args { order_data }).await
+}
+
+pub async fn update_order(id: Uuid, order_data: &UpdateOrder) -> Result<OrderWithItems, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args<'a> {
+        id: Uuid,
+        order_data: &'a UpdateOrder,
+    }
+    call(Command::update_order, &Args { id, order_data }).await
+}
+
+pub async fn delete_order(id: Uuid) -> Result<(), AppError> {
+    call(Command::delete_order, &IdArgs { id }).await
+}
+
+pub async fn restore_order(id: Uuid) -> Result<OrderWithItems, AppError> {
+    call(Command::restore_order, &IdArgs { id }).await
+}
+
+// 許可されていない遷移は Conflict になる（変更したユーザーはログイン中のユーザー）
+pub async fn transition_order(
+    order_id: Uuid,
+    status: OrderStatus,
+    note: Option<String>,
+) -> Result<OrderWithItems, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args {
+        order_id: Uuid,
+        status: OrderStatus,
+        note: Option<String>,
+    }
+    call(Command::transition_order, &Args { order_id, status, note }).await
+}
+
+pub async fn get_order_status_history(order_id: Uuid) -> Result<Vec<OrderStatusHistory>, AppError> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Args {
+        order_id: Uuid,
+    }
+    call(Command::get_order_status_history, &Args { order_id }).await
+}
+
+// 横断検索
+pub async fn search(query: &str, limit: Option<u32>) -> Result<Vec<SearchHit>, AppError> {
+    #[derive(Serialize)]
+    struct Args<'a> {
+        query: &'a str,
+        limit: Option<u32>,
+    }
+    call(Command::search, &Args { query, limit }).await
+}
+
+// 監査ログ
+pub async fn get_audit_log(query: &AuditQuery) -> Result<Page<AuditLogEntry>, AppError> {
+    call(Command::get_audit_log, &QueryArgs { query }).await
+}
+
+// 保存期間を過ぎた削除済みレコードの完全削除
+pub async fn purge_deleted() -> Result<PurgeReport, AppError> {
+    call(Command::purge_deleted, &NoArgs {}).await
+}
+
+// マイグレーション
+pub async fn get_migration_status() -> Result<Vec<MigrationStatus>, AppError> {
+    call(Command::get_migration_status, &NoArgs {}).await
+}
@@ -3,19 +3,24 @@
 
 use dioxus::{prelude::*};
 // use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::*;
+use ws_models::{
+    ConnectionStatus, LowStockAlert, Permission, SearchHit, SearchKind, CONNECTION_STATUS_EVENT,
+    LOW_STOCK_EVENT,
+};
 
+use crate::api;
+use crate::components::connection::ConnectionScreen;
+use crate::components::clients::Clients;
 use crate::components::menu_bar::{MenuBar, MenuItem};
-use crate::components::database_test::DatabaseTest;
+use crate::components::login::Login;
+use crate::components::orders::Orders;
+use crate::components::products::Products;
+use crate::components::search_box::SearchBox;
+use crate::components::settings::Settings;
+use crate::components::users::Users;
 
 static CSS: Asset = asset!("/assets/styles.css");
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
-
 // #[derive(Serialize, Deserialize)]
 // struct GreetArgs<'a> {
 //     name: &'a str,
@@ -23,11 +28,87 @@ extern "C" {
 
 pub fn App() -> Element {
     let mut selected_menu = use_signal(|| MenuItem::Dashboard);
+    // 検索結果から移動したときに表示するレコード
+    let mut focus = use_signal(|| Option::<SearchHit>::None);
+    // データベースの接続状態（None は取得前）
+    let mut connection = use_signal(|| Option::<ConnectionStatus>::None);
+    // バックエンドから知らされた、在庫が発注点以下の商品（閉じるまで表示する）
+    let mut low_stock = use_signal(Vec::<LowStockAlert>::new);
+
+    // 接続状態の変化を購読してから現在の状態を取得する（取りこぼさないように）
+    use_future(move || async move {
+        let listening = api::listen_event(CONNECTION_STATUS_EVENT, move |status: ConnectionStatus| {
+            connection.set(Some(status));
+        })
+        .await;
+        if let Err(e) = listening {
+            dioxus_logger::tracing::error!("failed to listen for connection status: {}", e);
+        }
+        if let Ok(status) = api::get_connection_status().await {
+            connection.set(Some(status));
+        }
+    });
+
+    // 同じ商品の知らせは新しいもので置き換える
+    use_future(move || async move {
+        let listening = api::listen_event(LOW_STOCK_EVENT, move |alerts: Vec<LowStockAlert>| {
+            let mut current = low_stock.write();
+            current.retain(|old| !alerts.iter().any(|alert| alert.product_id == old.product_id));
+            current.extend(alerts);
+        })
+        .await;
+        if let Err(e) = listening {
+            dioxus_logger::tracing::error!("failed to listen for low stock alerts: {}", e);
+        }
+    });
 
+    // Logout はセッションを破棄してログイン画面に戻る（画面は切り替えない）
     let handle_menu_change = move |item: MenuItem| {
+        if item == MenuItem::Logout {
+            spawn(async move {
+                if let Err(e) = api::logout().await {
+                    dioxus_logger::tracing::error!("failed to log out: {}", e);
+                }
+                selected_menu.set(MenuItem::Dashboard);
+                focus.set(None);
+            });
+            return;
+        }
         selected_menu.set(item);
     };
 
+    // 検索結果の種類に応じた画面に移動する
+    let mut handle_search_select = move |hit: SearchHit| {
+        selected_menu.set(match hit.kind {
+            SearchKind::Client => MenuItem::Clients,
+            SearchKind::Product => MenuItem::Products,
+            SearchKind::User => MenuItem::Users,
+            SearchKind::Order => MenuItem::Orders,
+        });
+        focus.set(Some(hit));
+    };
+
+    // 接続できるまではメイン画面の代わりに接続画面を表示する
+    if !connection.read().as_ref().is_some_and(|status| status.is_connected()) {
+        return rsx! {
+            document::Stylesheet { href: CSS }
+            ConnectionScreen { status: connection }
+        };
+    }
+
+    // ログインするまではログイン画面を表示する
+    let Some(session) = api::SESSION.read().clone() else {
+        return rsx! {
+            document::Stylesheet { href: CSS }
+            Login {}
+        };
+    };
+
+    // ロールが変わって開けなくなった画面はダッシュボードに戻す
+    let current_menu = Some(*selected_menu.read())
+        .filter(MenuItem::is_allowed)
+        .unwrap_or(MenuItem::Dashboard);
+
     rsx! {
         document::Stylesheet { href: CSS }
         // app container
@@ -35,10 +116,34 @@ pub fn App() -> Element {
             // sidebar
             div { class: "sidebar",
                 MenuBar { selected_menu, on_menu_change: handle_menu_change }
+                div { class: "current-user", "{session.user.name}" }
             }
             // main content
             div { class: "main-content",
-                match *selected_menu.read() {
+                SearchBox { on_select: handle_search_select }
+                if session.can(Permission::ViewProducts) && !low_stock.read().is_empty() {
+                    div { class: "low-stock-banner",
+                        strong { "Low stock (at or below the reorder point)" }
+                        for alert in low_stock.read().iter() {
+                            button {
+                                key: "{alert.product_id}",
+                                onclick: {
+                                    let hit = SearchHit {
+                                        kind: SearchKind::Product,
+                                        id: alert.product_id,
+                                        title: alert.name.clone(),
+                                        subtitle: Some(alert.sku.clone()),
+                                        rank: 0.0,
+                                    };
+                                    move |_| handle_search_select(hit.clone())
+                                },
+                                "{alert.name}: {alert.stock} left (reorder point {alert.reorder_point})"
+                            }
+                        }
+                        button { onclick: move |_| low_stock.write().clear(), "Dismiss" }
+                    }
+                }
+                match current_menu {
                     MenuItem::Dashboard => rsx! {
                         div { class: "dashboard-content",
                             h2 { "Dashboard" }
@@ -46,32 +151,22 @@ pub fn App() -> Element {
                         }
                     },
                     MenuItem::Clients => rsx! {
-                        div { class: "clients-content", DatabaseTest {} }
+                        div { class: "clients-content", Clients { focus } }
                     },
                     MenuItem::Orders => rsx! {
-                        div { class: "orders-content",
-                            h2 { "Orders" }
-                            p { "Welcome to the orders" }
-                        }
+                        div { class: "orders-content", Orders { focus } }
                     },
                     MenuItem::Products => rsx! {
-                        div { class: "products-content",
-                            h2 { "Products" }
-                            p { "Welcome to the products" }
-                        }
+                        div { class: "products-content", Products { focus } }
                     },
-                    MenuItem::Settings => rsx! {
-                        div { class: "settings-content",
-                            h2 { "Settings" }
-                            p { "Welcome to the settings" }
-                        }
+                    MenuItem::Users => rsx! {
+                        div { class: "users-content", Users { focus } }
                     },
-                    MenuItem::Logout => rsx! {
-                        div { class: "logout-content",
-                            h2 { "Logout" }
-                            p { "Welcome to the logout" }
-                        }
+                    MenuItem::Settings => rsx! {
+                        div { class: "settings-content", Settings {} }
                     },
+                    // Logout は handle_menu_change で処理するため選択されることはない
+                    MenuItem::Logout => rsx! {},
                 }
             }
         }
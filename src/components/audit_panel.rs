@@ -0,0 +1,79 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{AuditEntity, AuditFilter, AuditQuery, Permission};
+
+use crate::api;
+
+#[derive(Props, PartialEq, Clone)]
+pub struct AuditPanelProps {
+    pub entity: AuditEntity,
+    pub entity_id: Uuid,
+    // 値が変わるたびに履歴を読み直す（レコードの updated_at など）
+    #[props(into, default)]
+    pub revision: String,
+}
+
+// レコードの変更履歴（audit_log）。view_audit_log の権限がなければ何も表示しない
+#[component]
+pub fn AuditPanel(props: AuditPanelProps) -> Element {
+    let can_view = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ViewAuditLog));
+
+    let entries = use_resource(use_reactive!(|(props, can_view)| async move {
+        if !can_view {
+            return Ok(Vec::new());
+        }
+        let query = AuditQuery {
+            filter: AuditFilter {
+                entity: Some(props.entity),
+                entity_id: Some(props.entity_id),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        api::get_audit_log(&query).await.map(|page| page.items)
+    }));
+
+    if !can_view {
+        return rsx! {};
+    }
+
+    rsx! {
+      div { class: "audit-panel",
+        h4 { "History" }
+        match &*entries.read() {
+            None => rsx! {
+              p { class: "connection-retry", "Loading..." }
+            },
+            Some(Err(e)) => rsx! {
+              p { class: "error-message", "{e}" }
+            },
+            Some(Ok(entries)) if entries.is_empty() => rsx! {
+              p { class: "connection-retry", "No changes recorded." }
+            },
+            Some(Ok(entries)) => rsx! {
+              ol { class: "timeline",
+                for entry in entries.iter() {
+                  li { class: "timeline-entry", key: "{entry.id}",
+                    strong { "{entry.action}" }
+                    small {
+                      "{entry.created_at}"
+                      if let Some(name) = &entry.actor_name {
+                        " by {name}"
+                      }
+                    }
+                    for (field , change) in entry.changes.iter() {
+                      span { class: "audit-change",
+                        "{field}: {change.before} → {change.after}"
+                      }
+                    }
+                  }
+                }
+              }
+            },
+        }
+      }
+    }
+}
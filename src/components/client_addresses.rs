@@ -0,0 +1,302 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    Address, AppError, Client, CreateAddress, Permission, UpdateAddress, UpdateClient, COUNTRY_JP,
+    JP_PREFECTURES,
+};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ClientAddressesProps {
+    pub client: Client,
+    // 既定の請求先・配送先が変わったとき（取引先を読み直す）
+    pub on_change: EventHandler<()>,
+}
+
+// 編集フォームの初期値
+fn form_from(address: &Address) -> CreateAddress {
+    CreateAddress {
+        label: address.label.clone(),
+        address: address.address.clone(),
+    }
+}
+
+// フォームの値をすべて更新する（空の値はバックエンドで削除として扱われる）
+fn update_from(form: CreateAddress) -> UpdateAddress {
+    let address = form.address;
+    UpdateAddress {
+        label: Some(form.label),
+        recipient: Some(address.recipient),
+        postal_code: Some(address.postal_code),
+        region: Some(address.region),
+        city: Some(address.city),
+        line1: Some(address.line1),
+        line2: Some(address.line2),
+        country: Some(address.country),
+    }
+}
+
+// 取引先の住所の一覧と追加・編集フォーム
+// 最初に追加した住所が既定の請求先・配送先になり、一覧のボタンで変更できる
+#[component]
+pub fn ClientAddresses(props: ClientAddressesProps) -> Element {
+    let client = props.client.clone();
+    let client_id = client.id;
+    let mut addresses = use_signal(Vec::<Address>::new);
+    // 編集中の住所（None なら新規作成）
+    let mut editing = use_signal(|| Option::<Uuid>::None);
+    let mut form = use_signal(CreateAddress::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageClients));
+
+    let load_addresses = move || async move {
+        match api::get_client_addresses(client_id).await {
+            Ok(data) => addresses.set(data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    // 取引先が切り替わったら読み直す
+    use_effect(use_reactive!(|client_id| {
+        editing.set(None);
+        form.set(CreateAddress::default());
+        error.set(None);
+        spawn(async move {
+            match api::get_client_addresses(client_id).await {
+                Ok(data) => addresses.set(data),
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    }));
+
+    // 入力値の検証（JP の郵便番号・都道府県など）はバックエンドで行う
+    let save = move |_| async move {
+        let data = form.read().clone();
+        let result = match editing() {
+            Some(id) => api::update_client_address(id, &update_from(data)).await,
+            None => api::create_client_address(client_id, &data).await,
+        };
+        if let Err(e) = result {
+            error.set(Some(e));
+            return;
+        }
+        error.set(None);
+        editing.set(None);
+        form.set(CreateAddress::default());
+        load_addresses().await;
+        // 最初の住所は既定の請求先・配送先になる
+        props.on_change.call(());
+    };
+
+    let delete = move |id: Uuid| async move {
+        error.set(api::delete_client_address(id).await.err());
+        if editing() == Some(id) {
+            editing.set(None);
+            form.set(CreateAddress::default());
+        }
+        load_addresses().await;
+        props.on_change.call(());
+    };
+
+    let set_default = move |data: UpdateClient| async move {
+        error.set(api::update_client(client_id, &data).await.err());
+        props.on_change.call(());
+    };
+
+    let is_jp = form.read().address.country.trim().eq_ignore_ascii_case(COUNTRY_JP);
+
+    rsx! {
+      div { class: "client-addresses",
+        h4 { "Addresses" }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        div { class: "data-list",
+          for address in addresses.read().iter() {
+            div { class: if editing() == Some(address.id) { "data-item selected" } else { "data-item" },
+              strong { "{address.label.clone().unwrap_or_else(|| \"Address\".to_string())}" }
+              span { "{address.address}" }
+              div { class: "item-actions",
+                if client.default_billing_address_id == Some(address.id) {
+                  span { class: "role-badge", "Default billing" }
+                }
+                if client.default_shipping_address_id == Some(address.id) {
+                  span { class: "role-badge", "Default shipping" }
+                }
+                if can_manage {
+                  if client.default_billing_address_id != Some(address.id) {
+                    button {
+                      onclick: {
+                          let id = address.id;
+                          move |_| {
+                              let data = UpdateClient {
+                                  default_billing_address_id: Some(Some(id)),
+                                  ..Default::default()
+                              };
+                              spawn(async move {
+                                  set_default(data).await;
+                              });
+                          }
+                      },
+                      "Use for billing"
+                    }
+                  }
+                  if client.default_shipping_address_id != Some(address.id) {
+                    button {
+                      onclick: {
+                          let id = address.id;
+                          move |_| {
+                              let data = UpdateClient {
+                                  default_shipping_address_id: Some(Some(id)),
+                                  ..Default::default()
+                              };
+                              spawn(async move {
+                                  set_default(data).await;
+                              });
+                          }
+                      },
+                      "Use for shipping"
+                    }
+                  }
+                  button {
+                    onclick: {
+                        let address = address.clone();
+                        move |_| {
+                            editing.set(Some(address.id));
+                            form.set(form_from(&address));
+                            error.set(None);
+                        }
+                    },
+                    "Edit"
+                  }
+                  button {
+                    onclick: {
+                        let id = address.id;
+                        move |_| {
+                            spawn(async move {
+                                delete(id).await;
+                            });
+                        }
+                    },
+                    "Delete"
+                  }
+                }
+              }
+            }
+          }
+        }
+        if can_manage {
+          div { class: "client-form",
+            div { class: "field",
+              label { "Label" }
+              input {
+                placeholder: "Head office, Warehouse...",
+                value: "{form.read().label.clone().unwrap_or_default()}",
+                oninput: move |event| form.write().label = Some(event.value()),
+              }
+            }
+            div { class: "field",
+              label { "Recipient" }
+              input {
+                value: "{form.read().address.recipient.clone().unwrap_or_default()}",
+                oninput: move |event| form.write().address.recipient = Some(event.value()),
+              }
+            }
+            div { class: "field",
+              label { "Country" }
+              input {
+                placeholder: "JP",
+                value: "{form.read().address.country}",
+                oninput: move |event| form.write().address.country = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "country") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Postal code" }
+              input {
+                placeholder: if is_jp { "123-4567" } else { "" },
+                value: "{form.read().address.postal_code}",
+                oninput: move |event| form.write().address.postal_code = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "postal_code") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { if is_jp { "Prefecture" } else { "State / Region" } }
+              if is_jp {
+                select {
+                  onchange: move |event| form.write().address.region = event.value(),
+                  option { value: "", "Select prefecture" }
+                  for prefecture in JP_PREFECTURES.iter() {
+                    option {
+                      value: "{prefecture}",
+                      selected: form.read().address.region == *prefecture,
+                      "{prefecture}"
+                    }
+                  }
+                }
+              } else {
+                input {
+                  value: "{form.read().address.region}",
+                  oninput: move |event| form.write().address.region = event.value(),
+                }
+              }
+              if let Some(message) = field_message(&error.read(), "region") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "City" }
+              input {
+                value: "{form.read().address.city}",
+                oninput: move |event| form.write().address.city = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "city") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Address line 1" }
+              input {
+                value: "{form.read().address.line1}",
+                oninput: move |event| form.write().address.line1 = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "line1") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Address line 2" }
+              input {
+                value: "{form.read().address.line2.clone().unwrap_or_default()}",
+                oninput: move |event| form.write().address.line2 = Some(event.value()),
+              }
+            }
+            div { class: "form-group",
+              button { onclick: save,
+                if editing().is_some() { "Save Address" } else { "Add Address" }
+              }
+              if editing().is_some() {
+                button {
+                  onclick: move |_| {
+                      editing.set(None);
+                      form.set(CreateAddress::default());
+                      error.set(None);
+                  },
+                  "Cancel"
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+}
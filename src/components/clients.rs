@@ -0,0 +1,413 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    AppError, AuditEntity, Client, ClientFilter, CreateClient, Order, OrderFilter, Page,
+    PaymentTerms, Permission, SearchHit, SearchKind, UpdateClient,
+};
+
+use crate::api;
+use crate::components::audit_panel::AuditPanel;
+use crate::components::client_addresses::ClientAddresses;
+use crate::components::pager::{focused_ids, page_query, Pager};
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ClientsProps {
+    // 検索ボックスで選ばれたレコード
+    pub focus: Signal<Option<SearchHit>>,
+}
+
+// 編集フォームの初期値
+fn form_from(client: &Client) -> CreateClient {
+    CreateClient {
+        company_name: client.company_name.clone(),
+        contact_name: client.contact_name.clone(),
+        email: client.email.clone(),
+        phone: client.phone.clone(),
+        tax_id: client.tax_id.clone(),
+        payment_terms: client.payment_terms,
+        notes: client.notes.clone(),
+    }
+}
+
+// フォームの値をすべて更新する（空の値はバックエンドで削除として扱われる）
+// 既定の請求先・配送先は住所の一覧で変更する
+fn update_from(form: CreateClient) -> UpdateClient {
+    UpdateClient {
+        company_name: Some(form.company_name),
+        contact_name: Some(form.contact_name),
+        email: Some(form.email),
+        phone: Some(form.phone),
+        tax_id: Some(form.tax_id),
+        payment_terms: Some(form.payment_terms),
+        notes: Some(form.notes),
+        ..Default::default()
+    }
+}
+
+// 取引先の一覧と詳細（編集フォーム・注文・変更履歴）
+#[component]
+pub fn Clients(props: ClientsProps) -> Element {
+    let mut focus = props.focus;
+    let mut clients = use_signal(Page::<Client>::default);
+    let mut page = use_signal(|| 1u32);
+    let mut search = use_signal(String::new);
+    // 削除済みの取引先も一覧に表示する
+    let mut show_deleted = use_signal(|| false);
+    // 詳細を表示中の取引先（None で新規作成フォーム）
+    let mut selected_id = use_signal(|| Option::<Uuid>::None);
+    let mut form = use_signal(CreateClient::default);
+    let mut client_orders = use_signal(Page::<Order>::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageClients));
+
+    let load_clients = move || async move {
+        let text = search.read().trim().to_string();
+        let filter = ClientFilter {
+            ids: focused_ids(&focus.read(), SearchKind::Client),
+            search: (!text.is_empty()).then_some(text),
+            include_deleted: show_deleted(),
+            ..Default::default()
+        };
+        match api::get_clients(&page_query(page(), filter)).await {
+            Ok(clients_data) => clients.set(clients_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    // 取引先の最近の注文（1ページ目だけ）
+    let load_orders = move |client_id: Uuid| async move {
+        let filter = OrderFilter {
+            client_id: Some(client_id),
+            ..Default::default()
+        };
+        match api::get_orders(&page_query(1, filter)).await {
+            Ok(orders_data) => client_orders.set(orders_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let mut select = move |client: Option<Client>| {
+        error.set(None);
+        message.set(None);
+        client_orders.set(Page::default());
+        match client {
+            Some(client) => {
+                selected_id.set(Some(client.id));
+                form.set(form_from(&client));
+                spawn(async move {
+                    load_orders(client.id).await;
+                });
+            }
+            None => {
+                selected_id.set(None);
+                form.set(CreateClient::default());
+            }
+        }
+    };
+
+    // 新しい取引先を作成（選択中なら更新）
+    // 入力値の検証はバックエンドで行い、エラーは入力欄ごとに表示する
+    let save = move |_| async move {
+        let data = form.read().clone();
+        let result = match selected_id() {
+            Some(id) => api::update_client(id, &update_from(data)).await,
+            None => api::create_client(&data).await,
+        };
+        match result {
+            Ok(client) => {
+                error.set(None);
+                message.set(Some(format!("Saved {}", client.company_name)));
+                selected_id.set(Some(client.id));
+                form.set(form_from(&client));
+                load_clients().await;
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 削除は論理削除（Show deleted で表示して Restore で元に戻せる）
+    // 削除した取引先の注文はそのまま残るが、新しい注文の顧客には指定できない
+    let delete_or_restore = move |client_id: Uuid, restore: bool| async move {
+        let result = if restore {
+            api::restore_client(client_id).await.map(|_| ())
+        } else {
+            api::delete_client(client_id).await
+        };
+        error.set(result.err());
+        message.set(None);
+        if !restore {
+            selected_id.set(None);
+            form.set(CreateClient::default());
+        }
+        load_clients().await;
+    };
+
+    // 初回表示時と、検索結果から移動してきたときに読み込む
+    use_effect(move || {
+        let focused = focus
+            .read()
+            .as_ref()
+            .filter(|hit| hit.kind == SearchKind::Client)
+            .map(|hit| hit.id);
+        page.set(1);
+        spawn(async move {
+            load_clients().await;
+            if let Some(client_id) = focused {
+                let client = clients.read().items.iter().find(|c| c.id == client_id).cloned();
+                select(client);
+            }
+        });
+    });
+
+    let selected = selected_id
+        .read()
+        .as_ref()
+        .and_then(|id| clients.read().items.iter().find(|c| &c.id == id).cloned());
+
+    rsx! {
+      div { class: "clients",
+        h2 { "Clients" }
+        if let Some(hit) = focus.read().as_ref().filter(|hit| hit.kind == SearchKind::Client) {
+          div { class: "search-focus",
+            span { "Search result: Client {hit.title}" }
+            button { onclick: move |_| focus.set(None), "Show all" }
+          }
+        }
+        div { class: "form-group",
+          input {
+            placeholder: "Search company, contact, email or phone",
+            value: "{search}",
+            oninput: move |event| search.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    page.set(1);
+                    spawn(async move {
+                        load_clients().await;
+                    });
+                }
+            },
+          }
+          label { class: "show-deleted",
+            input {
+              r#type: "checkbox",
+              checked: show_deleted(),
+              onchange: move |event| {
+                  show_deleted.set(event.checked());
+                  page.set(1);
+                  spawn(async move {
+                      load_clients().await;
+                  });
+              },
+            }
+            "Show deleted"
+          }
+          if can_manage {
+            button { onclick: move |_| select(None), "New Client" }
+          }
+        }
+        div { class: "clients-layout",
+          // 取引先一覧
+          div { class: "data-list",
+            for client in clients.read().items.iter() {
+              div {
+                class: match (selected_id.read().as_ref() == Some(&client.id), client.deleted_at.is_some()) {
+                    (true, _) => "data-item selected",
+                    (false, true) => "data-item deleted",
+                    (false, false) => "data-item",
+                },
+                onclick: {
+                    let client = client.clone();
+                    move |_| select(Some(client.clone()))
+                },
+                strong { "{client.company_name}" }
+                if let Some(contact) = &client.contact_name {
+                  span { "{contact}" }
+                }
+                span { "{client.payment_terms.label()}" }
+                if client.deleted_at.is_some() {
+                  span { class: "status-badge status-cancelled", "Deleted" }
+                }
+              }
+            }
+            Pager {
+              page: clients.read().page,
+              total_pages: clients.read().total_pages,
+              total: clients.read().total,
+              on_page_change: move |p| {
+                  page.set(p);
+                  spawn(async move {
+                      load_clients().await;
+                  });
+              },
+            }
+          }
+          // 取引先の詳細（選択していなければ新規作成フォーム）
+          div { class: "client-detail section",
+            h3 {
+              match &selected {
+                  Some(client) => client.company_name.clone(),
+                  None => "New client".to_string(),
+              }
+            }
+            if let Some(message) = message.read().as_ref() {
+              p { class: "success-message", "{message}" }
+            }
+            if let Some(message) = general_message(&error.read()) {
+              p { class: "error-message", "{message}" }
+            }
+            if let Some(client) = selected.as_ref().filter(|c| c.deleted_at.is_some()) {
+              div { class: "form-group",
+                span { class: "status-badge status-cancelled", "Deleted" }
+                if can_manage {
+                  button {
+                    onclick: {
+                        let client_id = client.id;
+                        move |_| {
+                            spawn(async move {
+                                delete_or_restore(client_id, true).await;
+                            });
+                        }
+                    },
+                    "Restore"
+                  }
+                }
+              }
+            } else if selected.is_some() || can_manage {
+              div { class: "client-form",
+                div { class: "field",
+                  label { "Company name" }
+                  input {
+                    value: "{form.read().company_name}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().company_name = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "company_name") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Contact person" }
+                  input {
+                    value: "{form.read().contact_name.clone().unwrap_or_default()}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().contact_name = Some(event.value()),
+                  }
+                }
+                div { class: "field",
+                  label { "Email" }
+                  input {
+                    value: "{form.read().email.clone().unwrap_or_default()}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().email = Some(event.value()),
+                  }
+                  if let Some(message) = field_message(&error.read(), "email") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Phone" }
+                  input {
+                    value: "{form.read().phone.clone().unwrap_or_default()}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().phone = Some(event.value()),
+                  }
+                  if let Some(message) = field_message(&error.read(), "phone") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Tax ID" }
+                  input {
+                    value: "{form.read().tax_id.clone().unwrap_or_default()}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().tax_id = Some(event.value()),
+                  }
+                }
+                div { class: "field",
+                  label { "Payment terms" }
+                  select {
+                    disabled: !can_manage,
+                    onchange: move |event| {
+                        form.write().payment_terms = event.value().parse().unwrap_or_default();
+                    },
+                    for terms in PaymentTerms::ALL.iter() {
+                      option {
+                        value: "{terms.as_str()}",
+                        selected: *terms == form.read().payment_terms,
+                        "{terms.label()}"
+                      }
+                    }
+                  }
+                }
+                div { class: "field",
+                  label { "Notes" }
+                  textarea {
+                    value: "{form.read().notes.clone().unwrap_or_default()}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().notes = Some(event.value()),
+                  }
+                }
+                if can_manage {
+                  div { class: "form-group",
+                    button { onclick: save,
+                      if selected.is_some() { "Save Client" } else { "Add Client" }
+                    }
+                    if let Some(client) = selected.as_ref() {
+                      button {
+                        class: "cancel-button",
+                        onclick: {
+                            let client_id = client.id;
+                            move |_| {
+                                spawn(async move {
+                                    delete_or_restore(client_id, false).await;
+                                });
+                            }
+                        },
+                        "Delete client"
+                      }
+                    }
+                  }
+                }
+              }
+            }
+            if let Some(client) = selected.as_ref().filter(|c| c.deleted_at.is_none()) {
+              ClientAddresses {
+                client: client.clone(),
+                on_change: move |_| {
+                    spawn(async move {
+                        load_clients().await;
+                    });
+                },
+              }
+            }
+            if let Some(client) = selected.as_ref() {
+              h4 { "Orders ({client_orders.read().total})" }
+              div { class: "data-list",
+                for order in client_orders.read().items.iter() {
+                  div { class: "data-item",
+                    strong { "Order #{order.number()}" }
+                    span { "Amount: {order.total_amount}" }
+                    span { class: "status-badge status-{order.status}", "{order.status}" }
+                    small { "Created: {order.created_at}" }
+                  }
+                }
+              }
+              AuditPanel {
+                entity: AuditEntity::Client,
+                entity_id: client.id,
+                revision: format!("{}{:?}", client.updated_at, client.deleted_at),
+              }
+            }
+          }
+        }
+      }
+    }
+}
@@ -0,0 +1,190 @@
+use dioxus::prelude::*;
+use ws_models::{
+    AppError, ConnectionConfig, ConnectionSettings, ConnectionState, ConnectionStatus, FieldError,
+};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ConnectionScreenProps {
+    // バックエンドから受け取った接続状態（接続できたら App がメイン画面に切り替える）
+    pub status: Signal<Option<ConnectionStatus>>,
+}
+
+// データベースに接続できないときの画面（エラーの表示、接続設定の変更、再接続）
+#[component]
+pub fn ConnectionScreen(props: ConnectionScreenProps) -> Element {
+    let mut status = props.status;
+    let settings = status
+        .read()
+        .as_ref()
+        .map(|s| s.settings.clone())
+        .unwrap_or_default();
+
+    let mut host = use_signal(|| settings.host.clone());
+    let mut port = use_signal(|| settings.port.to_string());
+    let mut database = use_signal(|| settings.database.clone());
+    let mut username = use_signal(|| settings.username.clone());
+    // 空のままなら今のパスワードを使う
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    // 接続先を別のプロファイルに切り替えられるよう一覧を読み込む
+    let mut config = use_signal(|| Option::<ConnectionConfig>::None);
+
+    use_future(move || async move {
+        if let Ok(config_data) = api::get_connection_config().await {
+            config.set(Some(config_data));
+        }
+    });
+
+    // 別のプロファイルに切り替えて接続し直す
+    let select_profile = move |name: String| async move {
+        match api::select_connection_profile(&name).await {
+            Ok(new_status) => {
+                let settings = new_status.settings.clone();
+                host.set(settings.host);
+                port.set(settings.port.to_string());
+                database.set(settings.database);
+                username.set(settings.username);
+                password.set(String::new());
+                error.set(None);
+                status.set(Some(new_status));
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let retry = move |_| async move {
+        let Ok(port_number) = port.read().trim().parse::<u16>() else {
+            error.set(Some(AppError::Validation {
+                message: "port is not a number".to_string(),
+                fields: vec![FieldError::new(
+                    "connection.port",
+                    "must be a number between 1 and 65535",
+                )],
+            }));
+            return;
+        };
+        let password_text = password.read().clone();
+        let current = status.read().as_ref().map(|s| s.settings.clone()).unwrap_or_default();
+        let settings = ConnectionSettings {
+            host: host.read().trim().to_string(),
+            port: port_number,
+            database: database.read().trim().to_string(),
+            username: username.read().trim().to_string(),
+            password: (!password_text.is_empty()).then_some(password_text),
+            ..current
+        };
+        match api::connect_database(&settings).await {
+            Ok(new_status) => {
+                error.set(None);
+                password.set(String::new());
+                status.set(Some(new_status));
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let current = status.read().clone().unwrap_or_else(|| ConnectionStatus {
+        state: ConnectionState::Connecting,
+        profile: String::new(),
+        settings: ConnectionSettings::default(),
+        error: None,
+        attempts: 0,
+        retry_at: None,
+    });
+    let target = format!(
+        "{}:{}/{}",
+        current.settings.host, current.settings.port, current.settings.database
+    );
+
+    rsx! {
+      div { class: "connection-screen",
+        div { class: "section",
+          h2 { "Database connection" }
+          if current.state == ConnectionState::Disconnected {
+            p { class: "error-message",
+              "Cannot connect to {target}: {current.error.clone().unwrap_or_default()}"
+            }
+            if let Some(seconds) = retry_in_seconds(&current) {
+              p { class: "connection-retry",
+                "Retrying automatically in {seconds}s (attempt {current.attempts})"
+              }
+            }
+          } else {
+            p { class: "connection-retry", "Connecting to {target}..." }
+          }
+          if let Some(message) = general_message(&error.read()) {
+            p { class: "error-message", "{message}" }
+          }
+          if let Some(config_data) = config.read().as_ref().filter(|c| c.profiles.len() > 1) {
+            div { class: "field",
+              label { "Profile" }
+              select {
+                onchange: move |event| {
+                    spawn(select_profile(event.value()));
+                },
+                for profile in config_data.profiles.iter() {
+                  option {
+                    value: "{profile.name}",
+                    selected: profile.name == current.profile,
+                    "{profile.name}"
+                  }
+                }
+              }
+            }
+          }
+          div { class: "connection-form",
+            div { class: "field",
+              label { "Host" }
+              input { value: "{host}", oninput: move |event| host.set(event.value()) }
+              if let Some(message) = field_message(&error.read(), "connection.host") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Port" }
+              input { value: "{port}", oninput: move |event| port.set(event.value()) }
+              if let Some(message) = field_message(&error.read(), "connection.port") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Database" }
+              input { value: "{database}", oninput: move |event| database.set(event.value()) }
+              if let Some(message) = field_message(&error.read(), "connection.database") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "User" }
+              input { value: "{username}", oninput: move |event| username.set(event.value()) }
+              if let Some(message) = field_message(&error.read(), "connection.username") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Password" }
+              input {
+                r#type: "password",
+                placeholder: "(unchanged)",
+                value: "{password}",
+                oninput: move |event| password.set(event.value()),
+              }
+            }
+          }
+          div { class: "form-group",
+            button { onclick: retry, "Retry now" }
+          }
+        }
+      }
+    }
+}
+
+// 次の自動再接続までの秒数
+fn retry_in_seconds(status: &ConnectionStatus) -> Option<i64> {
+    let retry_at = status.retry_at?.timestamp_millis();
+    let now = js_sys::Date::now() as i64;
+    Some(((retry_at - now) / 1000).max(0))
+}
@@ -0,0 +1,118 @@
+use dioxus::prelude::*;
+use ws_models::{AppError, CreateUser};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+// ログイン画面（パスワードを持つユーザーがいなければ最初のユーザーの登録画面）
+// ログインすると api::SESSION が設定され、App がメイン画面に切り替える
+#[component]
+pub fn Login() -> Element {
+    // None は確認中
+    let mut setup_required = use_signal(|| Option::<bool>::None);
+    let mut name = use_signal(String::new);
+    let mut email = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut error = use_signal(|| Option::<AppError>::None);
+
+    use_future(move || async move {
+        match api::is_setup_required().await {
+            Ok(required) => setup_required.set(Some(required)),
+            Err(e) => {
+                setup_required.set(Some(false));
+                error.set(Some(e));
+            }
+        }
+    });
+
+    let login = move |_| async move {
+        let result = api::login(email.read().trim(), &password.read()).await;
+        password.set(String::new());
+        if let Err(e) = result {
+            error.set(Some(e));
+        }
+    };
+
+    let setup = move |_| async move {
+        let user_data = CreateUser {
+            name: name.read().clone(),
+            email: email.read().clone(),
+            password: Some(password.read().clone()),
+            // 最初のユーザーは常に admin になる
+            role: None,
+        };
+        if let Err(e) = api::setup_first_user(&user_data).await {
+            // 他の画面で先に登録された場合はログインしてもらう
+            if matches!(e, AppError::Conflict { .. }) {
+                setup_required.set(Some(false));
+            }
+            error.set(Some(e));
+        }
+    };
+
+    let Some(setup_required) = setup_required() else {
+        return rsx! {
+          div { class: "login-screen",
+            p { class: "connection-retry", "Loading..." }
+          }
+        };
+    };
+
+    rsx! {
+      div { class: "login-screen",
+        div { class: "section",
+          if setup_required {
+            h2 { "Create the first user" }
+            p { class: "connection-retry",
+              "No user can log in yet. The user created here becomes an admin."
+            }
+          } else {
+            h2 { "Log in" }
+          }
+          if let Some(message) = general_message(&error.read()) {
+            p { class: "error-message", "{message}" }
+          }
+          div { class: "login-form",
+            if setup_required {
+              div { class: "field",
+                label { "Name" }
+                input { value: "{name}", oninput: move |event| name.set(event.value()) }
+                if let Some(message) = field_message(&error.read(), "name") {
+                  span { class: "field-error", "{message}" }
+                }
+              }
+            }
+            div { class: "field",
+              label { "Email" }
+              input {
+                r#type: "email",
+                value: "{email}",
+                oninput: move |event| email.set(event.value()),
+              }
+              if let Some(message) = field_message(&error.read(), "email") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              label { "Password" }
+              input {
+                r#type: "password",
+                value: "{password}",
+                oninput: move |event| password.set(event.value()),
+              }
+              if let Some(message) = field_message(&error.read(), "password") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+          }
+          div { class: "form-group",
+            if setup_required {
+              button { onclick: setup, "Create user" }
+            } else {
+              button { onclick: login, "Log in" }
+            }
+          }
+        }
+      }
+    }
+}
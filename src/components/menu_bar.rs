@@ -1,21 +1,73 @@
 use dioxus::prelude::*;
+use ws_models::Permission;
 
-#[derive(Clone, PartialEq)]
+use crate::api;
+
+#[derive(Clone, Copy, PartialEq)]
 pub enum MenuItem {
     Dashboard,
     Clients,
     Orders,
     Products,
+    Users,
     Settings,
     Logout,
 }
 
+impl MenuItem {
+    const ALL: [MenuItem; 7] = [
+        MenuItem::Dashboard,
+        MenuItem::Clients,
+        MenuItem::Orders,
+        MenuItem::Products,
+        MenuItem::Users,
+        MenuItem::Settings,
+        MenuItem::Logout,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            MenuItem::Dashboard => "Dashboard",
+            MenuItem::Clients => "Clients",
+            MenuItem::Orders => "Orders",
+            MenuItem::Products => "Products",
+            MenuItem::Users => "Users",
+            MenuItem::Settings => "Settings",
+            MenuItem::Logout => "Logout",
+        }
+    }
+
+    // 画面を開くのに必要な権限（コマンドと同じ権限で判定する）
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            MenuItem::Dashboard | MenuItem::Logout => None,
+            MenuItem::Clients => Some(Permission::ViewClients),
+            MenuItem::Orders => Some(Permission::ViewOrders),
+            MenuItem::Products => Some(Permission::ViewProducts),
+            MenuItem::Users => Some(Permission::ViewUsers),
+            MenuItem::Settings => Some(Permission::ManageSettings),
+        }
+    }
+
+    // ログイン中のユーザーが開ける画面か
+    pub fn is_allowed(&self) -> bool {
+        match self.permission() {
+            Some(permission) => api::SESSION
+                .read()
+                .as_ref()
+                .is_some_and(|session| session.can(permission)),
+            None => true,
+        }
+    }
+}
+
 #[derive(Props, PartialEq, Clone)]
 pub struct MenuBarProps {
     pub selected_menu: Signal<MenuItem>,
     pub on_menu_change: EventHandler<MenuItem>,
 }
 
+// ロールの権限で開けない画面のメニューは表示しない
 #[component]
 pub fn MenuBar(props: MenuBarProps) -> Element {
     rsx! {
@@ -23,36 +75,13 @@ pub fn MenuBar(props: MenuBarProps) -> Element {
         h2 { "WS APP001" }
       }
       div { class: "menu-items",
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Dashboard { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Dashboard),
-          "Dashboard"
-        }
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Clients { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Clients),
-          "Clients"
-        }
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Orders { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Orders),
-          "Orders"
-        }
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Products { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Products),
-          "Products"
-        }
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Settings { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Settings),
-          "Settings"
-        }
-        button {
-          class: if *props.selected_menu.read() == MenuItem::Logout { "menu-item active" } else { "menu-item" },
-          onclick: move |_| props.on_menu_change.call(MenuItem::Logout),
-          "Logout"
+        for item in MenuItem::ALL.into_iter().filter(MenuItem::is_allowed) {
+          button {
+            class: if *props.selected_menu.read() == item { "menu-item active" } else { "menu-item" },
+            onclick: move |_| props.on_menu_change.call(item),
+            "{item.label()}"
+          }
         }
       }
     }
-}
\ No newline at end of file
+}
@@ -1,2 +1,19 @@
 pub mod menu_bar;
-pub mod database_test;
\ No newline at end of file
+pub mod audit_panel;
+pub mod client_addresses;
+pub mod clients;
+pub mod connection;
+pub mod login;
+pub mod order_form;
+pub mod orders;
+pub mod pager;
+pub mod product_categories;
+pub mod product_variants;
+pub mod products;
+pub mod reorder_suggestions;
+pub mod search_box;
+pub mod settings;
+pub mod stock_movements;
+pub mod users;
+pub mod warehouses;
+
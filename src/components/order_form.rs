@@ -0,0 +1,395 @@
+use dioxus::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use ws_models::{
+    Address, AppError, Client, ClientFilter, CreateOrder, CreateOrderItem, FieldError,
+    OrderWithItems, Page, Product, ProductFilter, ProductVariant, ProductVariants, Warehouse,
+};
+
+use crate::api;
+use crate::components::pager::page_query;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct OrderFormProps {
+    // 出荷する保管場所として選べる保管場所（注文画面で読み込んだもの）
+    pub warehouses: Vec<Warehouse>,
+    // 注文を作成したとき（注文の一覧を読み直す）
+    pub on_created: EventHandler<OrderWithItems>,
+}
+
+// 追加した明細（数量と値引き額は入力中の文字列のまま持ち、作成時に解析する）
+#[derive(Clone, PartialEq)]
+struct OrderLine {
+    product: Product,
+    variant: Option<ProductVariant>,
+    quantity: String,
+    discount: String,
+}
+
+impl OrderLine {
+    fn label(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{} ({})", self.product.name, variant.label()),
+            None => self.product.name.clone(),
+        }
+    }
+}
+
+// 明細の数量・値引き額を解析する（エラーはバックエンドと同じ items.{i}.* のフィールド名で返す）
+fn parse_lines(lines: &[OrderLine]) -> Result<Vec<CreateOrderItem>, AppError> {
+    let mut errors = Vec::new();
+    let mut items = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let quantity = line.quantity.trim().parse::<i32>().unwrap_or_else(|_| {
+            errors.push(FieldError::new(
+                format!("items.{}.quantity", i),
+                "must be a whole number",
+            ));
+            0
+        });
+        // 金額は浮動小数点を経由せず Decimal として解析する（空なら値引きなし）
+        let discount = match line.discount.trim() {
+            "" => None,
+            text => text.parse::<Decimal>().map(Some).unwrap_or_else(|_| {
+                errors.push(FieldError::new(
+                    format!("items.{}.discount", i),
+                    "must be a number",
+                ));
+                None
+            }),
+        };
+        items.push(CreateOrderItem {
+            product_id: line.product.id,
+            variant_id: line.variant.as_ref().map(|v| v.id),
+            quantity,
+            discount,
+        });
+    }
+    AppError::check_fields(errors)?;
+    Ok(items)
+}
+
+// 注文の作成フォーム（合計金額と在庫の引き当てはバックエンドが行う）
+#[component]
+pub fn OrderForm(props: OrderFormProps) -> Element {
+    // 顧客として選べる取引先（検索結果の1ページ目）
+    let mut clients = use_signal(Page::<Client>::default);
+    let mut client_search = use_signal(String::new);
+    // 空のままなら顧客を指定しない
+    let mut client_id = use_signal(|| Option::<Uuid>::None);
+    // 顧客の住所（空のままなら顧客の既定の請求先・配送先）
+    let mut addresses = use_signal(Vec::<Address>::new);
+    let mut billing_address_id = use_signal(|| Option::<Uuid>::None);
+    let mut shipping_address_id = use_signal(|| Option::<Uuid>::None);
+    // 空のままなら既定の保管場所から出荷する
+    let mut warehouse_id = use_signal(|| Option::<Uuid>::None);
+    // 明細の商品として選べる商品（取扱中の検索結果の1ページ目）
+    let mut products = use_signal(Page::<Product>::default);
+    let mut product_search = use_signal(String::new);
+    let mut product_id = use_signal(|| Option::<Uuid>::None);
+    // バリエーションのある商品ではバリエーションを選ぶ
+    let mut variants = use_signal(ProductVariants::default);
+    let mut variant_id = use_signal(|| Option::<Uuid>::None);
+    let mut lines = use_signal(Vec::<OrderLine>::new);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+
+    let load_clients = move || async move {
+        let text = client_search.read().trim().to_string();
+        let filter = ClientFilter {
+            search: (!text.is_empty()).then_some(text),
+            ..Default::default()
+        };
+        match api::get_clients(&page_query(1, filter)).await {
+            Ok(clients_data) => clients.set(clients_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let load_products = move || async move {
+        let text = product_search.read().trim().to_string();
+        let filter = ProductFilter {
+            search: (!text.is_empty()).then_some(text),
+            discontinued: Some(false),
+            ..Default::default()
+        };
+        match api::get_products(&page_query(1, filter)).await {
+            Ok(products_data) => products.set(products_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let mut select_client = move |id: Option<Uuid>| {
+        client_id.set(id);
+        addresses.set(Vec::new());
+        billing_address_id.set(None);
+        shipping_address_id.set(None);
+        if let Some(id) = id {
+            spawn(async move {
+                match api::get_client_addresses(id).await {
+                    Ok(data) => addresses.set(data),
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        }
+    };
+
+    let mut select_product = move |id: Option<Uuid>| {
+        product_id.set(id);
+        variant_id.set(None);
+        variants.set(ProductVariants::default());
+        if let Some(id) = id {
+            spawn(async move {
+                match api::get_product_variants(id).await {
+                    Ok(data) => variants.set(data),
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        }
+    };
+
+    // 選んだ商品（とバリエーション）を数量1の明細として追加する
+    let add_line = move |_| {
+        let Some(product) = products
+            .read()
+            .items
+            .iter()
+            .find(|p| Some(p.id) == product_id())
+            .cloned()
+        else {
+            return;
+        };
+        let variant = variants
+            .read()
+            .variants
+            .iter()
+            .find(|v| Some(v.id) == variant_id())
+            .cloned();
+        lines.write().push(OrderLine {
+            product,
+            variant,
+            quantity: "1".to_string(),
+            discount: String::new(),
+        });
+        // 明細の番号が変わるためフィールドエラーは消す
+        error.set(None);
+    };
+
+    // 出荷する保管場所の在庫不足は items.{i}.quantity のフィールドエラーとして返る
+    let create = move |_| async move {
+        message.set(None);
+        let items = match parse_lines(&lines.read()) {
+            Ok(items) => items,
+            Err(e) => {
+                error.set(Some(e));
+                return;
+            }
+        };
+        let order_data = CreateOrder {
+            client_id: client_id(),
+            billing_address_id: billing_address_id(),
+            shipping_address_id: shipping_address_id(),
+            warehouse_id: warehouse_id(),
+            items,
+        };
+        match api::create_order(&order_data).await {
+            Ok(order) => {
+                error.set(None);
+                message.set(Some(format!("Created order #{}", order.order.number())));
+                lines.set(Vec::new());
+                props.on_created.call(order);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    use_effect(move || {
+        spawn(async move {
+            load_clients().await;
+            load_products().await;
+        });
+    });
+
+    rsx! {
+      div { class: "order-form section",
+        h3 { "New order" }
+        if let Some(message) = message.read().as_ref() {
+          p { class: "success-message", "{message}" }
+        }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        // 顧客は選ばなくてもよい（住所は顧客の既定の請求先・配送先になる）
+        div { class: "form-group",
+          input {
+            placeholder: "Search clients",
+            value: "{client_search}",
+            oninput: move |event| client_search.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    spawn(async move {
+                        load_clients().await;
+                    });
+                }
+            },
+          }
+          div { class: "field",
+            select {
+              onchange: move |event| select_client(event.value().parse().ok()),
+              option { value: "", selected: client_id().is_none(), "No client" }
+              for client in clients.read().items.iter() {
+                option {
+                  value: "{client.id}",
+                  selected: client_id() == Some(client.id),
+                  "{client.company_name}"
+                }
+              }
+            }
+            if let Some(message) = field_message(&error.read(), "client_id") {
+              span { class: "field-error", "{message}" }
+            }
+          }
+        }
+        if !addresses.read().is_empty() {
+          div { class: "form-group",
+            div { class: "field",
+              select {
+                onchange: move |event| billing_address_id.set(event.value().parse().ok()),
+                option { value: "", selected: billing_address_id().is_none(), "Default billing address" }
+                for address in addresses.read().iter() {
+                  option {
+                    value: "{address.id}",
+                    selected: billing_address_id() == Some(address.id),
+                    "{address.label.clone().unwrap_or_default()} {address.address}"
+                  }
+                }
+              }
+              if let Some(message) = field_message(&error.read(), "billing_address_id") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              select {
+                onchange: move |event| shipping_address_id.set(event.value().parse().ok()),
+                option { value: "", selected: shipping_address_id().is_none(), "Default shipping address" }
+                for address in addresses.read().iter() {
+                  option {
+                    value: "{address.id}",
+                    selected: shipping_address_id() == Some(address.id),
+                    "{address.label.clone().unwrap_or_default()} {address.address}"
+                  }
+                }
+              }
+              if let Some(message) = field_message(&error.read(), "shipping_address_id") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+          }
+        }
+        if props.warehouses.len() > 1 {
+          div { class: "form-group",
+            div { class: "field",
+              select {
+                onchange: move |event| warehouse_id.set(event.value().parse().ok()),
+                option { value: "", selected: warehouse_id().is_none(), "Default warehouse" }
+                for warehouse in props.warehouses.iter() {
+                  option {
+                    value: "{warehouse.id}",
+                    selected: warehouse_id() == Some(warehouse.id),
+                    "{warehouse.name}"
+                  }
+                }
+              }
+              if let Some(message) = field_message(&error.read(), "warehouse_id") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+          }
+        }
+        // 明細に追加する商品
+        div { class: "form-group",
+          input {
+            placeholder: "Search products",
+            value: "{product_search}",
+            oninput: move |event| product_search.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    spawn(async move {
+                        load_products().await;
+                    });
+                }
+            },
+          }
+          select {
+            onchange: move |event| select_product(event.value().parse().ok()),
+            option { value: "", selected: product_id().is_none(), "Select product" }
+            for product in products.read().items.iter() {
+              option {
+                value: "{product.id}",
+                selected: product_id() == Some(product.id),
+                "{product.name} ({product.price})"
+              }
+            }
+          }
+          if !variants.read().variants.is_empty() {
+            select {
+              onchange: move |event| variant_id.set(event.value().parse().ok()),
+              option { value: "", selected: variant_id().is_none(), "Select variant" }
+              for variant in variants.read().variants.iter() {
+                option {
+                  value: "{variant.id}",
+                  selected: variant_id() == Some(variant.id),
+                  "{variant.label()} ({variant.sku}, stock {variant.stock})"
+                }
+              }
+            }
+          }
+          button { disabled: product_id().is_none(), onclick: add_line, "Add item" }
+        }
+        div { class: "data-list",
+          for (i, line) in lines.read().iter().enumerate() {
+            div { class: "data-item",
+              strong { "{line.label()}" }
+              div { class: "form-group",
+                div { class: "field",
+                  input {
+                    placeholder: "Quantity",
+                    value: "{line.quantity}",
+                    oninput: move |event| lines.write()[i].quantity = event.value(),
+                  }
+                  for field in ["quantity", "product_id", "variant_id"] {
+                    if let Some(message) = field_message(&error.read(), &format!("items.{}.{}", i, field)) {
+                      span { class: "field-error", "{message}" }
+                    }
+                  }
+                }
+                div { class: "field",
+                  input {
+                    placeholder: "Discount (optional)",
+                    value: "{line.discount}",
+                    oninput: move |event| lines.write()[i].discount = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), &format!("items.{}.discount", i)) {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                button {
+                  class: "cancel-button",
+                  onclick: move |_| {
+                      lines.write().remove(i);
+                      error.set(None);
+                  },
+                  "Remove"
+                }
+              }
+            }
+          }
+        }
+        if let Some(message) = field_message(&error.read(), "items") {
+          p { class: "field-error", "{message}" }
+        }
+        button { disabled: lines.read().is_empty(), onclick: create, "Create Order" }
+      }
+    }
+}
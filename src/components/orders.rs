@@ -0,0 +1,447 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    AppError, AuditEntity, Client, ClientFilter, OrderFilter, OrderStatus, OrderStatusHistory,
+    OrderWithItems, Page, Permission, SearchHit, SearchKind, UpdateOrder, Warehouse,
+};
+
+use crate::api;
+use crate::components::audit_panel::AuditPanel;
+use crate::components::order_form::OrderForm;
+use crate::components::pager::{focused_ids, page_query, Pager};
+use crate::components::warehouses::warehouse_name;
+
+#[derive(Props, PartialEq, Clone)]
+pub struct OrdersProps {
+    // 検索ボックスで選ばれたレコード
+    pub focus: Signal<Option<SearchHit>>,
+}
+
+#[component]
+pub fn Orders(props: OrdersProps) -> Element {
+    let mut focus = props.focus;
+    let mut orders = use_signal(Page::<OrderWithItems>::default);
+    // 表示中の注文の顧客（削除済みの取引先も含む）
+    let mut clients = use_signal(Vec::<Client>::new);
+    // 出荷する保管場所（保管場所での絞り込みと変更に使う）
+    let mut warehouses = use_signal(Vec::<Warehouse>::new);
+    let mut page = use_signal(|| 1u32);
+    // None のときは全ステータスを表示
+    let mut status_filter = use_signal(|| Option::<OrderStatus>::None);
+    // None のときはすべての保管場所の注文を表示
+    let mut warehouse_filter = use_signal(|| Option::<Uuid>::None);
+    // 削除済みの注文も一覧に表示する
+    let mut show_deleted = use_signal(|| false);
+    let mut selected_id = use_signal(|| Option::<Uuid>::None);
+    let mut history = use_signal(Vec::<OrderStatusHistory>::new);
+    let mut note = use_signal(String::new);
+    // 注文の作成フォームを表示する
+    let mut show_form = use_signal(|| false);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageOrders));
+
+    let load_orders = move || async move {
+        let filter = OrderFilter {
+            ids: focused_ids(&focus.read(), SearchKind::Order),
+            status: status_filter().map(|status| vec![status]),
+            warehouse_id: warehouse_filter(),
+            include_deleted: show_deleted(),
+            ..Default::default()
+        };
+        let orders_data = match api::get_orders_with_items(&page_query(page(), filter)).await {
+            Ok(orders_data) => orders_data,
+            Err(e) => {
+                error.set(Some(e));
+                return;
+            }
+        };
+        let client_ids: Vec<_> = orders_data.items.iter().filter_map(|o| o.order.client_id).collect();
+        orders.set(orders_data);
+        if client_ids.is_empty() {
+            clients.set(Vec::new());
+            return;
+        }
+        let filter = ClientFilter {
+            ids: Some(client_ids),
+            include_deleted: true,
+            ..Default::default()
+        };
+        match api::get_clients(&page_query(1, filter)).await {
+            Ok(clients_data) => clients.set(clients_data.items),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let load_history = move |order_id: Uuid| async move {
+        match api::get_order_status_history(order_id).await {
+            Ok(history_data) => history.set(history_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    // ステータスを変更し、一覧とタイムラインを更新する
+    let transition = move |order_id: Uuid, status: OrderStatus| async move {
+        let note_text = note.read().trim().to_string();
+        let note_text = (!note_text.is_empty()).then_some(note_text);
+        // 許可されていない遷移（他の画面で先に変更された場合など）は Conflict になる
+        if let Err(e) = api::transition_order(order_id, status, note_text).await {
+            error.set(Some(e));
+        } else {
+            error.set(None);
+            note.set(String::new());
+        }
+        load_orders().await;
+        load_history(order_id).await;
+    };
+
+    // 出荷前の注文の配送先を顧客の別の住所に変更する（注文にはその時点の内容が保存される）
+    let change_shipping = move |order_id: Uuid, address_id: Uuid| async move {
+        let order_data = UpdateOrder {
+            shipping_address_id: Some(address_id),
+            ..Default::default()
+        };
+        error.set(api::update_order(order_id, &order_data).await.err());
+        load_orders().await;
+    };
+
+    // 出荷前の注文の保管場所を変更する（引き当て済みの在庫は新しい保管場所から引き当て直す）
+    let change_warehouse = move |order_id: Uuid, warehouse_id: Uuid| async move {
+        let order_data = UpdateOrder {
+            warehouse_id: Some(warehouse_id),
+            ..Default::default()
+        };
+        error.set(api::update_order(order_id, &order_data).await.err());
+        load_orders().await;
+    };
+
+    // 削除は論理削除（Show deleted で表示して Restore で元に戻せる）
+    // 出荷前の注文は削除で在庫が戻り、復元で引き当て直す（在庫が足りなければ Conflict）
+    let delete_or_restore = move |order_id: Uuid, restore: bool| async move {
+        let result = if restore {
+            api::restore_order(order_id).await.map(|_| ())
+        } else {
+            api::delete_order(order_id).await
+        };
+        error.set(result.err());
+        load_orders().await;
+        load_history(order_id).await;
+    };
+
+    // 初回表示時と、検索結果から移動してきたときに読み込む
+    use_effect(move || {
+        let focused = focus
+            .read()
+            .as_ref()
+            .filter(|hit| hit.kind == SearchKind::Order)
+            .map(|hit| hit.id);
+        page.set(1);
+        spawn(async move {
+            match api::get_warehouses().await {
+                Ok(data) => warehouses.set(data),
+                Err(e) => error.set(Some(e)),
+            }
+            load_orders().await;
+            if let Some(order_id) = focused {
+                selected_id.set(Some(order_id));
+                load_history(order_id).await;
+            }
+        });
+    });
+
+    let selected = selected_id
+        .read()
+        .as_ref()
+        .and_then(|id| orders.read().items.iter().find(|o| &o.order.id == id).cloned());
+
+    // 選択中の注文の顧客の住所（配送先の変更に使う）
+    let selected_client_id = selected.as_ref().and_then(|o| o.order.client_id);
+    let client_addresses = use_resource(use_reactive!(|selected_client_id| async move {
+        match selected_client_id {
+            Some(client_id) => api::get_client_addresses(client_id).await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }));
+
+    rsx! {
+      div { class: "orders",
+        h2 { "Orders" }
+        if let Some(e) = error.read().as_ref() {
+          p { class: "error-message", "{e}" }
+        }
+        if let Some(hit) = focus.read().as_ref().filter(|hit| hit.kind == SearchKind::Order) {
+          div { class: "search-focus",
+            span { "Search result: Order {hit.title}" }
+            button { onclick: move |_| focus.set(None), "Show all" }
+          }
+        }
+        div { class: "form-group",
+          select {
+            onchange: move |event| {
+                status_filter.set(OrderStatus::ALL.iter().copied().find(|s| s.as_str() == event.value()));
+                page.set(1);
+                spawn(async move {
+                    load_orders().await;
+                });
+            },
+            option { value: "", "All statuses" }
+            for status in OrderStatus::ALL.iter() {
+              option { value: "{status.as_str()}", "{status}" }
+            }
+          }
+          select {
+            onchange: move |event| {
+                warehouse_filter.set(event.value().parse().ok());
+                page.set(1);
+                spawn(async move {
+                    load_orders().await;
+                });
+            },
+            option { value: "", "All warehouses" }
+            for warehouse in warehouses.read().iter() {
+              option { value: "{warehouse.id}", "{warehouse.name}" }
+            }
+          }
+          label { class: "show-deleted",
+            input {
+              r#type: "checkbox",
+              checked: show_deleted(),
+              onchange: move |event| {
+                  show_deleted.set(event.checked());
+                  page.set(1);
+                  spawn(async move {
+                      load_orders().await;
+                  });
+              },
+            }
+            "Show deleted"
+          }
+          if can_manage {
+            button {
+              onclick: move |_| show_form.set(!show_form()),
+              if show_form() { "Close" } else { "New Order" }
+            }
+          }
+        }
+        if show_form() {
+          OrderForm {
+            warehouses: warehouses.read().clone(),
+            on_created: move |created: OrderWithItems| {
+                let order_id = created.order.id;
+                selected_id.set(Some(order_id));
+                page.set(1);
+                spawn(async move {
+                    load_orders().await;
+                    load_history(order_id).await;
+                });
+            },
+          }
+        }
+        div { class: "orders-layout",
+          // 注文一覧
+          div { class: "data-list",
+            for OrderWithItems { order, items } in orders.read().items.iter() {
+              div {
+                class: if selected_id.read().as_ref() == Some(&order.id) { "data-item selected" } else { "data-item" },
+                onclick: {
+                    let order_id = order.id;
+                    move |_| {
+                        selected_id.set(Some(order_id));
+                        spawn(async move {
+                            load_history(order_id).await;
+                        });
+                    }
+                },
+                strong { "Order #{order.number()}" }
+                if let Some(name) = client_name(&clients.read(), order.client_id) {
+                  span { "{name}" }
+                }
+                span { "Amount: {order.total_amount} ({items.len()} items)" }
+                span { class: "status-badge status-{order.status}", "{order.status}" }
+                if order.deleted_at.is_some() {
+                  span { class: "status-badge status-cancelled", "Deleted" }
+                }
+              }
+            }
+            Pager {
+              page: orders.read().page,
+              total_pages: orders.read().total_pages,
+              total: orders.read().total,
+              on_page_change: move |p| {
+                  page.set(p);
+                  spawn(async move {
+                      load_orders().await;
+                  });
+              },
+            }
+          }
+          // 注文詳細とステータスのタイムライン
+          if let Some(OrderWithItems { order, items }) = selected {
+            div { class: "order-detail section",
+              h3 { "Order #{order.number()}" }
+              p {
+                "Client: "
+                {client_name(&clients.read(), order.client_id).unwrap_or_else(|| "-".to_string())}
+              }
+              p {
+                "Bill to: "
+                {order.billing_address.as_ref().map(|a| a.to_string()).unwrap_or_else(|| "-".to_string())}
+              }
+              p {
+                "Ship to: "
+                {order.shipping_address.as_ref().map(|a| a.to_string()).unwrap_or_else(|| "-".to_string())}
+              }
+              if order.deleted_at.is_none() && order.status.holds_stock() {
+                if let Some(addresses) = client_addresses.read().as_ref().filter(|a| !a.is_empty()) {
+                  div { class: "form-group",
+                    select {
+                      onchange: {
+                          let order_id = order.id;
+                          move |event: Event<FormData>| {
+                              if let Ok(address_id) = event.value().parse::<Uuid>() {
+                                  spawn(async move {
+                                      change_shipping(order_id, address_id).await;
+                                  });
+                              }
+                          }
+                      },
+                      option { value: "", "Change shipping address..." }
+                      for address in addresses.iter() {
+                        option { value: "{address.id}",
+                          "{address.label.clone().unwrap_or_default()} {address.address}"
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+              p { "Ships from: {warehouse_name(&warehouses.read(), order.warehouse_id)}" }
+              if order.deleted_at.is_none() && order.status.holds_stock() && warehouses.read().len() > 1 {
+                div { class: "form-group",
+                  select {
+                    onchange: {
+                        let order_id = order.id;
+                        move |event: Event<FormData>| {
+                            if let Ok(warehouse_id) = event.value().parse::<Uuid>() {
+                                spawn(async move {
+                                    change_warehouse(order_id, warehouse_id).await;
+                                });
+                            }
+                        }
+                    },
+                    option { value: "", "Ship from another warehouse..." }
+                    for warehouse in warehouses.read().iter().filter(|w| w.id != order.warehouse_id) {
+                      option { value: "{warehouse.id}", "{warehouse.name}" }
+                    }
+                  }
+                }
+              }
+              p { "Amount: {order.total_amount}" }
+              p {
+                "Status: "
+                span { class: "status-badge status-{order.status}", "{order.status}" }
+              }
+              div { class: "data-list",
+                for item in items.iter() {
+                  span { class: "order-item",
+                    "{&item.product_id.to_string()[..8]} × {item.quantity} @ {item.unit_price} = {item.line_total}"
+                  }
+                }
+              }
+              if order.deleted_at.is_some() {
+                div { class: "form-group",
+                  span { class: "status-badge status-cancelled", "Deleted" }
+                  button {
+                    onclick: {
+                        let order_id = order.id;
+                        move |_| {
+                            spawn(async move {
+                                delete_or_restore(order_id, true).await;
+                            });
+                        }
+                    },
+                    "Restore"
+                  }
+                }
+              } else if !order.status.next().is_empty() {
+                div { class: "form-group",
+                  input {
+                    placeholder: "Note (optional)",
+                    value: "{note}",
+                    oninput: move |event| note.set(event.value()),
+                  }
+                  for next in order.status.next().iter().copied() {
+                    button {
+                      onclick: {
+                          let order_id = order.id;
+                          move |_| {
+                              spawn(async move {
+                                  transition(order_id, next).await;
+                              });
+                          }
+                      },
+                      "Mark as {next}"
+                    }
+                  }
+                }
+              }
+              if order.deleted_at.is_none() {
+                button {
+                  class: "cancel-button",
+                  onclick: {
+                      let order_id = order.id;
+                      move |_| {
+                          spawn(async move {
+                              delete_or_restore(order_id, false).await;
+                          });
+                      }
+                  },
+                  "Delete order"
+                }
+              }
+              h4 { "Timeline" }
+              ol { class: "timeline",
+                for entry in history.read().iter() {
+                  li { class: "timeline-entry",
+                    strong {
+                      match entry.from_status {
+                          Some(from) => format!("{} → {}", from, entry.to_status),
+                          None => format!("created as {}", entry.to_status),
+                      }
+                    }
+                    small {
+                      "{entry.changed_at}"
+                      if let Some(name) = &entry.changed_by_name {
+                        " by {name}"
+                      }
+                    }
+                    if let Some(note) = &entry.note {
+                      span { "{note}" }
+                    }
+                  }
+                }
+              }
+              AuditPanel {
+                entity: AuditEntity::Order,
+                entity_id: order.id,
+                revision: order.updated_at.to_string(),
+              }
+            }
+          }
+        }
+      }
+    }
+}
+
+// 注文の顧客名（顧客が指定されていなければ None）
+fn client_name(clients: &[Client], client_id: Option<Uuid>) -> Option<String> {
+    let client_id = client_id?;
+    let name = clients
+        .iter()
+        .find(|c| c.id == client_id)
+        .map(|c| c.company_name.clone())
+        .unwrap_or_else(|| client_id.to_string()[..8].to_string());
+    Some(name)
+}
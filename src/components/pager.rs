@@ -0,0 +1,56 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{ListQuery, SearchHit, SearchKind};
+
+// 一覧1ページあたりの件数
+const PAGE_SIZE: u32 = 20;
+
+// 一覧の page ページ目を取得する条件（並び順はバックエンドの既定（新しい順））
+pub fn page_query<S, F>(page: u32, filter: F) -> ListQuery<S, F> {
+    ListQuery {
+        page: Some(page),
+        page_size: Some(PAGE_SIZE),
+        sort: None,
+        direction: None,
+        filter,
+    }
+}
+
+// focus が kind の検索結果ならそのレコードだけに絞り込む
+pub fn focused_ids(focus: &Option<SearchHit>, kind: SearchKind) -> Option<Vec<Uuid>> {
+    focus
+        .as_ref()
+        .filter(|hit| hit.kind == kind)
+        .map(|hit| vec![hit.id])
+}
+
+#[derive(Props, PartialEq, Clone)]
+pub struct PagerProps {
+    pub page: u32,
+    pub total_pages: u32,
+    pub total: i64,
+    pub on_page_change: EventHandler<u32>,
+}
+
+// 一覧のページ送り（page は 1 始まり）
+#[component]
+pub fn Pager(props: PagerProps) -> Element {
+    let page = props.page;
+    let total_pages = props.total_pages.max(1);
+
+    rsx! {
+      div { class: "pager",
+        button {
+          disabled: page <= 1,
+          onclick: move |_| props.on_page_change.call(page - 1),
+          "‹ Prev"
+        }
+        span { "Page {page} / {total_pages} ({props.total} total)" }
+        button {
+          disabled: page >= total_pages,
+          onclick: move |_| props.on_page_change.call(page + 1),
+          "Next ›"
+        }
+      }
+    }
+}
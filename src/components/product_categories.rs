@@ -0,0 +1,161 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    AppError, CreateProductCategory, Permission, ProductCategory, UpdateProductCategory,
+};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ProductCategoriesProps {
+    // 階層順のカテゴリ（商品画面で読み込んだもの）
+    pub categories: Vec<ProductCategory>,
+    // カテゴリを追加・変更・削除したとき（カテゴリを読み直す）
+    pub on_change: EventHandler<()>,
+}
+
+// 編集フォームの初期値
+fn form_from(category: &ProductCategory) -> CreateProductCategory {
+    CreateProductCategory {
+        parent_id: category.parent_id,
+        name: category.name.clone(),
+    }
+}
+
+// 階層の深さに応じて字下げしたカテゴリ名（select の選択肢用）
+pub fn indented_name(category: &ProductCategory) -> String {
+    format!("{}{}", "\u{00a0}\u{00a0}".repeat(category.depth as usize), category.name)
+}
+
+// 商品カテゴリの一覧と追加・編集フォーム
+// 下位のカテゴリや商品が残っているカテゴリは削除できない（バックエンドが Conflict を返す）
+#[component]
+pub fn ProductCategories(props: ProductCategoriesProps) -> Element {
+    // 編集中のカテゴリ（None なら新規作成）
+    let mut editing = use_signal(|| Option::<Uuid>::None);
+    let mut form = use_signal(CreateProductCategory::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageProducts));
+
+    let save = move |_| async move {
+        let data = form.read().clone();
+        let result = match editing() {
+            Some(id) => {
+                let update = UpdateProductCategory {
+                    parent_id: Some(data.parent_id),
+                    name: Some(data.name),
+                };
+                api::update_product_category(id, &update).await
+            }
+            None => api::create_product_category(&data).await,
+        };
+        if let Err(e) = result {
+            error.set(Some(e));
+            return;
+        }
+        error.set(None);
+        editing.set(None);
+        form.set(CreateProductCategory::default());
+        props.on_change.call(());
+    };
+
+    let delete = move |id: Uuid| async move {
+        error.set(api::delete_product_category(id).await.err());
+        if editing() == Some(id) {
+            editing.set(None);
+            form.set(CreateProductCategory::default());
+        }
+        props.on_change.call(());
+    };
+
+    rsx! {
+      div { class: "product-categories",
+        h4 { "Categories" }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        div { class: "data-list",
+          for category in props.categories.iter() {
+            div {
+              class: if editing() == Some(category.id) { "data-item selected" } else { "data-item" },
+              style: "padding-left: {category.depth + 1}em",
+              strong { "{category.name}" }
+              if can_manage {
+                div { class: "item-actions",
+                  button {
+                    onclick: {
+                        let category = category.clone();
+                        move |_| {
+                            editing.set(Some(category.id));
+                            form.set(form_from(&category));
+                            error.set(None);
+                        }
+                    },
+                    "Edit"
+                  }
+                  button {
+                    onclick: {
+                        let id = category.id;
+                        move |_| {
+                            spawn(async move {
+                                delete(id).await;
+                            });
+                        }
+                    },
+                    "Delete"
+                  }
+                }
+              }
+            }
+          }
+        }
+        if can_manage {
+          div { class: "form-group",
+            div { class: "field",
+              input {
+                placeholder: "Category name",
+                value: "{form.read().name}",
+                oninput: move |event| form.write().name = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "name") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              select {
+                onchange: move |event| form.write().parent_id = event.value().parse().ok(),
+                option { value: "", selected: form.read().parent_id.is_none(), "Top level" }
+                for category in props.categories.iter().filter(|c| editing() != Some(c.id)) {
+                  option {
+                    value: "{category.id}",
+                    selected: form.read().parent_id == Some(category.id),
+                    "{indented_name(category)}"
+                  }
+                }
+              }
+              if let Some(message) = field_message(&error.read(), "parent_id") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            button { onclick: save,
+              if editing().is_some() { "Save Category" } else { "Add Category" }
+            }
+            if editing().is_some() {
+              button {
+                onclick: move |_| {
+                    editing.set(None);
+                    form.set(CreateProductCategory::default());
+                    error.set(None);
+                },
+                "Cancel"
+              }
+            }
+          }
+        }
+      }
+    }
+}
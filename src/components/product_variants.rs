@@ -0,0 +1,444 @@
+use dioxus::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+use ws_models::{
+    AdjustStockBatch, AdjustStockItem, AppError, FieldError, Permission, Product, ProductVariant,
+    ProductVariants, SetProductOption, UpdateProductVariant, Warehouse, MAX_PRODUCT_OPTIONS,
+};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ProductVariantsGridProps {
+    pub product: Product,
+    // 商品画面で読み込んだオプションとバリエーション
+    pub variants: Signal<ProductVariants>,
+    // 保管場所（既定の保管場所が先頭）
+    pub warehouses: Vec<Warehouse>,
+    // オプション・バリエーションを保存したとき（商品と一覧を読み直す）
+    pub on_change: EventHandler<()>,
+}
+
+// オプションの編集フォーム（値はカンマ区切りで入力する）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct OptionForm {
+    name: String,
+    values: String,
+}
+
+// 表の1マスの入力値（価格が空なら商品の価格）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct VariantCell {
+    sku: String,
+    price: String,
+    stock: String,
+}
+
+// 表で変更した在庫数を記録する調整の保管場所と理由（None なら既定の保管場所）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StockForm {
+    warehouse_id: Option<Uuid>,
+    reason: String,
+}
+
+fn option_forms(data: &ProductVariants) -> Vec<OptionForm> {
+    data.options
+        .iter()
+        .map(|option| OptionForm {
+            name: option.name.clone(),
+            values: option.values.join(", "),
+        })
+        .collect()
+}
+
+fn cells(data: &ProductVariants) -> HashMap<Uuid, VariantCell> {
+    data.variants
+        .iter()
+        .map(|variant| {
+            let cell = VariantCell {
+                sku: variant.sku.clone(),
+                price: variant.price.map(|p| p.amount.to_string()).unwrap_or_default(),
+                stock: variant.stock.to_string(),
+            };
+            (variant.id, cell)
+        })
+        .collect()
+}
+
+// 表を保存するときに送る内容
+struct GridChanges {
+    updates: Vec<UpdateProductVariant>,
+    // 在庫数を変更したマスの調整（変更がなければ None）
+    stock: Option<AdjustStockBatch>,
+    // 調整の k 番目の明細のマスの並び順（エラーを表示するマス）
+    positions: Vec<usize>,
+}
+
+// 全角の読点・カンマも区切りとして扱う
+fn split_values(values: &str) -> Vec<String> {
+    values
+        .split([',', '、', '，'])
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+// 表の入力値を解析する（SKU の検証や一意性の確認はバックエンドで行う）
+// フィールド名はバックエンドと同じ variants.{i}.price（i はバリエーションの並び順）
+// 在庫数を変更したマスは、読み込んだときの在庫数との差を調整の明細にする
+fn parse_cells(
+    product_id: Uuid,
+    variants: &[ProductVariant],
+    cells: &HashMap<Uuid, VariantCell>,
+    stock_form: &StockForm,
+) -> Result<GridChanges, AppError> {
+    let mut errors = Vec::new();
+    let mut updates = Vec::with_capacity(variants.len());
+    let mut items = Vec::new();
+    let mut positions = Vec::new();
+    for (i, variant) in variants.iter().enumerate() {
+        let Some(cell) = cells.get(&variant.id) else {
+            continue;
+        };
+        let price = match cell.price.trim() {
+            "" => None,
+            text => text.parse::<Decimal>().map(Some).unwrap_or_else(|_| {
+                errors.push(FieldError::new(format!("variants.{}.price", i), "must be a number"));
+                None
+            }),
+        };
+        match cell.stock.trim().parse::<i32>() {
+            Ok(stock) if stock < 0 => {
+                errors.push(FieldError::new(
+                    format!("variants.{}.stock", i),
+                    "must not be negative",
+                ));
+            }
+            Ok(stock) if stock != variant.stock => {
+                items.push(AdjustStockItem {
+                    product_id,
+                    variant_id: Some(variant.id),
+                    quantity: stock - variant.stock,
+                });
+                positions.push(i);
+            }
+            Ok(_) => {}
+            Err(_) => {
+                errors.push(FieldError::new(
+                    format!("variants.{}.stock", i),
+                    "must be a whole number",
+                ));
+            }
+        }
+        updates.push(UpdateProductVariant {
+            id: variant.id,
+            sku: Some(cell.sku.clone()),
+            price: Some(price),
+        });
+    }
+    let reason = stock_form.reason.trim();
+    if !items.is_empty() && reason.is_empty() {
+        errors.push(FieldError::new("reason", "is required to change stock"));
+    }
+    AppError::check_fields(errors)?;
+    let stock = (!items.is_empty()).then(|| AdjustStockBatch {
+        warehouse_id: stock_form.warehouse_id,
+        reason: Some(reason.to_string()),
+        reference: None,
+        items,
+    });
+    Ok(GridChanges {
+        updates,
+        stock,
+        positions,
+    })
+}
+
+// 在庫の調整のエラー（items.{k}.quantity など）を表のマスのエラー（variants.{i}.stock）にする
+fn stock_errors(mut error: AppError, positions: &[usize]) -> AppError {
+    if let AppError::Validation { fields, .. } | AppError::Conflict { fields, .. } = &mut error {
+        for field in fields.iter_mut() {
+            let position = field
+                .field
+                .strip_prefix("items.")
+                .and_then(|rest| rest.split('.').next())
+                .and_then(|k| k.parse::<usize>().ok())
+                .and_then(|k| positions.get(k));
+            if let Some(i) = position {
+                field.field = format!("variants.{}.stock", i);
+            }
+        }
+    }
+    error
+}
+
+// 商品のオプション（サイズ・カラーなど）と、値の組み合わせごとの SKU・価格・在庫の表
+// 1つ目のオプションを行、2つ目のオプションを列にして、表の値をまとめて保存する
+// 変更した在庫数は、選んだ保管場所での調整（共通の理由を付ける）として入出庫の記録に残す
+#[component]
+pub fn ProductVariantsGrid(props: ProductVariantsGridProps) -> Element {
+    let product = props.product.clone();
+    let product_id = product.id;
+    let mut variants = props.variants;
+    let mut options = use_signal(Vec::<OptionForm>::new);
+    let mut grid = use_signal(HashMap::<Uuid, VariantCell>::new);
+    let mut stock_form = use_signal(StockForm::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageProducts));
+
+    // 読み込んだ内容でフォームと表を作り直す
+    use_effect(move || {
+        let data = variants.read();
+        options.set(option_forms(&data));
+        grid.set(cells(&data));
+    });
+
+    let save_options = move |_| async move {
+        let data: Vec<SetProductOption> = options
+            .read()
+            .iter()
+            .map(|option| SetProductOption {
+                name: option.name.clone(),
+                values: split_values(&option.values),
+            })
+            .collect();
+        match api::set_product_options(product_id, &data).await {
+            Ok(result) => {
+                error.set(None);
+                message.set(Some(format!("{} variants", result.variants.len())));
+                variants.set(result);
+                props.on_change.call(());
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // SKU・価格を保存してから、在庫数の変更を調整として記録する
+    let save_grid = move |_| async move {
+        let parsed = parse_cells(
+            product_id,
+            &variants.read().variants,
+            &grid.read(),
+            &stock_form.read(),
+        );
+        let changes = match parsed {
+            Ok(changes) => changes,
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+                return;
+            }
+        };
+        let result = match api::update_product_variants(product_id, &changes.updates).await {
+            Ok(result) => result,
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+                return;
+            }
+        };
+        let Some(batch) = changes.stock else {
+            error.set(None);
+            message.set(Some("Saved variants".to_string()));
+            variants.set(result);
+            props.on_change.call(());
+            return;
+        };
+        // 在庫を記録できなければ表の入力をそのまま残す（SKU・価格は保存済み）
+        match api::adjust_stock_batch(&batch).await {
+            Ok(movements) => {
+                error.set(None);
+                message.set(Some(format!(
+                    "Saved variants and adjusted the stock of {}",
+                    movements.len()
+                )));
+                stock_form.write().reason.clear();
+                props.on_change.call(());
+            }
+            Err(e) => {
+                message.set(Some("Saved SKUs and prices".to_string()));
+                error.set(Some(stock_errors(e, &changes.positions)));
+            }
+        }
+    };
+
+    let data = variants.read().clone();
+    let rows: Vec<String> = data.options.first().map(|o| o.values.clone()).unwrap_or_default();
+    // 2つ目のオプションがなければ1列だけの表にする
+    let columns: Vec<Option<String>> = match data.options.get(1) {
+        Some(option) => option.values.iter().cloned().map(Some).collect(),
+        None => vec![None],
+    };
+    let position = |variant: &ProductVariant| data.variants.iter().position(|v| v.id == variant.id);
+    let warehouses = props.warehouses.clone();
+    // 保管場所の選択肢で選択中か（未選択なら既定の保管場所）
+    let is_selected = |selected: Option<Uuid>, warehouse: &Warehouse| match selected {
+        Some(id) => id == warehouse.id,
+        None => warehouse.is_default,
+    };
+
+    rsx! {
+      div { class: "product-variants",
+        h4 { "Variants" }
+        if let Some(message) = message.read().as_ref() {
+          p { class: "success-message", "{message}" }
+        }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        // オプション全体のエラー（options.{i}.name などは各入力欄に表示する）
+        for e in error.read().iter().flat_map(|e| e.fields()).filter(|f| f.field == "options") {
+          p { class: "error-message", "{e.message}" }
+        }
+        // オプションの定義
+        if can_manage {
+          for (i, option) in options.read().iter().enumerate() {
+            div { class: "form-group",
+              div { class: "field",
+                input {
+                  placeholder: "Option (Size, Color...)",
+                  value: "{option.name}",
+                  oninput: move |event| options.write()[i].name = event.value(),
+                }
+                if let Some(message) = field_message(&error.read(), &format!("options.{}.name", i)) {
+                  span { class: "field-error", "{message}" }
+                }
+              }
+              div { class: "field",
+                input {
+                  placeholder: "Values (S, M, L)",
+                  value: "{option.values}",
+                  oninput: move |event| options.write()[i].values = event.value(),
+                }
+                if let Some(message) = field_message(&error.read(), &format!("options.{}.values", i)) {
+                  span { class: "field-error", "{message}" }
+                }
+              }
+              button {
+                onclick: move |_| {
+                    options.write().remove(i);
+                },
+                "Remove"
+              }
+            }
+          }
+          div { class: "form-group",
+            if options.read().len() < MAX_PRODUCT_OPTIONS {
+              button {
+                onclick: move |_| options.write().push(OptionForm::default()),
+                "Add Option"
+              }
+            }
+            button { onclick: save_options, "Save Options" }
+          }
+        } else {
+          for option in data.options.iter() {
+            span { class: "role-badge", "{option.name}: {option.values.join(\", \")}" }
+          }
+        }
+        // バリエーションの表（価格が空のマスは商品の価格で販売する）
+        if !data.variants.is_empty() {
+          table { class: "variant-matrix",
+            thead {
+              tr {
+                th { "{data.options[0].name}" }
+                for column in columns.iter() {
+                  th { "{column.clone().unwrap_or_default()}" }
+                }
+              }
+            }
+            tbody {
+              for row in rows.iter() {
+                tr {
+                  th { "{row}" }
+                  for column in columns.iter() {
+                    td {
+                      if let Some((i, variant)) = data
+                          .find(&match column {
+                              Some(column) => vec![row.as_str(), column.as_str()],
+                              None => vec![row.as_str()],
+                          })
+                          .and_then(|variant| position(variant).map(|i| (i, variant.clone())))
+                      {
+                        div { class: "variant-cell",
+                          input {
+                            title: "SKU",
+                            disabled: !can_manage,
+                            value: "{grid.read().get(&variant.id).map(|c| c.sku.clone()).unwrap_or_default()}",
+                            oninput: move |event| {
+                                grid.write().entry(variant.id).or_default().sku = event.value();
+                            },
+                          }
+                          input {
+                            title: "Price",
+                            placeholder: "{product.price.amount}",
+                            disabled: !can_manage,
+                            value: "{grid.read().get(&variant.id).map(|c| c.price.clone()).unwrap_or_default()}",
+                            oninput: move |event| {
+                                grid.write().entry(variant.id).or_default().price = event.value();
+                            },
+                          }
+                          input {
+                            title: "Stock",
+                            disabled: !can_manage,
+                            value: "{grid.read().get(&variant.id).map(|c| c.stock.clone()).unwrap_or_default()}",
+                            oninput: move |event| {
+                                grid.write().entry(variant.id).or_default().stock = event.value();
+                            },
+                          }
+                          for field in ["sku", "price", "stock"] {
+                            if let Some(message) = field_message(&error.read(), &format!("variants.{}.{}", i, field)) {
+                              span { class: "field-error", "{message}" }
+                            }
+                          }
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+            }
+          }
+          if can_manage {
+            // 在庫数を変更したときの調整の保管場所と理由
+            div { class: "form-group",
+              if warehouses.len() > 1 {
+                select {
+                  onchange: move |event| stock_form.write().warehouse_id = event.value().parse().ok(),
+                  for warehouse in warehouses.iter() {
+                    option {
+                      value: "{warehouse.id}",
+                      selected: is_selected(stock_form.read().warehouse_id, warehouse),
+                      "{warehouse.name}"
+                    }
+                  }
+                }
+              }
+              div { class: "field",
+                input {
+                  placeholder: "Reason for stock changes",
+                  value: "{stock_form.read().reason}",
+                  oninput: move |event| stock_form.write().reason = event.value(),
+                }
+                for field in ["reason", "warehouse_id"] {
+                  if let Some(message) = field_message(&error.read(), field) {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+              }
+              button { onclick: save_grid, "Save Variants" }
+            }
+          }
+        }
+      }
+    }
+}
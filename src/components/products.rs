@@ -0,0 +1,719 @@
+use dioxus::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use ws_models::{
+    AppError, AuditEntity, CreateProduct, CurrencyCode, FieldError, Money, Page, Permission,
+    Product, ProductCategory, ProductFilter, ProductVariants, SearchHit, SearchKind,
+    StockDiscrepancy, UnitOfMeasure, UpdateProduct, Warehouse,
+};
+
+use crate::api;
+use crate::components::audit_panel::AuditPanel;
+use crate::components::pager::{focused_ids, page_query, Pager};
+use crate::components::product_categories::{indented_name, ProductCategories};
+use crate::components::product_variants::ProductVariantsGrid;
+use crate::components::reorder_suggestions::ReorderSuggestions;
+use crate::components::stock_movements::StockPanel;
+use crate::components::warehouses::Warehouses;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ProductsProps {
+    // 検索ボックスで選ばれたレコード
+    pub focus: Signal<Option<SearchHit>>,
+}
+
+// 編集フォームの値（数値は入力中の文字列のまま保持する）
+#[derive(Debug, Clone, Default)]
+struct ProductForm {
+    name: String,
+    sku: String,
+    barcode: String,
+    description: String,
+    unit: UnitOfMeasure,
+    category_id: Option<Uuid>,
+    price: String,
+    stock: String,
+    reorder_point: String,
+    discontinued: bool,
+}
+
+// 編集フォームの初期値
+fn form_from(product: &Product) -> ProductForm {
+    ProductForm {
+        name: product.name.clone(),
+        sku: product.sku.clone(),
+        barcode: product.barcode.clone().unwrap_or_default(),
+        description: product.description.clone().unwrap_or_default(),
+        unit: product.unit,
+        category_id: product.category_id,
+        price: product.price.amount.to_string(),
+        stock: product.stock.to_string(),
+        reorder_point: product.reorder_point.map(|p| p.to_string()).unwrap_or_default(),
+        discontinued: product.discontinued,
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// 数値の入力欄を解析する（文字列の検証や一意性の確認はバックエンドで行う）
+// 金額は浮動小数点を経由せず Decimal として解析する
+fn parse_form(form: &ProductForm) -> Result<CreateProduct, AppError> {
+    let mut errors = Vec::new();
+    let price = form.price.trim().parse::<Decimal>().unwrap_or_else(|_| {
+        errors.push(FieldError::new("price", "must be a number"));
+        Decimal::ZERO
+    });
+    let stock = form.stock.trim().parse::<i32>().unwrap_or_else(|_| {
+        errors.push(FieldError::new("stock", "must be a whole number"));
+        0
+    });
+    let reorder_point = match form.reorder_point.trim() {
+        "" => None,
+        text => text.parse::<i32>().map(Some).unwrap_or_else(|_| {
+            errors.push(FieldError::new("reorder_point", "must be a whole number"));
+            None
+        }),
+    };
+    AppError::check_fields(errors)?;
+    Ok(CreateProduct {
+        name: form.name.clone(),
+        sku: form.sku.clone(),
+        barcode: non_empty(&form.barcode),
+        description: non_empty(&form.description),
+        unit: form.unit,
+        category_id: form.category_id,
+        price: Money::new(price, CurrencyCode::JPY),
+        stock,
+        reorder_point,
+    })
+}
+
+// フォームの値をすべて更新する（空のバーコード・説明は削除する）
+// 在庫数は入出庫の記録（StockPanel）で変更する
+fn update_from(data: CreateProduct, discontinued: bool) -> UpdateProduct {
+    UpdateProduct {
+        name: Some(data.name),
+        sku: Some(data.sku),
+        barcode: Some(data.barcode),
+        description: Some(data.description),
+        unit: Some(data.unit),
+        category_id: Some(data.category_id),
+        price: Some(data.price),
+        reorder_point: Some(data.reorder_point),
+        discontinued: Some(discontinued),
+    }
+}
+
+// 一覧に表示するカテゴリ名（上位のカテゴリを含む）
+fn category_path(categories: &[ProductCategory], id: Option<Uuid>) -> Option<String> {
+    let id = id?;
+    categories.iter().find(|c| c.id == id).map(|c| c.path.clone())
+}
+
+// 商品の一覧と詳細（編集フォーム・カテゴリ・変更履歴）
+#[component]
+pub fn Products(props: ProductsProps) -> Element {
+    let mut focus = props.focus;
+    let mut products = use_signal(Page::<Product>::default);
+    let mut categories = use_signal(Vec::<ProductCategory>::new);
+    let mut warehouses = use_signal(Vec::<Warehouse>::new);
+    let mut page = use_signal(|| 1u32);
+    let mut search = use_signal(String::new);
+    // カテゴリで絞り込む（下位のカテゴリの商品も含む）
+    let mut category_filter = use_signal(|| Option::<Uuid>::None);
+    // 在庫が発注点以下の商品だけを表示する
+    let mut below_reorder_point = use_signal(|| false);
+    // 削除済みの商品も一覧に表示する
+    let mut show_deleted = use_signal(|| false);
+    // バーコードリーダーの入力（Enter で商品を開く）
+    let mut barcode = use_signal(String::new);
+    // 詳細を表示中の商品（None で新規作成フォーム）
+    // バーコードで開いた商品は一覧の表示中のページにないことがあるため、商品ごと保持する
+    let mut selected = use_signal(|| Option::<Product>::None);
+    let mut form = use_signal(ProductForm::default);
+    // 選択中の商品のオプションとバリエーション
+    let mut variants = use_signal(ProductVariants::default);
+    // 在庫数と入出庫の記録の突き合わせの結果（None なら未確認）
+    let mut discrepancies = use_signal(|| Option::<Vec<StockDiscrepancy>>::None);
+    // 発注の提案を表示する
+    let mut show_reorder = use_signal(|| false);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageProducts));
+
+    let load_products = move || async move {
+        let text = search.read().trim().to_string();
+        let filter = ProductFilter {
+            ids: focused_ids(&focus.read(), SearchKind::Product),
+            search: (!text.is_empty()).then_some(text),
+            category_id: category_filter(),
+            below_reorder_point: below_reorder_point(),
+            include_deleted: show_deleted(),
+            ..Default::default()
+        };
+        match api::get_products(&page_query(page(), filter)).await {
+            Ok(products_data) => products.set(products_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let load_categories = move || async move {
+        match api::get_product_categories().await {
+            Ok(data) => categories.set(data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let load_warehouses = move || async move {
+        match api::get_warehouses().await {
+            Ok(data) => warehouses.set(data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let load_variants = move |product_id: Uuid| async move {
+        match api::get_product_variants(product_id).await {
+            Ok(data) => variants.set(data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let mut select = move |product: Option<Product>| {
+        error.set(None);
+        message.set(None);
+        variants.set(ProductVariants::default());
+        form.set(product.as_ref().map(form_from).unwrap_or_default());
+        if let Some(product) = product.as_ref().filter(|p| p.deleted_at.is_none()) {
+            let product_id = product.id;
+            spawn(async move {
+                load_variants(product_id).await;
+            });
+        }
+        selected.set(product);
+    };
+
+    // 入出庫を記録すると商品とバリエーションの在庫が変わる
+    let reload_selected = move || async move {
+        let Some(product_id) = selected.read().as_ref().map(|p| p.id) else {
+            return;
+        };
+        match api::get_product_by_id(product_id).await {
+            Ok(product) => {
+                form.write().stock = product.stock.to_string();
+                selected.set(Some(product));
+            }
+            Err(e) => error.set(Some(e)),
+        }
+        load_variants(product_id).await;
+        load_products().await;
+    };
+
+    let check_stock = move |_| async move {
+        match api::get_stock_discrepancies().await {
+            Ok(data) => discrepancies.set(Some(data)),
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 新しい商品を作成（選択中なら更新）
+    // SKU・バーコードの重複や形式の誤りは入力欄ごとに表示する
+    let save = move |_| async move {
+        let parsed = parse_form(&form.read());
+        let data = match parsed {
+            Ok(data) => data,
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+                return;
+            }
+        };
+        let discontinued = form.read().discontinued;
+        let selected_id = selected.read().as_ref().map(|p| p.id);
+        let result = match selected_id {
+            Some(id) => api::update_product(id, &update_from(data, discontinued)).await,
+            None => api::create_product(&data).await,
+        };
+        match result {
+            Ok(product) => {
+                error.set(None);
+                message.set(Some(format!("Saved {}", product.name)));
+                form.set(form_from(&product));
+                selected.set(Some(product));
+                load_products().await;
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 削除は論理削除（Show deleted で表示して Restore で元に戻せる）
+    let delete_or_restore = move |product_id: Uuid, restore: bool| async move {
+        let result = if restore {
+            api::restore_product(product_id).await.map(Some)
+        } else {
+            api::delete_product(product_id).await.map(|_| None)
+        };
+        match result {
+            Ok(product) => {
+                error.set(None);
+                select(product);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+        load_products().await;
+    };
+
+    // バーコードで商品を開く（見つからなければ NotFound を表示する）
+    let lookup_barcode = move || async move {
+        let code = barcode.read().trim().to_string();
+        if code.is_empty() {
+            return;
+        }
+        match api::get_product_by_barcode(&code).await {
+            Ok(product) => {
+                barcode.set(String::new());
+                select(Some(product));
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 発注の提案から商品を開く（表示中のページにないことがあるため取得し直す）
+    let open_product = move |id: Uuid| async move {
+        match api::get_product_by_id(id).await {
+            Ok(product) => select(Some(product)),
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 初回表示時と、検索結果から移動してきたときに読み込む
+    use_effect(move || {
+        let focused = focus
+            .read()
+            .as_ref()
+            .filter(|hit| hit.kind == SearchKind::Product)
+            .map(|hit| hit.id);
+        page.set(1);
+        spawn(async move {
+            load_categories().await;
+            load_warehouses().await;
+            load_products().await;
+            if let Some(product_id) = focused {
+                let product = products.read().items.iter().find(|p| p.id == product_id).cloned();
+                select(product);
+            }
+        });
+    });
+
+    let selected_product = selected.read().clone();
+
+    rsx! {
+      div { class: "products",
+        h2 { "Products" }
+        if let Some(hit) = focus.read().as_ref().filter(|hit| hit.kind == SearchKind::Product) {
+          div { class: "search-focus",
+            span { "Search result: Product {hit.title}" }
+            button { onclick: move |_| focus.set(None), "Show all" }
+          }
+        }
+        div { class: "form-group",
+          input {
+            placeholder: "Search name, SKU or barcode",
+            value: "{search}",
+            oninput: move |event| search.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    page.set(1);
+                    spawn(async move {
+                        load_products().await;
+                    });
+                }
+            },
+          }
+          select {
+            onchange: move |event| {
+                category_filter.set(event.value().parse().ok());
+                page.set(1);
+                spawn(async move {
+                    load_products().await;
+                });
+            },
+            option { value: "", selected: category_filter().is_none(), "All categories" }
+            for category in categories.read().iter() {
+              option {
+                value: "{category.id}",
+                selected: category_filter() == Some(category.id),
+                "{indented_name(category)}"
+              }
+            }
+          }
+          input {
+            placeholder: "Scan barcode",
+            value: "{barcode}",
+            oninput: move |event| barcode.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    spawn(async move {
+                        lookup_barcode().await;
+                    });
+                }
+            },
+          }
+          label { class: "show-deleted",
+            input {
+              r#type: "checkbox",
+              checked: below_reorder_point(),
+              onchange: move |event| {
+                  below_reorder_point.set(event.checked());
+                  page.set(1);
+                  spawn(async move {
+                      load_products().await;
+                  });
+              },
+            }
+            "Needs reorder"
+          }
+          label { class: "show-deleted",
+            input {
+              r#type: "checkbox",
+              checked: show_deleted(),
+              onchange: move |event| {
+                  show_deleted.set(event.checked());
+                  page.set(1);
+                  spawn(async move {
+                      load_products().await;
+                  });
+              },
+            }
+            "Show deleted"
+          }
+          if can_manage {
+            button { onclick: move |_| select(None), "New Product" }
+            button { onclick: check_stock, "Check stock" }
+          }
+          button { onclick: move |_| show_reorder.set(true), "Reorder suggestions" }
+        }
+        if show_reorder() {
+          ReorderSuggestions {
+            on_select: move |id| {
+                spawn(async move {
+                    open_product(id).await;
+                });
+            },
+            on_close: move |_| show_reorder.set(false),
+          }
+        }
+        // 在庫数が入出庫の記録の合計と一致しない商品
+        if let Some(found) = discrepancies.read().as_ref() {
+          div { class: "form-group",
+            if found.is_empty() {
+              span { class: "success-message", "Stock matches the movement ledger." }
+            }
+            for item in found.iter() {
+              span { class: "status-badge status-cancelled",
+                "{item.name}: stock {item.stock}, ledger {item.ledger_stock}"
+              }
+            }
+            button { onclick: move |_| discrepancies.set(None), "Close" }
+          }
+        }
+        div { class: "clients-layout",
+          // 商品一覧
+          div { class: "data-list",
+            for product in products.read().items.iter() {
+              div {
+                class: match (
+                    selected.read().as_ref().is_some_and(|p| p.id == product.id),
+                    product.deleted_at.is_some(),
+                ) {
+                    (true, _) => "data-item selected",
+                    (false, true) => "data-item deleted",
+                    (false, false) => "data-item",
+                },
+                onclick: {
+                    let product = product.clone();
+                    move |_| select(Some(product.clone()))
+                },
+                strong { "{product.name}" }
+                span { "{product.sku}" }
+                if let Some(path) = category_path(&categories.read(), product.category_id) {
+                  span { "{path}" }
+                }
+                span { "{product.price}" }
+                span { "Stock: {product.stock} {product.unit.symbol()}" }
+                if product.reorder_point.is_some_and(|point| product.stock <= point) {
+                  span { class: "status-badge status-pending", "Reorder" }
+                }
+                if product.discontinued {
+                  span { class: "status-badge status-cancelled", "Discontinued" }
+                }
+                if product.deleted_at.is_some() {
+                  span { class: "status-badge status-cancelled", "Deleted" }
+                }
+              }
+            }
+            Pager {
+              page: products.read().page,
+              total_pages: products.read().total_pages,
+              total: products.read().total,
+              on_page_change: move |p| {
+                  page.set(p);
+                  spawn(async move {
+                      load_products().await;
+                  });
+              },
+            }
+          }
+          // 商品の詳細（選択していなければ新規作成フォーム）
+          div { class: "client-detail section",
+            h3 {
+              match &selected_product {
+                  Some(product) => product.name.clone(),
+                  None => "New product".to_string(),
+              }
+            }
+            if let Some(message) = message.read().as_ref() {
+              p { class: "success-message", "{message}" }
+            }
+            if let Some(message) = general_message(&error.read()) {
+              p { class: "error-message", "{message}" }
+            }
+            if let Some(product) = selected_product.as_ref().filter(|p| p.deleted_at.is_some()) {
+              div { class: "form-group",
+                span { class: "status-badge status-cancelled", "Deleted" }
+                if can_manage {
+                  button {
+                    onclick: {
+                        let product_id = product.id;
+                        move |_| {
+                            spawn(async move {
+                                delete_or_restore(product_id, true).await;
+                            });
+                        }
+                    },
+                    "Restore"
+                  }
+                }
+              }
+            } else if selected_product.is_some() || can_manage {
+              div { class: "client-form",
+                div { class: "field",
+                  label { "Name" }
+                  input {
+                    value: "{form.read().name}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().name = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "name") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "SKU" }
+                  input {
+                    value: "{form.read().sku}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().sku = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "sku") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Barcode (JAN/EAN)" }
+                  input {
+                    value: "{form.read().barcode}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().barcode = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "barcode") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Category" }
+                  select {
+                    disabled: !can_manage,
+                    onchange: move |event| form.write().category_id = event.value().parse().ok(),
+                    option { value: "", selected: form.read().category_id.is_none(), "No category" }
+                    for category in categories.read().iter() {
+                      option {
+                        value: "{category.id}",
+                        selected: form.read().category_id == Some(category.id),
+                        "{indented_name(category)}"
+                      }
+                    }
+                  }
+                  if let Some(message) = field_message(&error.read(), "category_id") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Unit" }
+                  select {
+                    disabled: !can_manage,
+                    onchange: move |event| {
+                        form.write().unit = event.value().parse().unwrap_or_default();
+                    },
+                    for unit in UnitOfMeasure::ALL.iter() {
+                      option {
+                        value: "{unit.as_str()}",
+                        selected: *unit == form.read().unit,
+                        "{unit.symbol()}"
+                      }
+                    }
+                  }
+                }
+                div { class: "field",
+                  label { "Price" }
+                  input {
+                    value: "{form.read().price}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().price = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "price") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  // 作成後の在庫数は入出庫の記録からだけ変わる
+                  label {
+                    if selected_product.is_none() {
+                      "Opening stock"
+                    } else if variants.read().variants.is_empty() {
+                      "Stock"
+                    } else {
+                      "Stock (total of variants)"
+                    }
+                  }
+                  input {
+                    value: "{form.read().stock}",
+                    disabled: !can_manage || selected_product.is_some(),
+                    oninput: move |event| form.write().stock = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "stock") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Reorder point" }
+                  input {
+                    value: "{form.read().reorder_point}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().reorder_point = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "reorder_point") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Description" }
+                  textarea {
+                    value: "{form.read().description}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().description = event.value(),
+                  }
+                }
+                // 取扱終了の商品は新しい注文に追加できない
+                if selected_product.is_some() {
+                  label { class: "show-deleted",
+                    input {
+                      r#type: "checkbox",
+                      disabled: !can_manage,
+                      checked: form.read().discontinued,
+                      onchange: move |event| form.write().discontinued = event.checked(),
+                    }
+                    "Discontinued"
+                  }
+                }
+                if can_manage {
+                  div { class: "form-group",
+                    button { onclick: save,
+                      if selected_product.is_some() { "Save Product" } else { "Add Product" }
+                    }
+                    if let Some(product) = selected_product.as_ref() {
+                      button {
+                        class: "cancel-button",
+                        onclick: {
+                            let product_id = product.id;
+                            move |_| {
+                                spawn(async move {
+                                    delete_or_restore(product_id, false).await;
+                                });
+                            }
+                        },
+                        "Delete product"
+                      }
+                    }
+                  }
+                }
+              }
+            }
+            if let Some(product) = selected_product.as_ref().filter(|p| p.deleted_at.is_none()) {
+              ProductVariantsGrid {
+                product: product.clone(),
+                variants,
+                warehouses: warehouses.read().clone(),
+                on_change: move |_| {
+                    spawn(async move {
+                        reload_selected().await;
+                    });
+                },
+              }
+              StockPanel {
+                product: product.clone(),
+                variants,
+                warehouses: warehouses.read().clone(),
+                on_change: move |_| {
+                    spawn(async move {
+                        reload_selected().await;
+                    });
+                },
+              }
+            }
+            if let Some(product) = selected_product.as_ref() {
+              AuditPanel {
+                entity: AuditEntity::Product,
+                entity_id: product.id,
+                revision: format!("{}{:?}", product.updated_at, product.deleted_at),
+              }
+            }
+            ProductCategories {
+              categories: categories.read().clone(),
+              on_change: move |_| {
+                  spawn(async move {
+                      load_categories().await;
+                  });
+              },
+            }
+            Warehouses {
+              warehouses: warehouses.read().clone(),
+              on_change: move |_| {
+                  spawn(async move {
+                      load_warehouses().await;
+                  });
+              },
+            }
+          }
+        }
+      }
+    }
+}
@@ -0,0 +1,131 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{ReorderQuery, MAX_REORDER_DAYS};
+
+use crate::api;
+
+#[derive(Props, PartialEq, Clone)]
+pub struct ReorderSuggestionsProps {
+    // 商品名をクリックしたとき（商品画面でその商品を開く）
+    pub on_select: EventHandler<Uuid>,
+    pub on_close: EventHandler<()>,
+}
+
+// 発注の提案（直近の販売の速さから求めた発注数）
+// 日数を変えるたびに読み直す（範囲外の日数は無視する）
+#[component]
+pub fn ReorderSuggestions(props: ReorderSuggestionsProps) -> Element {
+    let mut query = use_signal(ReorderQuery::default);
+    let suggestions = use_resource(move || async move {
+        api::get_reorder_suggestions(&query()).await
+    });
+
+    let parse_days = |value: String| {
+        value
+            .trim()
+            .parse::<i32>()
+            .ok()
+            .filter(|days| (1..=MAX_REORDER_DAYS).contains(days))
+    };
+
+    rsx! {
+      div { class: "reorder-suggestions",
+        h4 { "Reorder suggestions" }
+        div { class: "form-group",
+          label { class: "show-deleted",
+            "Sales in the last"
+            input {
+              r#type: "number",
+              min: "1",
+              max: "{MAX_REORDER_DAYS}",
+              value: "{query().sales_days}",
+              onchange: move |event| {
+                  if let Some(days) = parse_days(event.value()) {
+                      query.write().sales_days = days;
+                  }
+              },
+            }
+            "days"
+          }
+          label { class: "show-deleted",
+            "Cover"
+            input {
+              r#type: "number",
+              min: "1",
+              max: "{MAX_REORDER_DAYS}",
+              value: "{query().cover_days}",
+              onchange: move |event| {
+                  if let Some(days) = parse_days(event.value()) {
+                      query.write().cover_days = days;
+                  }
+              },
+            }
+            "days of sales"
+          }
+          button { onclick: move |_| props.on_close.call(()), "Close" }
+        }
+        match &*suggestions.read() {
+            None => rsx! {
+              p { class: "connection-retry", "Loading..." }
+            },
+            Some(Err(e)) => rsx! {
+              p { class: "error-message", "{e}" }
+            },
+            Some(Ok(items)) if items.is_empty() => rsx! {
+              span { class: "success-message", "Stock covers the reorder points and expected sales." }
+            },
+            Some(Ok(items)) => rsx! {
+              table { class: "variant-matrix",
+                thead {
+                  tr {
+                    th { "Product" }
+                    th { "Stock" }
+                    th { "Reorder point" }
+                    th { "Sold" }
+                    th { "Per day" }
+                    th { "Days left" }
+                    th { "Order" }
+                  }
+                }
+                tbody {
+                  for item in items.iter() {
+                    tr { key: "{item.product_id}",
+                      td {
+                        button {
+                          onclick: {
+                              let product_id = item.product_id;
+                              move |_| props.on_select.call(product_id)
+                          },
+                          "{item.name}"
+                        }
+                        " ({item.sku})"
+                      }
+                      td { "{item.stock}" }
+                      td {
+                        if let Some(point) = item.reorder_point {
+                          "{point}"
+                        } else {
+                          "-"
+                        }
+                      }
+                      td { "{item.sold}" }
+                      td { "{item.daily_sales}" }
+                      td {
+                        if let Some(days) = item.days_of_stock {
+                          "{days}"
+                        } else {
+                          "-"
+                        }
+                      }
+                      td {
+                        strong { "{item.suggested_quantity}" }
+                      }
+                    }
+                  }
+                }
+              }
+            },
+        }
+      }
+    }
+}
@@ -0,0 +1,79 @@
+use dioxus::prelude::*;
+use ws_models::{SearchHit, SearchKind};
+
+use crate::api;
+
+// 検索結果の種類の表示名
+pub fn kind_label(kind: SearchKind) -> &'static str {
+    match kind {
+        SearchKind::User => "User",
+        SearchKind::Client => "Client",
+        SearchKind::Product => "Product",
+        SearchKind::Order => "Order",
+    }
+}
+
+#[derive(Props, PartialEq, Clone)]
+pub struct SearchBoxProps {
+    pub on_select: EventHandler<SearchHit>,
+}
+
+// 画面上部の横断検索ボックス（名前・メールアドレス・会社名・商品名・注文番号）
+#[component]
+pub fn SearchBox(props: SearchBoxProps) -> Element {
+    let mut text = use_signal(String::new);
+    let mut hits = use_signal(Vec::<SearchHit>::new);
+
+    let run_search = move |query: String| async move {
+        let result = api::search(&query, None).await;
+        // 入力が変わっていたら古い結果は捨てる
+        if *text.read() != query {
+            return;
+        }
+        // 検索のエラーは結果なしとして扱う
+        hits.set(result.unwrap_or_default());
+    };
+
+    rsx! {
+      div { class: "search-box",
+        input {
+          r#type: "search",
+          placeholder: "Search users, products, order #...",
+          value: "{text}",
+          oninput: move |event| {
+              let query = event.value();
+              text.set(query.clone());
+              if query.trim().is_empty() {
+                  hits.set(Vec::new());
+              } else {
+                  spawn(async move {
+                      run_search(query).await;
+                  });
+              }
+          },
+        }
+        if !hits.read().is_empty() {
+          div { class: "search-results",
+            for hit in hits.read().iter() {
+              button {
+                class: "search-hit",
+                onclick: {
+                    let hit = hit.clone();
+                    move |_| {
+                        text.set(String::new());
+                        hits.set(Vec::new());
+                        props.on_select.call(hit.clone());
+                    }
+                },
+                span { class: "search-kind", "{kind_label(hit.kind)}" }
+                strong { "{hit.title}" }
+                if let Some(subtitle) = &hit.subtitle {
+                  small { "{subtitle}" }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+}
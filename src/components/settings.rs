@@ -0,0 +1,483 @@
+use dioxus::prelude::*;
+use std::str::FromStr;
+use ws_models::{
+    AppError, ConnectionConfig, ConnectionProfile, ConnectionSettings, FieldError, Permission,
+    PoolSettings, SslMode, ORDER_RETENTION_YEARS, PURGE_AFTER_DAYS,
+};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+// 編集中のプロファイル（数値も入力のまま文字列で持つ）
+#[derive(Clone, PartialEq, Default)]
+struct ProfileForm {
+    name: String,
+    host: String,
+    port: String,
+    database: String,
+    username: String,
+    // 空のままなら保存済みのパスワードを使い続ける
+    password: String,
+    ssl_mode: SslMode,
+    max_connections: String,
+    min_connections: String,
+    acquire_timeout_secs: String,
+    // 空欄は None（閉じない・サーバーの設定のまま）
+    idle_timeout_secs: String,
+    statement_timeout_ms: String,
+}
+
+impl ProfileForm {
+    fn from_profile(profile: &ConnectionProfile) -> Self {
+        let optional = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+        ProfileForm {
+            name: profile.name.clone(),
+            host: profile.connection.host.clone(),
+            port: profile.connection.port.to_string(),
+            database: profile.connection.database.clone(),
+            username: profile.connection.username.clone(),
+            password: String::new(),
+            ssl_mode: profile.connection.ssl_mode,
+            max_connections: profile.pool.max_connections.to_string(),
+            min_connections: profile.pool.min_connections.to_string(),
+            acquire_timeout_secs: profile.pool.acquire_timeout_secs.to_string(),
+            idle_timeout_secs: optional(profile.pool.idle_timeout_secs),
+            statement_timeout_ms: optional(profile.pool.statement_timeout_ms),
+        }
+    }
+
+    fn new_profile() -> Self {
+        ProfileForm::from_profile(&ConnectionProfile {
+            name: String::new(),
+            connection: ConnectionSettings::default(),
+            pool: PoolSettings::default(),
+        })
+    }
+
+    // 数値に変換できない項目はフィールドごとのエラーにする
+    fn to_profile(&self) -> Result<ConnectionProfile, AppError> {
+        let mut fields = Vec::new();
+        let port = parse_number(&self.port, "connection.port", &mut fields).unwrap_or_default();
+        let max_connections =
+            parse_number(&self.max_connections, "pool.max_connections", &mut fields).unwrap_or_default();
+        let min_connections =
+            parse_number(&self.min_connections, "pool.min_connections", &mut fields).unwrap_or_default();
+        let acquire_timeout_secs =
+            parse_number(&self.acquire_timeout_secs, "pool.acquire_timeout_secs", &mut fields)
+                .unwrap_or_default();
+        let idle_timeout_secs = parse_optional(&self.idle_timeout_secs, "pool.idle_timeout_secs", &mut fields);
+        let statement_timeout_ms =
+            parse_optional(&self.statement_timeout_ms, "pool.statement_timeout_ms", &mut fields);
+        AppError::check_fields(fields)?;
+
+        Ok(ConnectionProfile {
+            name: self.name.trim().to_string(),
+            connection: ConnectionSettings {
+                host: self.host.trim().to_string(),
+                port,
+                database: self.database.trim().to_string(),
+                username: self.username.trim().to_string(),
+                ssl_mode: self.ssl_mode,
+                password: None,
+            },
+            pool: PoolSettings {
+                max_connections,
+                min_connections,
+                acquire_timeout_secs,
+                idle_timeout_secs,
+                statement_timeout_ms,
+            },
+        })
+    }
+}
+
+fn parse_number<T: FromStr>(value: &str, field: &str, fields: &mut Vec<FieldError>) -> Option<T> {
+    let parsed = value.trim().parse().ok();
+    if parsed.is_none() {
+        fields.push(FieldError::new(field, "must be a number"));
+    }
+    parsed
+}
+
+fn parse_optional(value: &str, field: &str, fields: &mut Vec<FieldError>) -> Option<u64> {
+    if value.trim().is_empty() {
+        return None;
+    }
+    parse_number(value, field, fields)
+}
+
+// 接続プロファイルの一覧と編集（切り替え・追加・削除）
+#[component]
+pub fn Settings() -> Element {
+    let mut config = use_signal(|| Option::<ConnectionConfig>::None);
+    // 編集中のフォーム（None なら一覧だけを表示）
+    let mut form = use_signal(|| Option::<ProfileForm>::None);
+    // 既存のプロファイルを編集しているときはその名前（名前は変更できない）
+    let mut editing = use_signal(|| Option::<String>::None);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+
+    let load_config = move || async move {
+        match api::get_connection_config().await {
+            Ok(config_data) => config.set(Some(config_data)),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    use_future(move || async move {
+        load_config().await;
+    });
+
+    let save = move |_| async move {
+        let Some(form_data) = form.read().clone() else {
+            return;
+        };
+        let profile = match form_data.to_profile() {
+            Ok(profile) => profile,
+            Err(e) => {
+                error.set(Some(e));
+                return;
+            }
+        };
+        let password = (!form_data.password.is_empty()).then_some(form_data.password);
+        match api::save_connection_profile(&profile, password).await {
+            Ok(config_data) => {
+                config.set(Some(config_data));
+                message.set(Some(format!("Saved profile '{}'", profile.name)));
+                error.set(None);
+                form.set(None);
+                editing.set(None);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    // 切り替えるとすぐに接続し直す（接続できなければ App が接続画面を表示する）
+    let select = move |name: String| async move {
+        match api::select_connection_profile(&name).await {
+            Ok(_) => {
+                message.set(Some(format!("Switched to profile '{}'", name)));
+                error.set(None);
+                load_config().await;
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let delete = move |name: String| async move {
+        match api::delete_connection_profile(&name).await {
+            Ok(config_data) => {
+                config.set(Some(config_data));
+                message.set(Some(format!("Deleted profile '{}'", name)));
+                error.set(None);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let field_error = move |field: &str| field_message(&error.read(), field);
+
+    // 削除済みレコードの完全削除は purge_records の権限（admin）があるときだけ
+    let can_purge = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::PurgeRecords));
+    let purge = move |_| async move {
+        match api::purge_deleted().await {
+            Ok(report) => {
+                message.set(Some(format!(
+                    "Purged {} users, {} clients, {} products and {} orders",
+                    report.users, report.clients, report.products, report.orders
+                )));
+                error.set(None);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    rsx! {
+      div { class: "settings",
+        h2 { "Settings" }
+        if let Some(text) = general_message(&error.read()) {
+          p { class: "error-message", "{text}" }
+        }
+        if let Some(text) = message.read().as_ref() {
+          p { class: "success-message", "{text}" }
+        }
+
+        div { class: "section",
+          h3 { "Connection profiles" }
+          div { class: "data-list",
+            if let Some(config_data) = config.read().as_ref() {
+              for profile in config_data.profiles.iter() {
+                div { class: "data-item",
+                  strong { "{profile.name}" }
+                  span {
+                    "{profile.connection.username}@{profile.connection.host}:{profile.connection.port}/{profile.connection.database}"
+                  }
+                  if profile.name == config_data.active {
+                    span { class: "status-badge", "active" }
+                  } else {
+                    button {
+                      onclick: {
+                          let name = profile.name.clone();
+                          move |_| {
+                              spawn(select(name.clone()));
+                          }
+                      },
+                      "Use"
+                    }
+                  }
+                  button {
+                    onclick: {
+                        let profile = profile.clone();
+                        move |_| {
+                            form.set(Some(ProfileForm::from_profile(&profile)));
+                            editing.set(Some(profile.name.clone()));
+                            error.set(None);
+                        }
+                    },
+                    "Edit"
+                  }
+                  if profile.name != config_data.active {
+                    button {
+                      onclick: {
+                          let name = profile.name.clone();
+                          move |_| {
+                              spawn(delete(name.clone()));
+                          }
+                      },
+                      "Delete"
+                    }
+                  }
+                }
+              }
+            }
+          }
+          div { class: "form-group",
+            button {
+              onclick: move |_| {
+                  form.set(Some(ProfileForm::new_profile()));
+                  editing.set(None);
+                  error.set(None);
+              },
+              "New profile"
+            }
+          }
+        }
+
+        if let Some(form_data) = form.read().clone() {
+          div { class: "section",
+            h3 {
+              if let Some(name) = editing.read().as_ref() {
+                "Edit profile: {name}"
+              } else {
+                "New profile"
+              }
+            }
+            div { class: "settings-form",
+              div { class: "field",
+                label { "Name" }
+                input {
+                  value: "{form_data.name}",
+                  disabled: editing.read().is_some(),
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.name = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("name") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Host" }
+                input {
+                  value: "{form_data.host}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.host = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("connection.host") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Port" }
+                input {
+                  value: "{form_data.port}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.port = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("connection.port") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Database" }
+                input {
+                  value: "{form_data.database}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.database = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("connection.database") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "User" }
+                input {
+                  value: "{form_data.username}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.username = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("connection.username") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Password" }
+                input {
+                  r#type: "password",
+                  placeholder: if editing.read().is_some() { "(unchanged)" } else { "" },
+                  value: "{form_data.password}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.password = event.value();
+                      }
+                  },
+                }
+              }
+              div { class: "field",
+                label { "TLS mode" }
+                select {
+                  onchange: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.ssl_mode = SslMode::ALL
+                              .iter()
+                              .copied()
+                              .find(|mode| mode.as_str() == event.value())
+                              .unwrap_or_default();
+                      }
+                  },
+                  for mode in SslMode::ALL.iter() {
+                    option {
+                      value: "{mode.as_str()}",
+                      selected: *mode == form_data.ssl_mode,
+                      "{mode.as_str()}"
+                    }
+                  }
+                }
+              }
+              div { class: "field",
+                label { "Max connections" }
+                input {
+                  value: "{form_data.max_connections}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.max_connections = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("pool.max_connections") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Min connections" }
+                input {
+                  value: "{form_data.min_connections}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.min_connections = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("pool.min_connections") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Acquire timeout (s)" }
+                input {
+                  value: "{form_data.acquire_timeout_secs}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.acquire_timeout_secs = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("pool.acquire_timeout_secs") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Idle timeout (s)" }
+                input {
+                  placeholder: "never",
+                  value: "{form_data.idle_timeout_secs}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.idle_timeout_secs = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("pool.idle_timeout_secs") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+              div { class: "field",
+                label { "Statement timeout (ms)" }
+                input {
+                  placeholder: "server default",
+                  value: "{form_data.statement_timeout_ms}",
+                  oninput: move |event| {
+                      if let Some(f) = form.write().as_mut() {
+                          f.statement_timeout_ms = event.value();
+                      }
+                  },
+                }
+                if let Some(text) = field_error("pool.statement_timeout_ms") {
+                  span { class: "field-error", "{text}" }
+                }
+              }
+            }
+            div { class: "form-group",
+              button { onclick: save, "Save" }
+              button {
+                onclick: move |_| {
+                    form.set(None);
+                    editing.set(None);
+                    error.set(None);
+                },
+                "Cancel"
+              }
+            }
+          }
+        }
+        if can_purge {
+          div { class: "section",
+            h3 { "Deleted records" }
+            p { class: "connection-retry",
+              "Deleted users, clients, products and orders are kept and can be restored. Purging removes those deleted more than {PURGE_AFTER_DAYS} days ago; orders are kept for {ORDER_RETENTION_YEARS} years after they were placed, and users, clients and products referenced by orders are never purged."
+            }
+            div { class: "form-group",
+              button { onclick: purge, "Purge deleted records" }
+            }
+          }
+        }
+      }
+    }
+}
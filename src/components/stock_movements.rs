@@ -0,0 +1,455 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    AdjustStock, AppError, CreateStockTransfer, FieldError, Permission, Product, ProductVariants,
+    StockLevel, StockMovementFilter, StockMovementQuery, StockMovementType, StockTransferItem,
+    Warehouse,
+};
+
+use crate::api;
+use crate::components::pager::Pager;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct StockPanelProps {
+    pub product: Product,
+    // 商品画面で読み込んだバリエーション（バリエーションのある商品はバリエーションごとに記録する）
+    pub variants: Signal<ProductVariants>,
+    // 保管場所（既定の保管場所が先頭）
+    pub warehouses: Vec<Warehouse>,
+    // 入出庫を記録したとき（在庫数が変わるので商品とバリエーションを読み直す）
+    pub on_change: EventHandler<()>,
+}
+
+// 在庫調整フォームの値（数量は入力中の文字列のまま保持する）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AdjustForm {
+    movement_type: StockMovementType,
+    variant_id: Option<Uuid>,
+    // None なら既定の保管場所
+    warehouse_id: Option<Uuid>,
+    quantity: String,
+    reason: String,
+    reference: String,
+}
+
+// 保管場所の間の移動フォームの値（この商品の1明細だけの移動伝票を作る）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TransferForm {
+    from_warehouse_id: Option<Uuid>,
+    to_warehouse_id: Option<Uuid>,
+    variant_id: Option<Uuid>,
+    quantity: String,
+    reference: String,
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// 数量の入力欄を解析する（種類ごとの符号や理由の確認はバックエンドで行う）
+fn parse_form(product_id: Uuid, form: &AdjustForm) -> Result<AdjustStock, AppError> {
+    let mut errors = Vec::new();
+    let quantity = form.quantity.trim().parse::<i32>().unwrap_or_else(|_| {
+        errors.push(FieldError::new("quantity", "must be a whole number"));
+        0
+    });
+    AppError::check_fields(errors)?;
+    Ok(AdjustStock {
+        product_id,
+        variant_id: form.variant_id,
+        warehouse_id: form.warehouse_id,
+        movement_type: form.movement_type,
+        quantity,
+        reason: non_empty(&form.reason),
+        reference: non_empty(&form.reference),
+    })
+}
+
+// 移動フォームを解析する（在庫の確認はバックエンドで行う）
+fn parse_transfer(product_id: Uuid, form: &TransferForm) -> Result<CreateStockTransfer, AppError> {
+    let mut errors = Vec::new();
+    let from_warehouse_id = form.from_warehouse_id.unwrap_or_else(|| {
+        errors.push(FieldError::new("from_warehouse_id", "choose a warehouse"));
+        Uuid::nil()
+    });
+    let to_warehouse_id = form.to_warehouse_id.unwrap_or_else(|| {
+        errors.push(FieldError::new("to_warehouse_id", "choose a warehouse"));
+        Uuid::nil()
+    });
+    let quantity = form.quantity.trim().parse::<i32>().unwrap_or_else(|_| {
+        errors.push(FieldError::new("items.0.quantity", "must be a whole number"));
+        0
+    });
+    AppError::check_fields(errors)?;
+    Ok(CreateStockTransfer {
+        from_warehouse_id,
+        to_warehouse_id,
+        reference: non_empty(&form.reference),
+        note: None,
+        items: vec![StockTransferItem {
+            product_id,
+            variant_id: form.variant_id,
+            quantity,
+        }],
+    })
+}
+
+// 保管場所ごとの在庫数の合計（記録のない保管場所は 0）
+fn warehouse_total(levels: &[StockLevel], warehouse_id: Uuid) -> i32 {
+    levels
+        .iter()
+        .filter(|level| level.warehouse_id == warehouse_id)
+        .map(|level| level.quantity)
+        .sum()
+}
+
+// 商品の保管場所ごとの在庫数、在庫の入出庫の記録と、入庫・返品・調整・保管場所の間の移動のフォーム
+#[component]
+pub fn StockPanel(props: StockPanelProps) -> Element {
+    let product = props.product.clone();
+    let product_id = product.id;
+    let variants = props.variants;
+    let mut form = use_signal(AdjustForm::default);
+    let mut transfer_form = use_signal(TransferForm::default);
+    let mut page = use_signal(|| 1u32);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageProducts));
+    // 在庫数が変わると商品の updated_at も変わるため、商品が変わるたびに記録を読み直す
+    let movements = use_resource(use_reactive!(|product| async move {
+        let query = StockMovementQuery {
+            page: Some(page()),
+            filter: StockMovementFilter {
+                product_id: Some(product.id),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        api::get_stock_movements(&query).await
+    }));
+    let levels = use_resource(use_reactive!(|product| async move {
+        api::get_stock_levels(product.id).await
+    }));
+
+    let save = move |_| async move {
+        let parsed = parse_form(product_id, &form.read());
+        let data = match parsed {
+            Ok(data) => data,
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+                return;
+            }
+        };
+        match api::adjust_stock(&data).await {
+            Ok(movement) => {
+                error.set(None);
+                message.set(Some(format!(
+                    "Recorded {} of {:+}",
+                    movement.movement_type.label().to_lowercase(),
+                    movement.quantity
+                )));
+                form.set(AdjustForm {
+                    movement_type: movement.movement_type,
+                    variant_id: movement.variant_id,
+                    warehouse_id: Some(movement.warehouse_id),
+                    ..Default::default()
+                });
+                page.set(1);
+                props.on_change.call(());
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    let transfer = move |_| async move {
+        let parsed = parse_transfer(product_id, &transfer_form.read());
+        let data = match parsed {
+            Ok(data) => data,
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+                return;
+            }
+        };
+        match api::create_stock_transfer(&data).await {
+            Ok(transfer) => {
+                error.set(None);
+                let quantity: i32 = transfer.items.iter().map(|item| item.quantity).sum();
+                message.set(Some(format!("Transferred {}", quantity)));
+                transfer_form.set(TransferForm {
+                    from_warehouse_id: Some(transfer.from_warehouse_id),
+                    to_warehouse_id: Some(transfer.to_warehouse_id),
+                    variant_id: transfer.items.first().and_then(|item| item.variant_id),
+                    ..Default::default()
+                });
+                page.set(1);
+                props.on_change.call(());
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    let data = variants.read().clone();
+    let variant_label = |id: Option<Uuid>| {
+        id.and_then(|id| data.variants.iter().find(|v| v.id == id))
+            .map(|v| v.label())
+    };
+    let warehouses = props.warehouses.clone();
+    let warehouse_name = |id: Uuid| {
+        warehouses
+            .iter()
+            .find(|w| w.id == id)
+            .map(|w| w.name.clone())
+            .unwrap_or_default()
+    };
+    // 保管場所の選択肢で選択中か（未選択なら既定の保管場所）
+    let is_selected = |selected: Option<Uuid>, warehouse: &Warehouse| match selected {
+        Some(id) => id == warehouse.id,
+        None => warehouse.is_default,
+    };
+
+    rsx! {
+      div { class: "stock-panel",
+        h4 { "Stock" }
+        if let Some(message) = message.read().as_ref() {
+          p { class: "success-message", "{message}" }
+        }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        // 保管場所ごとの在庫数（バリエーションのある商品はバリエーションごとの内訳も表示する）
+        match &*levels.read() {
+            None => rsx! {},
+            Some(Err(e)) => rsx! {
+              p { class: "error-message", "{e}" }
+            },
+            Some(Ok(levels)) => rsx! {
+              div { class: "data-list",
+                for warehouse in warehouses.iter() {
+                  div { class: "data-item", key: "{warehouse.id}",
+                    strong { "{warehouse.name}" }
+                    span { "Stock: {warehouse_total(levels, warehouse.id)} {product.unit.symbol()}" }
+                    for level in levels.iter().filter(|l| l.warehouse_id == warehouse.id && l.quantity > 0) {
+                      if let Some(label) = variant_label(level.variant_id) {
+                        span { "{label}: {level.quantity}" }
+                      }
+                    }
+                  }
+                }
+              }
+            },
+        }
+        // 入庫・返品は正の数、調整は増やすなら正・減らすなら負の数
+        if can_manage {
+          div { class: "client-form",
+            div { class: "form-group",
+              select {
+                onchange: move |event| {
+                    form.write().movement_type = event.value().parse().unwrap_or_default();
+                },
+                for kind in StockMovementType::ALL.iter().filter(|kind| kind.is_manual()) {
+                  option {
+                    value: "{kind.as_str()}",
+                    selected: *kind == form.read().movement_type,
+                    "{kind.label()}"
+                  }
+                }
+              }
+              select {
+                onchange: move |event| form.write().warehouse_id = event.value().parse().ok(),
+                for warehouse in warehouses.iter() {
+                  option {
+                    value: "{warehouse.id}",
+                    selected: is_selected(form.read().warehouse_id, warehouse),
+                    "{warehouse.name}"
+                  }
+                }
+              }
+              if !data.variants.is_empty() {
+                select {
+                  onchange: move |event| form.write().variant_id = event.value().parse().ok(),
+                  option { value: "", selected: form.read().variant_id.is_none(), "Choose a variant" }
+                  for variant in data.variants.iter() {
+                    option {
+                      value: "{variant.id}",
+                      selected: form.read().variant_id == Some(variant.id),
+                      "{variant.label()} ({variant.stock})"
+                    }
+                  }
+                }
+              }
+              input {
+                placeholder: "Quantity (+/-)",
+                value: "{form.read().quantity}",
+                oninput: move |event| form.write().quantity = event.value(),
+              }
+            }
+            for field in ["movement_type", "warehouse_id", "variant_id", "quantity"] {
+              if let Some(message) = field_message(&error.read(), field) {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              input {
+                placeholder: "Reason (required for adjustments)",
+                value: "{form.read().reason}",
+                oninput: move |event| form.write().reason = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "reason") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              input {
+                placeholder: "Reference (delivery note, count sheet...)",
+                value: "{form.read().reference}",
+                oninput: move |event| form.write().reference = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "reference") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "form-group",
+              button { onclick: save, "Record" }
+            }
+          }
+        }
+        // 保管場所の間の移動（移動元の出庫と移動先の入庫を同時に記録する）
+        if can_manage && warehouses.len() > 1 {
+          div { class: "client-form",
+            div { class: "form-group",
+              select {
+                onchange: move |event| {
+                    transfer_form.write().from_warehouse_id = event.value().parse().ok();
+                },
+                option {
+                  value: "",
+                  selected: transfer_form.read().from_warehouse_id.is_none(),
+                  "From"
+                }
+                for warehouse in warehouses.iter() {
+                  option {
+                    value: "{warehouse.id}",
+                    selected: transfer_form.read().from_warehouse_id == Some(warehouse.id),
+                    "{warehouse.name}"
+                  }
+                }
+              }
+              select {
+                onchange: move |event| {
+                    transfer_form.write().to_warehouse_id = event.value().parse().ok();
+                },
+                option {
+                  value: "",
+                  selected: transfer_form.read().to_warehouse_id.is_none(),
+                  "To"
+                }
+                for warehouse in warehouses.iter() {
+                  option {
+                    value: "{warehouse.id}",
+                    selected: transfer_form.read().to_warehouse_id == Some(warehouse.id),
+                    "{warehouse.name}"
+                  }
+                }
+              }
+              if !data.variants.is_empty() {
+                select {
+                  onchange: move |event| transfer_form.write().variant_id = event.value().parse().ok(),
+                  option {
+                    value: "",
+                    selected: transfer_form.read().variant_id.is_none(),
+                    "Choose a variant"
+                  }
+                  for variant in data.variants.iter() {
+                    option {
+                      value: "{variant.id}",
+                      selected: transfer_form.read().variant_id == Some(variant.id),
+                      "{variant.label()}"
+                    }
+                  }
+                }
+              }
+              input {
+                placeholder: "Quantity",
+                value: "{transfer_form.read().quantity}",
+                oninput: move |event| transfer_form.write().quantity = event.value(),
+              }
+              input {
+                placeholder: "Reference",
+                value: "{transfer_form.read().reference}",
+                oninput: move |event| transfer_form.write().reference = event.value(),
+              }
+              button { onclick: transfer, "Transfer" }
+            }
+            for field in [
+                "from_warehouse_id",
+                "to_warehouse_id",
+                "items.0.variant_id",
+                "items.0.quantity",
+            ] {
+              if let Some(message) = field_message(&error.read(), field) {
+                span { class: "field-error", "{message}" }
+              }
+            }
+          }
+        }
+        match &*movements.read() {
+            None => rsx! {
+              p { class: "connection-retry", "Loading..." }
+            },
+            Some(Err(e)) => rsx! {
+              p { class: "error-message", "{e}" }
+            },
+            Some(Ok(movements)) if movements.items.is_empty() => rsx! {
+              p { class: "connection-retry", "No stock movements recorded." }
+            },
+            Some(Ok(movements)) => rsx! {
+              ol { class: "timeline",
+                for movement in movements.items.iter() {
+                  li { class: "timeline-entry", key: "{movement.id}",
+                    strong { "{movement.movement_type.label()} {movement.quantity:+}" }
+                    small {
+                      "{movement.created_at}"
+                      if let Some(name) = &movement.actor_name {
+                        " by {name}"
+                      }
+                    }
+                    span { "{warehouse_name(movement.warehouse_id)}" }
+                    if let Some(label) = variant_label(movement.variant_id) {
+                      span { "{label}" }
+                    }
+                    if let Some(reason) = &movement.reason {
+                      span { "{reason}" }
+                    }
+                    if let Some(reference) = &movement.reference {
+                      span { "Ref: {reference}" }
+                    }
+                    if let Some(order_id) = movement.order_id {
+                      span { "Order {order_id}" }
+                    }
+                  }
+                }
+              }
+              Pager {
+                page: movements.page,
+                total_pages: movements.total_pages,
+                total: movements.total,
+                on_page_change: move |p| page.set(p),
+              }
+            },
+        }
+      }
+    }
+}
@@ -0,0 +1,351 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{
+    AppError, AuditEntity, CreateUser, Page, Permission, Role, SearchHit, SearchKind, UpdateUser,
+    User, UserFilter,
+};
+
+use crate::api;
+use crate::components::audit_panel::AuditPanel;
+use crate::components::pager::{focused_ids, page_query, Pager};
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct UsersProps {
+    // 検索ボックスで選ばれたレコード
+    pub focus: Signal<Option<SearchHit>>,
+}
+
+// 編集フォームの初期値（パスワードは空のまま。空なら設定・変更しない）
+fn form_from(user: &User) -> CreateUser {
+    CreateUser {
+        name: user.name.clone(),
+        email: user.email.clone(),
+        password: None,
+        role: Some(user.role),
+    }
+}
+
+// ユーザーの一覧と詳細（編集フォーム・変更履歴）
+#[component]
+pub fn Users(props: UsersProps) -> Element {
+    let mut focus = props.focus;
+    let mut users = use_signal(Page::<User>::default);
+    let mut page = use_signal(|| 1u32);
+    let mut search = use_signal(String::new);
+    // 削除済みのユーザーも一覧に表示する
+    let mut show_deleted = use_signal(|| false);
+    // 詳細を表示中のユーザー（None で新規作成フォーム）
+    let mut selected_id = use_signal(|| Option::<Uuid>::None);
+    let mut form = use_signal(CreateUser::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let mut message = use_signal(|| Option::<String>::None);
+    let (can_manage, can_manage_roles) = api::SESSION
+        .read()
+        .as_ref()
+        .map(|session| {
+            (
+                session.can(Permission::ManageUsers),
+                session.can(Permission::ManageRoles),
+            )
+        })
+        .unwrap_or_default();
+
+    let load_users = move || async move {
+        let text = search.read().trim().to_string();
+        let filter = UserFilter {
+            ids: focused_ids(&focus.read(), SearchKind::User),
+            search: (!text.is_empty()).then_some(text),
+            include_deleted: show_deleted(),
+            ..Default::default()
+        };
+        match api::get_users(&page_query(page(), filter)).await {
+            Ok(users_data) => users.set(users_data),
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let mut select = move |user: Option<User>| {
+        error.set(None);
+        message.set(None);
+        selected_id.set(user.as_ref().map(|u| u.id));
+        form.set(user.as_ref().map(form_from).unwrap_or_default());
+    };
+
+    // 新しいユーザーを作成（選択中なら更新）
+    // 入力値の検証はバックエンドで行い、エラーは入力欄ごとに表示する
+    // ロールは manage_roles の権限があるときだけ送る
+    let save = move |_| async move {
+        let data = form.read().clone();
+        let password = data.password.filter(|p| !p.is_empty());
+        let role = if can_manage_roles { data.role } else { None };
+        let result = match selected_id() {
+            Some(id) => {
+                let user_data = UpdateUser {
+                    name: Some(data.name),
+                    email: Some(data.email),
+                    password,
+                    role,
+                };
+                api::update_user(id, &user_data).await
+            }
+            None => {
+                let user_data = CreateUser {
+                    password,
+                    role,
+                    ..data
+                };
+                api::create_user(&user_data).await
+            }
+        };
+        match result {
+            Ok(user) => {
+                error.set(None);
+                message.set(Some(format!("Saved {}", user.name)));
+                selected_id.set(Some(user.id));
+                form.set(form_from(&user));
+                load_users().await;
+            }
+            Err(e) => {
+                message.set(None);
+                error.set(Some(e));
+            }
+        }
+    };
+
+    // 削除は論理削除（Show deleted で表示して Restore で元に戻せる）
+    let delete_or_restore = move |user_id: Uuid, restore: bool| async move {
+        let result = if restore {
+            api::restore_user(user_id).await.map(|_| ())
+        } else {
+            api::delete_user(user_id).await
+        };
+        error.set(result.err());
+        message.set(None);
+        if !restore {
+            selected_id.set(None);
+            form.set(CreateUser::default());
+        }
+        load_users().await;
+    };
+
+    // 初回表示時と、検索結果から移動してきたときに読み込む
+    use_effect(move || {
+        let focused = focus
+            .read()
+            .as_ref()
+            .filter(|hit| hit.kind == SearchKind::User)
+            .map(|hit| hit.id);
+        page.set(1);
+        spawn(async move {
+            load_users().await;
+            if let Some(user_id) = focused {
+                let user = users.read().items.iter().find(|u| u.id == user_id).cloned();
+                select(user);
+            }
+        });
+    });
+
+    let selected = selected_id
+        .read()
+        .as_ref()
+        .and_then(|id| users.read().items.iter().find(|u| &u.id == id).cloned());
+
+    rsx! {
+      div { class: "users",
+        h2 { "Users" }
+        if let Some(hit) = focus.read().as_ref().filter(|hit| hit.kind == SearchKind::User) {
+          div { class: "search-focus",
+            span { "Search result: User {hit.title}" }
+            button { onclick: move |_| focus.set(None), "Show all" }
+          }
+        }
+        div { class: "form-group",
+          input {
+            placeholder: "Search name or email",
+            value: "{search}",
+            oninput: move |event| search.set(event.value()),
+            onkeydown: move |event| {
+                if event.key() == Key::Enter {
+                    page.set(1);
+                    spawn(async move {
+                        load_users().await;
+                    });
+                }
+            },
+          }
+          label { class: "show-deleted",
+            input {
+              r#type: "checkbox",
+              checked: show_deleted(),
+              onchange: move |event| {
+                  show_deleted.set(event.checked());
+                  page.set(1);
+                  spawn(async move {
+                      load_users().await;
+                  });
+              },
+            }
+            "Show deleted"
+          }
+          if can_manage {
+            button { onclick: move |_| select(None), "New User" }
+          }
+        }
+        div { class: "users-layout",
+          // ユーザー一覧
+          div { class: "data-list",
+            for user in users.read().items.iter() {
+              div {
+                class: match (selected_id.read().as_ref() == Some(&user.id), user.deleted_at.is_some()) {
+                    (true, _) => "data-item selected",
+                    (false, true) => "data-item deleted",
+                    (false, false) => "data-item",
+                },
+                onclick: {
+                    let user = user.clone();
+                    move |_| select(Some(user.clone()))
+                },
+                strong { "{user.name}" }
+                span { "{user.email}" }
+                span { class: "role-badge", "{user.role.label()}" }
+                if user.deleted_at.is_some() {
+                  span { class: "status-badge status-cancelled", "Deleted" }
+                }
+              }
+            }
+            Pager {
+              page: users.read().page,
+              total_pages: users.read().total_pages,
+              total: users.read().total,
+              on_page_change: move |p| {
+                  page.set(p);
+                  spawn(async move {
+                      load_users().await;
+                  });
+              },
+            }
+          }
+          // ユーザーの詳細（選択していなければ新規作成フォーム）
+          div { class: "user-detail section",
+            h3 {
+              match &selected {
+                  Some(user) => user.name.clone(),
+                  None => "New user".to_string(),
+              }
+            }
+            if let Some(message) = message.read().as_ref() {
+              p { class: "success-message", "{message}" }
+            }
+            if let Some(message) = general_message(&error.read()) {
+              p { class: "error-message", "{message}" }
+            }
+            if let Some(user) = selected.as_ref().filter(|u| u.deleted_at.is_some()) {
+              div { class: "form-group",
+                span { class: "status-badge status-cancelled", "Deleted" }
+                if can_manage {
+                  button {
+                    onclick: {
+                        let user_id = user.id;
+                        move |_| {
+                            spawn(async move {
+                                delete_or_restore(user_id, true).await;
+                            });
+                        }
+                    },
+                    "Restore"
+                  }
+                }
+              }
+            } else if selected.is_some() || can_manage {
+              div { class: "user-form",
+                div { class: "field",
+                  label { "Name" }
+                  input {
+                    value: "{form.read().name}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().name = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "name") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                div { class: "field",
+                  label { "Email" }
+                  input {
+                    value: "{form.read().email}",
+                    disabled: !can_manage,
+                    oninput: move |event| form.write().email = event.value(),
+                  }
+                  if let Some(message) = field_message(&error.read(), "email") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                if can_manage {
+                  div { class: "field",
+                    label { "Password" }
+                    input {
+                      r#type: "password",
+                      placeholder: if selected.is_some() { "Password (unchanged)" } else { "Password" },
+                      value: "{form.read().password.clone().unwrap_or_default()}",
+                      oninput: move |event| form.write().password = Some(event.value()),
+                    }
+                    if let Some(message) = field_message(&error.read(), "password") {
+                      span { class: "field-error", "{message}" }
+                    }
+                  }
+                }
+                div { class: "field",
+                  label { "Role" }
+                  select {
+                    disabled: !can_manage || !can_manage_roles,
+                    onchange: move |event| {
+                        form.write().role = Some(event.value().parse().unwrap_or_default());
+                    },
+                    for role in Role::ALL.iter() {
+                      option {
+                        value: "{role.as_str()}",
+                        selected: *role == form.read().role.unwrap_or_default(),
+                        "{role.label()}"
+                      }
+                    }
+                  }
+                  if let Some(message) = field_message(&error.read(), "role") {
+                    span { class: "field-error", "{message}" }
+                  }
+                }
+                if can_manage {
+                  div { class: "form-group",
+                    button { onclick: save,
+                      if selected.is_some() { "Save User" } else { "Add User" }
+                    }
+                    if let Some(user) = selected.as_ref() {
+                      button {
+                        class: "cancel-button",
+                        onclick: {
+                            let user_id = user.id;
+                            move |_| {
+                                spawn(async move {
+                                    delete_or_restore(user_id, false).await;
+                                });
+                            }
+                        },
+                        "Delete user"
+                      }
+                    }
+                  }
+                }
+              }
+            }
+            if let Some(user) = selected.as_ref() {
+              AuditPanel {
+                entity: AuditEntity::User,
+                entity_id: user.id,
+                revision: format!("{}{:?}", user.updated_at, user.deleted_at),
+              }
+            }
+          }
+        }
+      }
+    }
+}
@@ -0,0 +1,174 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+use ws_models::{AppError, CreateWarehouse, Permission, UpdateWarehouse, Warehouse};
+
+use crate::api;
+use crate::error::{field_message, general_message};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct WarehousesProps {
+    // 保管場所（商品画面で読み込んだもの。既定の保管場所が先頭）
+    pub warehouses: Vec<Warehouse>,
+    // 保管場所を追加・変更・削除したとき（保管場所を読み直す）
+    pub on_change: EventHandler<()>,
+}
+
+// 編集フォームの初期値
+fn form_from(warehouse: &Warehouse) -> CreateWarehouse {
+    CreateWarehouse {
+        name: warehouse.name.clone(),
+        address: warehouse.address.clone(),
+        is_default: warehouse.is_default,
+    }
+}
+
+// 保管場所の名前（保管場所の一覧未読み込みの場合は ID の先頭を表示）
+pub fn warehouse_name(warehouses: &[Warehouse], warehouse_id: Uuid) -> String {
+    warehouses
+        .iter()
+        .find(|w| w.id == warehouse_id)
+        .map(|w| w.name.clone())
+        .unwrap_or_else(|| warehouse_id.to_string()[..8].to_string())
+}
+
+// 保管場所の一覧と追加・編集フォーム
+// 既定の保管場所と、入出庫の記録や注文のある保管場所は削除できない（バックエンドが Conflict を返す）
+#[component]
+pub fn Warehouses(props: WarehousesProps) -> Element {
+    // 編集中の保管場所（None なら新規作成）
+    let mut editing = use_signal(|| Option::<Uuid>::None);
+    let mut form = use_signal(CreateWarehouse::default);
+    let mut error = use_signal(|| Option::<AppError>::None);
+    let can_manage = api::SESSION
+        .read()
+        .as_ref()
+        .is_some_and(|session| session.can(Permission::ManageProducts));
+
+    let save = move |_| async move {
+        let data = form.read().clone();
+        let result = match editing() {
+            Some(id) => {
+                // 空の住所は削除する（空白の除去はバックエンドで行う）
+                let update = UpdateWarehouse {
+                    name: Some(data.name),
+                    address: Some(data.address),
+                    is_default: Some(data.is_default),
+                };
+                api::update_warehouse(id, &update).await
+            }
+            None => api::create_warehouse(&data).await,
+        };
+        if let Err(e) = result {
+            error.set(Some(e));
+            return;
+        }
+        error.set(None);
+        editing.set(None);
+        form.set(CreateWarehouse::default());
+        props.on_change.call(());
+    };
+
+    let delete = move |id: Uuid| async move {
+        error.set(api::delete_warehouse(id).await.err());
+        if editing() == Some(id) {
+            editing.set(None);
+            form.set(CreateWarehouse::default());
+        }
+        props.on_change.call(());
+    };
+
+    rsx! {
+      div { class: "warehouses",
+        h4 { "Warehouses" }
+        if let Some(message) = general_message(&error.read()) {
+          p { class: "error-message", "{message}" }
+        }
+        div { class: "data-list",
+          for warehouse in props.warehouses.iter() {
+            div { class: if editing() == Some(warehouse.id) { "data-item selected" } else { "data-item" },
+              strong { "{warehouse.name}" }
+              if let Some(address) = &warehouse.address {
+                span { "{address}" }
+              }
+              if warehouse.is_default {
+                span { class: "role-badge", "Default" }
+              }
+              if can_manage {
+                div { class: "item-actions",
+                  button {
+                    onclick: {
+                        let warehouse = warehouse.clone();
+                        move |_| {
+                            editing.set(Some(warehouse.id));
+                            form.set(form_from(&warehouse));
+                            error.set(None);
+                        }
+                    },
+                    "Edit"
+                  }
+                  if !warehouse.is_default {
+                    button {
+                      onclick: {
+                          let id = warehouse.id;
+                          move |_| {
+                              spawn(async move {
+                                  delete(id).await;
+                              });
+                          }
+                      },
+                      "Delete"
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+        if can_manage {
+          div { class: "form-group",
+            div { class: "field",
+              input {
+                placeholder: "Warehouse name",
+                value: "{form.read().name}",
+                oninput: move |event| form.write().name = event.value(),
+              }
+              if let Some(message) = field_message(&error.read(), "name") {
+                span { class: "field-error", "{message}" }
+              }
+            }
+            div { class: "field",
+              input {
+                placeholder: "Address",
+                value: "{form.read().address.clone().unwrap_or_default()}",
+                oninput: move |event| form.write().address = Some(event.value()),
+              }
+            }
+            label { class: "show-deleted",
+              input {
+                r#type: "checkbox",
+                checked: form.read().is_default,
+                onchange: move |event| form.write().is_default = event.checked(),
+              }
+              "Default"
+            }
+            if let Some(message) = field_message(&error.read(), "is_default") {
+              span { class: "field-error", "{message}" }
+            }
+            button { onclick: save,
+              if editing().is_some() { "Save Warehouse" } else { "Add Warehouse" }
+            }
+            if editing().is_some() {
+              button {
+                onclick: move |_| {
+                    editing.set(None);
+                    form.set(CreateWarehouse::default());
+                    error.set(None);
+                },
+                "Cancel"
+              }
+            }
+          }
+        }
+      }
+    }
+}
@@ -0,0 +1,22 @@
+use wasm_bindgen::JsValue;
+use ws_models::AppError;
+
+// invoke が reject した値を復元する（AppError の形でなければ Internal として扱う）
+pub fn from_js(value: JsValue) -> AppError {
+    serde_wasm_bindgen::from_value(value.clone()).unwrap_or_else(|_| AppError::Internal {
+        message: value.as_string().unwrap_or_else(|| format!("{:?}", value)),
+    })
+}
+
+// 入力欄の下に表示するエラー
+pub fn field_message(error: &Option<AppError>, field: &str) -> Option<String> {
+    error.as_ref().and_then(|e| e.field_message(field))
+}
+
+// 入力欄に対応しないエラー（接続エラーや NotFound など）
+pub fn general_message(error: &Option<AppError>) -> Option<String> {
+    error
+        .as_ref()
+        .filter(|e| e.fields().is_empty())
+        .map(|e| e.to_string())
+}